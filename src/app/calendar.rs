@@ -0,0 +1,139 @@
+use anyhow::Result;
+use chrono::{Datelike, Duration, Local, Months, NaiveDate};
+
+use crate::app::undo::{Change, Command};
+use crate::app::App;
+use crate::models::Task;
+use crate::utils;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalendarView {
+    Month,
+    Week,
+}
+
+/// Local calendar day a task is due on.
+pub fn due_day(task: &Task) -> Option<NaiveDate> {
+    task.due_date.map(|due| due.with_timezone(&Local).date_naive())
+}
+
+/// Monday of the week containing `date`.
+pub fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+impl App {
+    /// Tasks due on `date`, highest priority first.
+    pub fn tasks_due_on(&self, date: NaiveDate) -> Vec<&Task> {
+        let mut tasks: Vec<&Task> = self
+            .filtered_tasks()
+            .into_iter()
+            .filter(|task| due_day(task) == Some(date))
+            .collect();
+        tasks.sort_by_key(|task| std::cmp::Reverse(task.priority));
+        tasks
+    }
+
+    /// The task under the calendar cursor, if the cursor day has any.
+    pub fn calendar_task(&self) -> Option<&Task> {
+        self.tasks_due_on(self.calendar_cursor)
+            .get(self.calendar_task_index)
+            .copied()
+    }
+
+    pub fn toggle_calendar_view(&mut self) {
+        self.calendar_view = match self.calendar_view {
+            CalendarView::Month => CalendarView::Week,
+            CalendarView::Week => CalendarView::Month,
+        };
+    }
+
+    pub fn move_calendar_cursor(&mut self, days: i64) {
+        self.set_calendar_cursor(self.calendar_cursor + Duration::days(days));
+    }
+
+    pub fn move_calendar_month(&mut self, forward: bool) {
+        let months = Months::new(1);
+        let moved = if forward {
+            self.calendar_cursor.checked_add_months(months)
+        } else {
+            self.calendar_cursor.checked_sub_months(months)
+        };
+        if let Some(date) = moved {
+            self.set_calendar_cursor(date);
+        }
+    }
+
+    pub fn calendar_today(&mut self) {
+        self.set_calendar_cursor(Local::now().date_naive());
+    }
+
+    fn set_calendar_cursor(&mut self, date: NaiveDate) {
+        self.calendar_cursor = date;
+        self.calendar_task_index = 0;
+        self.sync_calendar_selection();
+    }
+
+    /// Cycles through the tasks due on the cursor day.
+    pub fn cycle_calendar_task(&mut self, forward: bool) {
+        let count = self.tasks_due_on(self.calendar_cursor).len();
+        if count == 0 {
+            return;
+        }
+        self.calendar_task_index = if forward {
+            (self.calendar_task_index + 1) % count
+        } else {
+            (self.calendar_task_index + count - 1) % count
+        };
+        self.sync_calendar_selection();
+    }
+
+    /// Opens the details of the task under the cursor.
+    pub fn open_calendar_task(&mut self) {
+        if self.calendar_task().is_some() {
+            self.sync_calendar_selection();
+            self.show_task_details = true;
+        }
+    }
+
+    /// Shifts the due date of the task under the cursor and follows it with the cursor.
+    pub fn reschedule_calendar_task(&mut self, days: i64) -> Result<()> {
+        let Some(before) = self.calendar_task().cloned() else {
+            return Ok(());
+        };
+        let Some(due) = before.due_date else {
+            return Ok(());
+        };
+
+        let mut after = before.clone();
+        after.due_date = Some(due + Duration::days(days));
+        after.record_changes(&before);
+        let new_day = due_day(&after).unwrap_or(self.calendar_cursor);
+        let id = after.id.clone();
+
+        let description = format!("Reschedule \"{}\" to {}", after.title, utils::format_day(new_day));
+        self.execute(Command::new(description.clone(), vec![Change::Updated { before: Box::new(before), after: Box::new(after) }]))?;
+        self.status_message = Some(description);
+
+        self.calendar_cursor = new_day;
+        self.calendar_task_index = self
+            .tasks_due_on(new_day)
+            .iter()
+            .position(|task| task.id == id)
+            .unwrap_or(0);
+        self.sync_calendar_selection();
+        Ok(())
+    }
+
+    /// Keeps `selected_task_index` on the calendar task so edit and detail keys act on it.
+    fn sync_calendar_selection(&mut self) {
+        if let Some(id) = self.calendar_task().map(|task| task.id.clone()) {
+            self.select_task_by_id(&id);
+        }
+    }
+
+    /// First day shown in the month grid (the Monday on or before the 1st).
+    pub fn calendar_month_start(&self) -> NaiveDate {
+        week_start(self.calendar_cursor.with_day(1).unwrap_or(self.calendar_cursor))
+    }
+}
@@ -0,0 +1,256 @@
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
+
+use crate::models::workflow::Workflow;
+use crate::models::recurrence::Recurrence;
+use crate::models::{dependencies, subtasks, Task, TaskPriority, TaskStatus};
+use crate::utils;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumIter)]
+pub enum FormField {
+    Title,
+    Description,
+    Status,
+    Priority,
+    Points,
+    Estimate,
+    #[strum(serialize = "Due date")]
+    DueDate,
+    Tags,
+    Parent,
+    #[strum(serialize = "Blocked by")]
+    BlockedBy,
+    Repeat,
+}
+
+impl FormField {
+    /// Pickers cycle through fixed options instead of taking typed text.
+    pub fn is_picker(self) -> bool {
+        matches!(self, FormField::Status | FormField::Priority)
+    }
+}
+
+/// Working copy of a task's fields while it is being edited.
+pub struct EditForm {
+    pub task_id: String,
+    pub title: String,
+    pub description: String,
+    pub status: TaskStatus,
+    /// Statuses the workflow lets this task move to, in column order.
+    pub status_options: Vec<TaskStatus>,
+    pub priority: TaskPriority,
+    pub points: String,
+    /// Effort as typed, e.g. "1h30m"; empty for none.
+    pub estimate: String,
+    pub due_date: String,
+    pub tags: String,
+    /// Id prefix of the parent task; empty for a top-level task.
+    pub parent: String,
+    /// Id prefixes of prerequisite tasks, comma separated.
+    pub blocked_by: String,
+    /// Recurrence rule as typed, e.g. "weekly mon,thu"; empty for none.
+    pub repeat: String,
+    pub focused: FormField,
+    pub error: Option<String>,
+    /// Description and due date as the form opened with them; saving them
+    /// unchanged keeps the task's own values, including the due time.
+    initial_description: String,
+    initial_due_date: String,
+}
+
+impl EditForm {
+    pub fn from_task(task: &Task, workflow: &Workflow) -> Self {
+        let due_date = task
+            .due_date
+            .map(|due| due.with_timezone(&chrono::Local).format("%Y-%m-%d").to_string())
+            .unwrap_or_default();
+        Self {
+            task_id: task.id.clone(),
+            title: task.title.clone(),
+            description: task.description.clone(),
+            status: task.status.clone(),
+            status_options: workflow.allowed_from(&task.status),
+            priority: task.priority,
+            points: task.points.map(|points| points.to_string()).unwrap_or_default(),
+            estimate: task.estimate_minutes.map(|minutes| utils::format_estimate(minutes.into())).unwrap_or_default(),
+            due_date: due_date.clone(),
+            tags: task.tags.join(", "),
+            parent: task.parent_id.as_deref().map(short_id).unwrap_or_default(),
+            blocked_by: task.blocked_by.iter().map(|id| short_id(id)).collect::<Vec<_>>().join(", "),
+            repeat: task.recurrence.as_ref().map(ToString::to_string).unwrap_or_default(),
+            focused: FormField::Title,
+            error: None,
+            initial_description: task.description.clone(),
+            initial_due_date: due_date,
+        }
+    }
+
+    pub fn next_field(&mut self) {
+        let fields: Vec<FormField> = FormField::iter().collect();
+        let pos = fields.iter().position(|&f| f == self.focused).unwrap_or(0);
+        self.focused = fields[(pos + 1) % fields.len()];
+    }
+
+    pub fn previous_field(&mut self) {
+        let fields: Vec<FormField> = FormField::iter().collect();
+        let pos = fields.iter().position(|&f| f == self.focused).unwrap_or(0);
+        self.focused = fields[(pos + fields.len() - 1) % fields.len()];
+    }
+
+    /// Moves a picker to its next (or previous) option.
+    pub fn cycle_option(&mut self, forward: bool) {
+        match self.focused {
+            FormField::Status => self.status = cycle_in(&self.status_options, &self.status, forward),
+            FormField::Priority => self.priority = cycle(self.priority, forward),
+            _ => {}
+        }
+    }
+
+    pub fn input_char(&mut self, c: char) {
+        if let Some(text) = self.focused_text_mut() {
+            text.push(c);
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        if let Some(text) = self.focused_text_mut() {
+            text.pop();
+        }
+    }
+
+    fn focused_text_mut(&mut self) -> Option<&mut String> {
+        match self.focused {
+            FormField::Title => Some(&mut self.title),
+            FormField::Description => Some(&mut self.description),
+            FormField::Points => Some(&mut self.points),
+            FormField::Estimate => Some(&mut self.estimate),
+            FormField::DueDate => Some(&mut self.due_date),
+            FormField::Tags => Some(&mut self.tags),
+            FormField::Parent => Some(&mut self.parent),
+            FormField::BlockedBy => Some(&mut self.blocked_by),
+            FormField::Repeat => Some(&mut self.repeat),
+            FormField::Status | FormField::Priority => None,
+        }
+    }
+
+    /// Validates the form and copies its values onto `task`; `tasks` is the board,
+    /// which the parent id is looked up in.
+    pub fn apply(&self, task: &mut Task, tasks: &[Task]) -> Result<(), String> {
+        let title = self.title.trim();
+        if title.is_empty() {
+            return Err("Title cannot be empty".to_string());
+        }
+
+        let points = if self.points.trim().is_empty() {
+            None
+        } else {
+            let points = self.points.trim();
+            Some(points.parse::<u32>().map_err(|_| format!("Points must be a whole number, not \"{}\"", points))?)
+        };
+        let estimate_minutes = if self.estimate.trim().is_empty() {
+            None
+        } else {
+            Some(utils::parse_estimate(&self.estimate)?)
+        };
+
+        let due_date = if self.due_date == self.initial_due_date {
+            task.due_date
+        } else if self.due_date.trim().is_empty() {
+            None
+        } else {
+            Some(utils::parse_due_date(&self.due_date)?)
+        };
+
+        let mut tags: Vec<String> = Vec::new();
+        for tag in self.tags.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+            if !tags.iter().any(|t| t == tag) {
+                tags.push(tag.to_string());
+            }
+        }
+
+        let parent_id = subtasks::resolve_parent(tasks, &task.id, &self.parent)?;
+        let blocked_by = dependencies::resolve_prerequisites(tasks, &task.id, &self.blocked_by)?;
+        let recurrence = if self.repeat.trim().is_empty() {
+            None
+        } else {
+            Some(self.repeat.parse::<Recurrence>()?)
+        };
+
+        let before = task.clone();
+        task.title = title.to_string();
+        if self.description != self.initial_description {
+            task.description = self.description.trim().to_string();
+        }
+        task.status = self.status.clone();
+        task.priority = self.priority;
+        task.points = points;
+        task.estimate_minutes = estimate_minutes;
+        task.due_date = due_date;
+        task.tags = tags;
+        task.parent_id = parent_id;
+        task.blocked_by = blocked_by;
+        task.recurrence = recurrence;
+        task.record_changes(&before);
+        Ok(())
+    }
+}
+
+fn short_id(id: &str) -> String {
+    id.chars().take(8).collect()
+}
+
+fn cycle<T: IntoEnumIterator + PartialEq + Copy>(current: T, forward: bool) -> T {
+    let options: Vec<T> = T::iter().collect();
+    cycle_in(&options, &current, forward)
+}
+
+fn cycle_in<T: PartialEq + Clone>(options: &[T], current: &T, forward: bool) -> T {
+    if options.is_empty() {
+        return current.clone();
+    }
+    let pos = options.iter().position(|o| o == current).unwrap_or(0);
+    let next = if forward {
+        (pos + 1) % options.len()
+    } else {
+        (pos + options.len() - 1) % options.len()
+    };
+    options[next].clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone, Utc};
+
+    fn task() -> Task {
+        let mut task = Task::new("Write report", "Draft first\n", TaskStatus::new("Todo"), TaskPriority::Medium);
+        task.due_date = Some(Local.with_ymd_and_hms(2024, 5, 10, 9, 30, 0).unwrap().with_timezone(&Utc));
+        task
+    }
+
+    #[test]
+    fn saving_without_edits_changes_nothing() {
+        let mut task = task();
+        let before = task.clone();
+        let form = EditForm::from_task(&task, &Workflow::default());
+
+        form.apply(&mut task, std::slice::from_ref(&before)).unwrap();
+        assert_eq!(task.due_date, before.due_date);
+        assert_eq!(task.description, before.description);
+        assert_eq!(task.history.len(), before.history.len());
+    }
+
+    #[test]
+    fn edited_due_date_and_description_are_saved() {
+        let mut task = task();
+        let before = task.clone();
+        let mut form = EditForm::from_task(&task, &Workflow::default());
+        form.due_date = "2024-05-11".to_string();
+        form.description.push_str("  ");
+
+        form.apply(&mut task, std::slice::from_ref(&before)).unwrap();
+        assert_eq!(task.due_date, Some(utils::parse_due_date("2024-05-11").unwrap()));
+        assert_eq!(task.description, "Draft first");
+        assert_eq!(task.history.len(), before.history.len() + 1);
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::HashSet;
+
+use crate::app::{App, AppTab};
+use crate::models::{search, Task};
+
+/// Tag matches rank below title matches of the same quality.
+const TAG_PENALTY: i64 = 10;
+
+/// The Ctrl-p popup that jumps to any task on the board by fuzzy matching.
+pub struct TaskFinder {
+    pub query: String,
+    /// Ids of the matching tasks, best match first.
+    pub results: Vec<String>,
+    pub index: usize,
+}
+
+/// Rank of `task` for `query`: an id prefix beats everything, then the best of
+/// the title and its tags. `None` when nothing matches.
+fn score(task: &Task, query: &str) -> Option<i64> {
+    let query = query.trim();
+    if query.len() >= 2 && task.id.starts_with(&query.to_lowercase()) {
+        return Some(i64::MAX);
+    }
+    let tags = task.tags.iter().filter_map(|tag| search::fuzzy_score(tag, query)).map(|s| s - TAG_PENALTY);
+    search::fuzzy_score(&task.title, query).into_iter().chain(tags).max()
+}
+
+impl App {
+    pub fn open_finder(&mut self) {
+        self.finder = Some(TaskFinder { query: String::new(), results: Vec::new(), index: 0 });
+        self.update_finder_results();
+    }
+
+    pub fn close_finder(&mut self) {
+        self.finder = None;
+    }
+
+    pub fn finder_input_char(&mut self, c: char) {
+        if let Some(finder) = self.finder.as_mut() {
+            finder.query.push(c);
+        }
+        self.update_finder_results();
+    }
+
+    pub fn finder_backspace(&mut self) {
+        if let Some(finder) = self.finder.as_mut() {
+            finder.query.pop();
+        }
+        self.update_finder_results();
+    }
+
+    pub fn select_finder_result(&mut self, forward: bool) {
+        if let Some(finder) = self.finder.as_mut() {
+            let count = finder.results.len();
+            if count > 0 {
+                finder.index = if forward { (finder.index + 1) % count } else { (finder.index + count - 1) % count };
+            }
+        }
+    }
+
+    /// The task highlighted in the finder.
+    pub fn finder_task(&self) -> Option<&Task> {
+        let finder = self.finder.as_ref()?;
+        let id = finder.results.get(finder.index)?;
+        self.tasks.iter().find(|task| &task.id == id)
+    }
+
+    /// Goes to the highlighted task on the Tasks tab. Filters, the search and a
+    /// view tab that would hide it are dropped, and its folded parents unfolded.
+    pub fn confirm_finder(&mut self) {
+        let Some(task) = self.finder_task().cloned() else {
+            return;
+        };
+        self.finder = None;
+        self.current_tab = AppTab::Tasks;
+        self.show_task_details = false;
+
+        // Unfold every ancestor so the list view shows the task
+        let mut seen = HashSet::new();
+        let mut parent = task.parent_id.clone();
+        while let Some(id) = parent.filter(|id| seen.insert(id.clone())) {
+            self.collapsed.remove(&id);
+            parent = self.tasks.iter().find(|t| t.id == id).and_then(|t| t.parent_id.clone());
+        }
+
+        if !self.filtered_tasks().iter().any(|t| t.id == task.id) {
+            self.search_query.clear();
+            self.filter_active = false;
+            self.active_view = None;
+            self.status_message = Some(format!("Cleared filters to show \"{}\"", task.title));
+        }
+        self.select_task_by_id(&task.id);
+    }
+
+    /// Re-ranks the board for the current query; without one, lists every task in board order.
+    fn update_finder_results(&mut self) {
+        let Some(query) = self.finder.as_ref().map(|finder| finder.query.clone()) else {
+            return;
+        };
+
+        let results: Vec<String> = if query.trim().is_empty() {
+            self.tasks.iter().map(|task| task.id.clone()).collect()
+        } else {
+            let mut scored: Vec<(i64, &Task)> =
+                self.tasks.iter().filter_map(|task| score(task, &query).map(|s| (s, task))).collect();
+            // Stable sort keeps board order among equal scores
+            scored.sort_by_key(|&(s, _)| std::cmp::Reverse(s));
+            scored.into_iter().map(|(_, task)| task.id.clone()).collect()
+        };
+
+        if let Some(finder) = self.finder.as_mut() {
+            finder.results = results;
+            finder.index = 0;
+        }
+    }
+}
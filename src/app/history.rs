@@ -0,0 +1,60 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::storage::storage_manager;
+
+const MAX_HISTORY_LEN: usize = 50;
+
+/// Lines entered at a prompt, newest last, persisted to a JSON file in the board
+/// directory so they survive restarts.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct InputHistory {
+    #[serde(alias = "queries")]
+    pub entries: Vec<String>,
+}
+
+impl InputHistory {
+    pub fn load(data_dir: &Path, file_name: &str) -> Result<Self> {
+        let path = data_dir.join(file_name);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(&path)?;
+        serde_json::from_str(&contents).with_context(|| format!("failed to parse {}", path.display()))
+    }
+
+    pub fn save(&self, data_dir: &Path, file_name: &str) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        storage_manager::write_atomic(&data_dir.join(file_name), json.as_bytes())
+    }
+
+    /// Remembers `entry` as the newest one, dropping an older copy of it.
+    pub fn push(&mut self, entry: &str) {
+        self.entries.retain(|e| e != entry);
+        self.entries.push(entry.to_string());
+        if self.entries.len() > MAX_HISTORY_LEN {
+            self.entries.drain(..self.entries.len() - MAX_HISTORY_LEN);
+        }
+    }
+
+    /// Where browsing goes from `index` (`None` while typing): `older` steps back
+    /// in time and stops at the oldest entry, newer steps past the newest back to typing.
+    pub fn browse(&self, index: Option<usize>, older: bool) -> Option<usize> {
+        let count = self.entries.len();
+        match (index, older) {
+            _ if count == 0 => None,
+            (None, true) => Some(count - 1),
+            (Some(index), true) => Some(index.saturating_sub(1)),
+            (Some(index), false) => (index + 1 < count).then_some(index + 1),
+            (None, false) => None,
+        }
+    }
+
+    /// The entry at a browsing position, or nothing when back to typing.
+    pub fn entry(&self, index: Option<usize>) -> String {
+        index.and_then(|i| self.entries.get(i)).cloned().unwrap_or_default()
+    }
+}
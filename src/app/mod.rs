@@ -1,8 +1,46 @@
+pub mod calendar;
+pub mod edit_form;
+pub mod finder;
+pub mod history;
+pub mod palette;
+pub mod projects;
+pub mod search;
+pub mod subtasks;
+pub mod themes;
+pub mod time_tracking;
+pub mod undo;
+pub mod views;
+
 use anyhow::Result;
-use std::error;
 
-use crate::models::{Task, TaskStatus, TaskPriority};
-use crate::storage::storage_manager;
+use chrono::{Local, NaiveDate, Utc};
+
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::config;
+use crate::models::filter::Filter;
+use crate::models::keymap::{KeyChord, Keymap};
+use crate::models::theme::Theme;
+use crate::models::workflow::Workflow;
+use crate::models::{dependencies, recurrence, search as task_search, subtasks as task_tree, time_tracking as timers, Task, TaskStatus, TaskPriority, TrashedTask};
+use crate::stats;
+use crate::storage::views::SavedViews;
+use crate::storage::{self, storage_manager, Storage, Write};
+use crate::utils;
+use calendar::CalendarView;
+use edit_form::EditForm;
+use finder::TaskFinder;
+use palette::CommandPalette;
+use projects::ProjectSwitcher;
+use history::InputHistory;
+use search::SearchPrompt;
+use subtasks::InputTarget;
+use themes::ThemeSwitcher;
+use undo::{Change, Command, Expected, Journal};
+use views::ActiveView;
 
 pub type AppResult<T> = Result<T>;
 
@@ -17,6 +55,35 @@ pub enum AppMode {
 pub enum InputMode {
     Normal,
     Editing,
+    EditForm,
+    Search,
+    Command,
+}
+
+/// Order of tasks set with `:sort`; without one, columns go by priority and the
+/// list keeps board order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Priority,
+    Due,
+    Title,
+    Created,
+    Status,
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "priority" => Ok(SortKey::Priority),
+            "due" => Ok(SortKey::Due),
+            "title" => Ok(SortKey::Title),
+            "created" => Ok(SortKey::Created),
+            "status" => Ok(SortKey::Status),
+            _ => Err(format!("unknown sort \"{}\" (expected priority, due, title, created or status)", s)),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -24,442 +91,839 @@ pub enum AppTab {
     Tasks,
     Calendar,
     Statistics,
+    Trash,
 }
 
 pub struct App {
     pub tasks: Vec<Task>,
+    pub trash: Vec<TrashedTask>,
+    pub selected_trash_index: Option<usize>,
     pub mode: AppMode,
     pub input_mode: InputMode,
     pub current_tab: AppTab,
     pub selected_task_index: Option<usize>,
     pub new_task_input: String,
-    pub status_filter: Option<TaskStatus>,
-    pub priority_filter: Option<TaskPriority>,
+    pub input_target: InputTarget,
+    /// The query set with `F` or the number keys, applied while `filter_active`.
+    pub filter: Filter,
+    pub filter_text: String,
+    /// Text search over titles, descriptions and tags; applies whether or not
+    /// the status and priority filters are on.
     pub search_query: String,
+    pub search_prompt: Option<SearchPrompt>,
+    pub search_history: InputHistory,
+    /// The `:` command line while it is open.
+    pub palette: Option<CommandPalette>,
+    pub command_history: InputHistory,
     pub filter_active: bool,
+    pub sort: Option<SortKey>,
+    /// The project's saved views; pinned ones get a tab next to Tasks.
+    pub views: SavedViews,
+    /// The pinned view whose tab is open, narrowing the Tasks tab.
+    pub active_view: Option<ActiveView>,
     pub show_help: bool,
+    pub help_scroll: u16,
     pub show_task_details: bool,
+    /// Dependency graph popup over the task details.
+    pub show_dependency_graph: bool,
+    pub status_message: Option<String>,
+    pub edit_form: Option<EditForm>,
+    pub journal: Journal,
+    pub workflow: Workflow,
+    pub calendar_view: CalendarView,
+    pub calendar_cursor: NaiveDate,
+    pub calendar_task_index: usize,
+    /// Ids of tasks whose subtasks are folded away in the list view.
+    pub collapsed: HashSet<String>,
+    /// Cursor in the task detail checklist.
+    pub checklist_index: usize,
+    /// The `.taskx/` directory the board was found in; `None` for the global store.
+    pub local_board: Option<PathBuf>,
+    /// Name of the project whose board is open.
+    pub project: String,
+    pub project_switcher: Option<ProjectSwitcher>,
+    /// The Ctrl-p fuzzy finder while it is open.
+    pub finder: Option<TaskFinder>,
+    pub keymap: Keymap,
+    pub theme: Theme,
+    pub theme_switcher: Option<ThemeSwitcher>,
+    /// Keys typed so far of a multi-key binding such as `gg`.
+    pub pending_keys: Vec<KeyChord>,
+    storage: Box<dyn Storage>,
 }
 
-impl Default for App {
-    fn default() -> Self {
+impl App {
+    pub fn new(storage: Box<dyn Storage>, project: &str) -> Self {
         Self {
             tasks: Vec::new(),
-            mode: AppMode::Kanban,
+            trash: Vec::new(),
+            selected_trash_index: None,
+            mode: match config::current().default_view() {
+                "list" => AppMode::List,
+                "detail" => AppMode::Normal,
+                _ => AppMode::Kanban,
+            },
             input_mode: InputMode::Normal,
             current_tab: AppTab::Tasks,
             selected_task_index: None,
             new_task_input: String::new(),
-            status_filter: None,
-            priority_filter: None,
+            input_target: InputTarget::NewTask,
+            filter: Filter::default(),
+            filter_text: String::new(),
             search_query: String::new(),
+            search_prompt: None,
+            search_history: InputHistory::default(),
+            palette: None,
+            command_history: InputHistory::default(),
             filter_active: false,
+            sort: None,
+            views: SavedViews::default(),
+            active_view: None,
             show_help: false,
+            help_scroll: 0,
             show_task_details: false,
+            show_dependency_graph: false,
+            status_message: None,
+            edit_form: None,
+            journal: Journal::default(),
+            workflow: Workflow::default(),
+            calendar_view: CalendarView::Month,
+            calendar_cursor: Local::now().date_naive(),
+            calendar_task_index: 0,
+            collapsed: HashSet::new(),
+            checklist_index: 0,
+            local_board: storage_manager::find_local_board(),
+            project: project.to_string(),
+            project_switcher: None,
+            finder: None,
+            keymap: Keymap::default(),
+            theme: Theme::default(),
+            theme_switcher: None,
+            pending_keys: Vec::new(),
+            storage,
         }
     }
-}
-
-impl App {
-    pub fn new() -> Self {
-        Self::default()
-    }
 
     pub fn load_tasks(&mut self) -> Result<()> {
-        self.tasks = storage_manager::load_tasks()?;
-        if self.tasks.is_empty() {
+        // A broken workflow file should not lock the user out of their board
+        let workflow_warning = match storage::load_workflow(self.storage.data_dir()) {
+            Ok(workflow) => {
+                self.workflow = workflow;
+                None
+            }
+            Err(err) => {
+                self.workflow = Workflow::default();
+                Some(format!("{:#}; using the default workflow", err))
+            }
+        };
+
+        // Likewise a bad keymap only costs the user's own key bindings
+        let keymap_warning = match config::keymap::load() {
+            Ok(keymap) => {
+                self.keymap = keymap;
+                None
+            }
+            Err(err) => {
+                self.keymap = Keymap::default();
+                Some(format!("{:#}; using the default keys", err))
+            }
+        };
+
+        let theme_warning = self.load_theme();
+        let config_warning = config::current().warnings().first().cloned();
+
+        let loaded = self.storage.load()?;
+        self.tasks = loaded.tasks;
+        self.trash = storage::purge_expired_trash(self.storage.as_mut(), loaded.trash)?;
+        let unknown = self.canonicalize_statuses();
+
+        // A recovery warning matters more than a migration notice
+        self.status_message = loaded
+            .warning
+            .or(config_warning)
+            .or(workflow_warning)
+            .or(keymap_warning)
+            .or(theme_warning)
+            .or_else(|| (unknown > 0).then(|| format!("{} tasks have a status missing from the workflow", unknown)))
+            .or_else(|| loaded.migrations.summary());
+        // Only the global default project starts with examples; new projects and
+        // boards checked into a repository start empty
+        let samples = config::current().sample_tasks();
+        if self.tasks.is_empty() && samples && self.in_default_project() && self.local_board.is_none() {
             self.add_sample_tasks();
+            self.storage.upsert_many(&self.tasks)?;
         }
+
+        // A damaged journal only costs the undo history, not the session
+        self.journal = match Journal::load(self.storage.data_dir()) {
+            Ok(journal) => journal,
+            Err(err) => {
+                self.status_message = Some(format!("Undo history discarded: {:#}", err));
+                Journal::default()
+            }
+        };
+        self.load_views();
+        // Search and command history are shared by every project on the board
+        let root = storage_manager::get_data_dir()?;
+        self.search_history = InputHistory::load(&root, search::SEARCH_HISTORY_FILE).unwrap_or_default();
+        self.command_history = InputHistory::load(&root, palette::COMMAND_HISTORY_FILE).unwrap_or_default();
         Ok(())
     }
 
+    /// Picks up edits to a config.toml while the board is open. Settings are read
+    /// where they are used, so most apply at once; a new storage backend or data
+    /// directory waits for a restart.
+    pub fn check_config(&mut self) {
+        match config::reload_if_changed() {
+            None => {}
+            Some(Ok((old, new))) => {
+                let restart = old.storage() != new.storage() || old.data_dir() != new.data_dir();
+                self.status_message = Some(if let Some(warning) = new.warnings().first() {
+                    warning.clone()
+                } else if restart {
+                    "Settings reloaded; restart TaskX to switch storage".to_string()
+                } else {
+                    "Settings reloaded".to_string()
+                });
+                if old.theme() != new.theme()
+                    && let Some(warning) = self.load_theme()
+                {
+                    self.status_message = Some(warning);
+                }
+            }
+            Some(Err(err)) => {
+                self.status_message = Some(format!("{:#}; keeping the previous settings", err));
+            }
+        }
+    }
+
+    /// Spells every status the way the workflow does and returns how many it does not know.
+    fn canonicalize_statuses(&mut self) -> usize {
+        let mut unknown = 0;
+        let tasks = self.tasks.iter_mut().chain(self.trash.iter_mut().map(|entry| &mut entry.task));
+        for task in tasks {
+            match self.workflow.resolve(task.status.as_str()) {
+                Ok(status) => task.status = status,
+                Err(_) => unknown += 1,
+            }
+        }
+        unknown
+    }
+
+    /// Applies a command, persists it and records it for undo.
+    pub fn execute(&mut self, command: Command) -> Result<()> {
+        self.apply_changes(&command.changes)?;
+        self.reload_journal();
+        self.journal.record(command);
+        self.journal.save(self.storage.data_dir())
+    }
+
+    pub fn undo(&mut self) -> Result<()> {
+        self.reload_journal();
+        let Some(command) = self.journal.undo.pop() else {
+            self.status_message = Some("Nothing to undo".to_string());
+            return Ok(());
+        };
+
+        if !self.board_matches(&command.changes)? {
+            return self.discard_stale("undo", &command);
+        }
+        let changes: Vec<Change> =
+            command.inverse_changes().into_iter().map(|change| self.keep_history(change)).collect();
+        self.apply_changes(&changes)?;
+        self.status_message = Some(format!("Undid: {}", command.description));
+        self.journal.push_redo(command);
+        self.journal.save(self.storage.data_dir())?;
+        self.clamp_selections();
+        Ok(())
+    }
+
+    pub fn redo(&mut self) -> Result<()> {
+        self.reload_journal();
+        let Some(command) = self.journal.redo.pop() else {
+            self.status_message = Some("Nothing to redo".to_string());
+            return Ok(());
+        };
+
+        if !self.board_matches(&command.inverse_changes())? {
+            return self.discard_stale("redo", &command);
+        }
+        let changes: Vec<Change> = command.changes.iter().cloned().map(|change| self.keep_history(change)).collect();
+        self.apply_changes(&changes)?;
+        self.status_message = Some(format!("Redid: {}", command.description));
+        self.journal.push_undo(command);
+        self.journal.save(self.storage.data_dir())?;
+        self.clamp_selections();
+        Ok(())
+    }
+
+    // The CLI records its changes in the same journal, so pick those up first
+    fn reload_journal(&mut self) {
+        if let Ok(journal) = Journal::load(self.storage.data_dir()) {
+            self.journal = journal;
+        }
+    }
+
+    /// Re-reads the board and checks that every task `changes` touch is still the
+    /// way they left it, i.e. nothing such as the CLI changed it since. History is
+    /// not compared, as undo and redo add to it.
+    fn board_matches(&mut self, changes: &[Change]) -> Result<bool> {
+        let loaded = self.storage.load()?;
+        self.tasks = loaded.tasks;
+        self.trash = loaded.trash;
+        self.canonicalize_statuses();
+
+        Ok(undo::expected_state(changes).into_iter().all(|(id, expected)| {
+            let on_board = self.tasks.iter().find(|task| task.id == id);
+            let in_trash = self.trash.iter().find(|entry| entry.task.id == id).map(|entry| &entry.task);
+            let same = |found: Option<&Task>, task: &Task| {
+                found.is_some_and(|found| Task { history: Vec::new(), ..found.clone() } == Task { history: Vec::new(), ..task.clone() })
+            };
+            match expected {
+                Expected::OnBoard(task) => same(on_board, task),
+                Expected::InTrash(task) => on_board.is_none() && same(in_trash, task),
+                Expected::OffBoard => on_board.is_none(),
+                Expected::OffTrash => in_trash.is_none(),
+            }
+        }))
+    }
+
+    /// Rewrites a change being undone or redone to keep the task's history as it is
+    /// now, with events for what it reverts, rather than the snapshot's history.
+    fn keep_history(&self, change: Change) -> Change {
+        let carry = |task: &mut Task| {
+            let current = self.tasks.iter().chain(self.trash.iter().map(|entry| &entry.task)).find(|t| t.id == task.id);
+            if let Some(current) = current {
+                task.history = current.history.clone();
+                task.record_changes(current);
+            }
+        };
+        match change {
+            Change::Added(mut task) => {
+                carry(&mut task);
+                Change::Added(task)
+            }
+            Change::Updated { before, mut after } => {
+                carry(&mut after);
+                Change::Updated { before, after }
+            }
+            Change::Trashed(mut entry) => {
+                carry(&mut entry.task);
+                Change::Trashed(entry)
+            }
+            Change::Restored(mut entry) => {
+                carry(&mut entry.task);
+                Change::Restored(entry)
+            }
+            other => other,
+        }
+    }
+
+    /// Drops a command that would overwrite newer changes, along with everything to redo.
+    fn discard_stale(&mut self, action: &str, command: &Command) -> Result<()> {
+        self.journal.redo.clear();
+        self.journal.save(self.storage.data_dir())?;
+        self.status_message = Some(format!("Cannot {} {}: the task has changed since", action, command.description));
+        self.clamp_selections();
+        Ok(())
+    }
+
+    /// Writes `changes` to storage as one update, then mirrors them in memory.
+    fn apply_changes(&mut self, changes: &[Change]) -> Result<()> {
+        let writes: Vec<Write> = changes.iter().flat_map(Change::writes).collect();
+        self.storage.apply(&writes)?;
+        for change in changes {
+            self.mirror_change(change);
+        }
+        Ok(())
+    }
+
+    fn mirror_change(&mut self, change: &Change) {
+        match change {
+            Change::Added(task) => self.mirror_upsert(task),
+            Change::Updated { after, .. } => self.mirror_upsert(after),
+            Change::Removed(task) => self.tasks.retain(|t| t.id != task.id),
+            Change::Trashed(entry) => {
+                self.tasks.retain(|t| t.id != entry.task.id);
+                self.trash.retain(|e| e.task.id != entry.task.id);
+                self.trash.push(entry.clone());
+            }
+            Change::Restored(entry) => {
+                self.trash.retain(|e| e.task.id != entry.task.id);
+                self.tasks.retain(|t| t.id != entry.task.id);
+                self.tasks.push(entry.task.clone());
+            }
+            Change::Purged(entries) => {
+                self.trash.retain(|e| !entries.iter().any(|entry| entry.task.id == e.task.id));
+            }
+            Change::Unpurged(entries) => {
+                for entry in entries {
+                    self.trash.retain(|e| e.task.id != entry.task.id);
+                    self.trash.push(entry.clone());
+                }
+            }
+        }
+    }
+
+    fn mirror_upsert(&mut self, task: &Task) {
+        match self.tasks.iter_mut().find(|t| t.id == task.id) {
+            Some(existing) => *existing = task.clone(),
+            None => self.tasks.push(task.clone()),
+        }
+    }
+
     pub fn add_sample_tasks(&mut self) {
+        let todo = self.workflow.initial();
+        let done = self.workflow.first_done();
+        let in_progress = self
+            .workflow
+            .statuses()
+            .nth(1)
+            .filter(|status| !self.workflow.is_done(status))
+            .unwrap_or_else(|| todo.clone());
+
         let sample_tasks = vec![
-            Task::new("Implement task creation", "Add ability to create new tasks", TaskStatus::Todo, TaskPriority::High),
-            Task::new("Design kanban view", "Create a beautiful kanban board UI", TaskStatus::InProgress, TaskPriority::Critical),
-            Task::new("Add keyboard shortcuts", "Implement intuitive keyboard navigation", TaskStatus::Todo, TaskPriority::Medium),
-            Task::new("Create task filtering", "Allow filtering by status and priority", TaskStatus::Todo, TaskPriority::Low),
-            Task::new("Implement persistence", "Save tasks to disk", TaskStatus::InProgress, TaskPriority::High),
-            Task::new("Add due dates", "Implement due date field and sorting", TaskStatus::Todo, TaskPriority::Medium),
-            Task::new("Create list view", "Implement alternative list view", TaskStatus::Done, TaskPriority::Medium),
-            Task::new("Add task search", "Implement text search functionality", TaskStatus::Done, TaskPriority::Low),
-            Task::new("Design statistics view", "Create charts for task progress", TaskStatus::Todo, TaskPriority::Medium),
-            Task::new("Write documentation", "Document usage and shortcuts", TaskStatus::Todo, TaskPriority::Low),
+            Task::new("Implement task creation", "Add ability to create new tasks", todo.clone(), TaskPriority::High),
+            Task::new("Design kanban view", "Create a beautiful kanban board UI", in_progress.clone(), TaskPriority::Critical),
+            Task::new("Add keyboard shortcuts", "Implement intuitive keyboard navigation", todo.clone(), TaskPriority::Medium),
+            Task::new("Create task filtering", "Allow filtering by status and priority", todo.clone(), TaskPriority::Low),
+            Task::new("Implement persistence", "Save tasks to disk", in_progress.clone(), TaskPriority::High),
+            Task::new("Add due dates", "Implement due date field and sorting", todo.clone(), TaskPriority::Medium),
+            Task::new("Create list view", "Implement alternative list view", done.clone(), TaskPriority::Medium),
+            Task::new("Add task search", "Implement text search functionality", done.clone(), TaskPriority::Low),
+            Task::new("Design statistics view", "Create charts for task progress", todo.clone(), TaskPriority::Medium),
+            Task::new("Write documentation", "Document usage and shortcuts", todo.clone(), TaskPriority::Low),
         ];
 
         self.tasks.extend(sample_tasks);
     }
 
-    pub fn save_tasks(&self) -> Result<()> {
-        storage_manager::save_tasks(&self.tasks)?;
-        Ok(())
-    }
-
-    pub fn add_task(&mut self, title: String, description: String, status: TaskStatus, priority: TaskPriority) {
+    pub fn add_task(&mut self, title: String, description: String, status: TaskStatus, priority: TaskPriority) -> Result<()> {
         let task = Task::new(&title, &description, status, priority);
-        self.tasks.push(task);
+        self.execute(Command::new(format!("Add \"{}\"", task.title), vec![Change::Added(task)]))
     }
 
     pub fn toggle_mode(&mut self) {
-        self.mode = match self.mode {
+        self.set_mode(match self.mode {
             AppMode::Normal => AppMode::Kanban,
             AppMode::Kanban => AppMode::List,
             AppMode::List => AppMode::Normal,
-        };
+        });
+    }
+
+    pub fn set_mode(&mut self, mode: AppMode) {
+        // The list view orders tasks as a tree, so keep the selection by id
+        let selected_id = self.selected_task().map(|task| task.id.clone());
+        self.mode = mode;
+        if let Some(id) = selected_id {
+            self.select_task_by_id(&id);
+        }
     }
 
+    /// Pinned views sit between the Tasks and Calendar tabs.
     pub fn next_tab(&mut self) {
-        self.current_tab = match self.current_tab {
-            AppTab::Tasks => AppTab::Calendar,
-            AppTab::Calendar => AppTab::Statistics,
-            AppTab::Statistics => AppTab::Tasks,
-        };
+        let pinned = self.views.pinned();
+        let view = self.active_view.as_ref().map(|view| view.index);
+        match self.current_tab {
+            AppTab::Tasks => {
+                let next = match view {
+                    Some(index) => pinned.iter().skip_while(|&&i| i != index).nth(1).copied(),
+                    None => pinned.first().copied(),
+                };
+                match next {
+                    Some(index) => self.open_view(Some(index)),
+                    None => {
+                        self.active_view = None;
+                        self.current_tab = AppTab::Calendar;
+                    }
+                }
+            }
+            AppTab::Calendar => self.current_tab = AppTab::Statistics,
+            AppTab::Statistics => self.current_tab = AppTab::Trash,
+            AppTab::Trash => self.open_view(None),
+        }
     }
 
     pub fn previous_tab(&mut self) {
-        self.current_tab = match self.current_tab {
-            AppTab::Tasks => AppTab::Statistics,
-            AppTab::Calendar => AppTab::Tasks,
-            AppTab::Statistics => AppTab::Calendar,
-        };
+        let pinned = self.views.pinned();
+        match self.current_tab {
+            AppTab::Tasks => match self.active_view.as_ref().map(|view| view.index) {
+                Some(index) => {
+                    let previous = pinned.iter().rev().skip_while(|&&i| i != index).nth(1).copied();
+                    self.open_view(previous);
+                }
+                None => self.current_tab = AppTab::Trash,
+            },
+            AppTab::Calendar => self.open_view(pinned.last().copied()),
+            AppTab::Statistics => self.current_tab = AppTab::Calendar,
+            AppTab::Trash => self.current_tab = AppTab::Statistics,
+        }
     }
 
     pub fn toggle_input_mode(&mut self) {
+        self.input_target = InputTarget::NewTask;
         self.input_mode = match self.input_mode {
             InputMode::Normal => InputMode::Editing,
-            InputMode::Editing => InputMode::Normal,
+            InputMode::Editing | InputMode::EditForm | InputMode::Search | InputMode::Command => InputMode::Normal,
         };
     }
 
-    pub fn toggle_help(&mut self) {
-        self.show_help = !self.show_help;
+    /// Moves the selected task to the trash bin.
+    pub fn delete_selected_task(&mut self) -> Result<()> {
+        let Some(task) = self.selected_task().cloned() else {
+            return Ok(());
+        };
+
+        self.status_message = Some(format!("Moved \"{}\" to trash (u to undo)", task.title));
+        let description = format!("Delete \"{}\"", task.title);
+        let entry = TrashedTask { task, deleted_at: Utc::now() };
+        self.execute(Command::new(description, vec![Change::Trashed(entry)]))?;
+        self.clamp_selections();
+        Ok(())
     }
 
-    pub fn filtered_tasks(&self) -> Vec<&Task> {
-        self.tasks
-            .iter()
-            .filter(|task| {
-                if !self.filter_active {
-                    return true;
-                }
+    /// Puts the selected trash entry back on the board.
+    pub fn restore_selected_trash(&mut self) -> Result<()> {
+        let Some(index) = self.selected_trash_index.filter(|&i| i < self.trash.len()) else {
+            return Ok(());
+        };
+
+        let entry = self.trash[index].clone();
+        self.status_message = Some(format!("Restored \"{}\"", entry.task.title));
+        let description = format!("Restore \"{}\"", entry.task.title);
+        self.execute(Command::new(description, vec![Change::Restored(entry)]))?;
+        self.clamp_selections();
+        Ok(())
+    }
 
-                let status_match = self.status_filter
-                    .map_or(true, |status| task.status == status);
+    /// Permanently deletes the selected trash entry.
+    pub fn purge_selected_trash(&mut self) -> Result<()> {
+        let Some(index) = self.selected_trash_index.filter(|&i| i < self.trash.len()) else {
+            return Ok(());
+        };
 
-                let priority_match = self.priority_filter
-                    .map_or(true, |priority| task.priority == priority);
+        let entry = self.trash[index].clone();
+        self.status_message = Some(format!("Permanently deleted \"{}\"", entry.task.title));
+        let description = format!("Purge \"{}\"", entry.task.title);
+        self.execute(Command::new(description, vec![Change::Purged(vec![entry])]))?;
+        self.clamp_selections();
+        Ok(())
+    }
 
-                let search_match = self.search_query.is_empty() ||
-                    task.title.to_lowercase().contains(&self.search_query.to_lowercase()) ||
-                    task.description.to_lowercase().contains(&self.search_query.to_lowercase());
+    /// Permanently deletes everything in the trash bin.
+    pub fn empty_trash(&mut self) -> Result<()> {
+        if self.trash.is_empty() {
+            return Ok(());
+        }
 
-                status_match && priority_match && search_match
-            })
-            .collect()
+        let entries = self.trash.clone();
+        self.status_message = Some(format!("Emptied trash ({} tasks)", entries.len()));
+        let description = format!("Empty trash ({} tasks)", entries.len());
+        self.execute(Command::new(description, vec![Change::Purged(entries)]))?;
+        self.selected_trash_index = None;
+        Ok(())
     }
 
-    pub fn select_next_task(&mut self) {
-        let tasks = self.filtered_tasks();
-        if tasks.is_empty() {
-            self.selected_task_index = None;
+    pub fn select_next_trash(&mut self) {
+        if self.trash.is_empty() {
+            self.selected_trash_index = None;
             return;
         }
+        self.selected_trash_index = Some(match self.selected_trash_index {
+            Some(index) => (index + 1) % self.trash.len(),
+            None => 0,
+        });
+    }
 
-        if let Some(current_idx) = self.selected_task_index {
-            if current_idx >= tasks.len() - 1 {
-                self.selected_task_index = Some(0);
-                return;
-            }
-
-            // Only in Kanban mode, we want to avoid jumping between columns unexpectedly
-            if self.mode == AppMode::Kanban && current_idx < tasks.len() - 1 {
-                let current_status = tasks[current_idx].status;
-
-                // Find all tasks in the same column, sorted by priority
-                let same_column_tasks: Vec<(usize, &&Task)> = tasks.iter()
-                    .enumerate()
-                    .filter(|(_, t)| t.status == current_status)
-                    .collect();
-
-                // Sort these tasks by priority (highest first)
-                let mut prioritized_column_tasks = same_column_tasks.clone();
-                prioritized_column_tasks.sort_by(|(_, a), (_, b)| {
-                    let a_weight = match a.priority {
-                        TaskPriority::Critical => 4,
-                        TaskPriority::High => 3,
-                        TaskPriority::Medium => 2,
-                        TaskPriority::Low => 1,
-                    };
-                    let b_weight = match b.priority {
-                        TaskPriority::Critical => 4,
-                        TaskPriority::High => 3,
-                        TaskPriority::Medium => 2,
-                        TaskPriority::Low => 1,
-                    };
-                    b_weight.cmp(&a_weight)
-                });
+    pub fn select_previous_trash(&mut self) {
+        if self.trash.is_empty() {
+            self.selected_trash_index = None;
+            return;
+        }
+        self.selected_trash_index = Some(match self.selected_trash_index {
+            Some(0) | None => self.trash.len() - 1,
+            Some(index) => index - 1,
+        });
+    }
 
-                // Extract just the indexes
-                let sorted_column_task_indexes: Vec<usize> = prioritized_column_tasks.iter()
-                    .map(|(idx, _)| *idx)
-                    .collect();
-
-                // Find position of current task in this sorted list
-                if let Some(pos) = sorted_column_task_indexes.iter().position(|&idx| idx == current_idx) {
-                    // If not at the end of column, move to next task in same column
-                    if pos < sorted_column_task_indexes.len() - 1 {
-                        self.selected_task_index = Some(sorted_column_task_indexes[pos + 1]);
-                        return;
-                    } else {
-                        // At the end of column, find next column
-                        let next_status = match current_status {
-                            TaskStatus::Todo => TaskStatus::InProgress,
-                            TaskStatus::InProgress => TaskStatus::Done,
-                            TaskStatus::Done => TaskStatus::Todo,
-                        };
-
-                        // Find tasks in next column, also sorted by priority
-                        let next_column_tasks: Vec<(usize, &&Task)> = tasks.iter()
-                            .enumerate()
-                            .filter(|(_, t)| t.status == next_status)
-                            .collect();
-
-                        if !next_column_tasks.is_empty() {
-                            // Sort by priority
-                            let mut prioritized_next = next_column_tasks.clone();
-                            prioritized_next.sort_by(|(_, a), (_, b)| {
-                                let a_weight = match a.priority {
-                                    TaskPriority::Critical => 4,
-                                    TaskPriority::High => 3,
-                                    TaskPriority::Medium => 2,
-                                    TaskPriority::Low => 1,
-                                };
-                                let b_weight = match b.priority {
-                                    TaskPriority::Critical => 4,
-                                    TaskPriority::High => 3,
-                                    TaskPriority::Medium => 2,
-                                    TaskPriority::Low => 1,
-                                };
-                                b_weight.cmp(&a_weight)
-                            });
-
-                            // Take first task in next column
-                            if let Some((idx, _)) = prioritized_next.first() {
-                                self.selected_task_index = Some(*idx);
-                                return;
-                            }
-                        }
-                    }
-                }
-            }
+    /// Keeps both selections pointing at items that still exist after a change.
+    fn clamp_selections(&mut self) {
+        self.selected_trash_index = if self.trash.is_empty() {
+            None
+        } else {
+            self.selected_trash_index.map(|index| index.min(self.trash.len() - 1))
+        };
 
-            // Fallback to simple next
-            self.selected_task_index = Some(current_idx + 1);
+        let remaining = self.filtered_tasks().len();
+        self.selected_task_index = if remaining == 0 {
+            None
         } else {
-            self.selected_task_index = Some(0);
+            self.selected_task_index.map(|index| index.min(remaining - 1))
+        };
+    }
+
+    pub fn selected_task(&self) -> Option<&Task> {
+        self.selected_task_index
+            .and_then(|index| self.filtered_tasks().get(index).copied())
+    }
+
+    /// Points the selection at the task with `id` if it is visible.
+    pub fn select_task_by_id(&mut self, id: &str) {
+        if let Some(index) = self.filtered_tasks().iter().position(|task| task.id == id) {
+            self.selected_task_index = Some(index);
         }
     }
 
-    pub fn select_previous_task(&mut self) {
-        let tasks = self.filtered_tasks();
-        if tasks.is_empty() {
-            self.selected_task_index = None;
-            return;
+    /// Opens the edit form for the selected task, if there is one.
+    pub fn open_edit_form(&mut self) {
+        if let Some(task) = self.selected_task() {
+            self.edit_form = Some(EditForm::from_task(task, &self.workflow));
+            self.input_mode = InputMode::EditForm;
         }
+    }
 
-        if let Some(current_idx) = self.selected_task_index {
-            if current_idx == 0 {
-                self.selected_task_index = Some(tasks.len() - 1);
-                return;
-            }
+    pub fn cancel_edit_form(&mut self) {
+        self.edit_form = None;
+        self.input_mode = InputMode::Normal;
+    }
 
-            // Only in Kanban mode, we want to avoid jumping between columns unexpectedly
-            if self.mode == AppMode::Kanban && current_idx > 0 {
-                let current_status = tasks[current_idx].status;
-
-                // Find all tasks in the same column, sorted by priority
-                let same_column_tasks: Vec<(usize, &&Task)> = tasks.iter()
-                    .enumerate()
-                    .filter(|(_, t)| t.status == current_status)
-                    .collect();
-
-                // Sort these tasks by priority (highest first)
-                let mut prioritized_column_tasks = same_column_tasks.clone();
-                prioritized_column_tasks.sort_by(|(_, a), (_, b)| {
-                    let a_weight = match a.priority {
-                        TaskPriority::Critical => 4,
-                        TaskPriority::High => 3,
-                        TaskPriority::Medium => 2,
-                        TaskPriority::Low => 1,
-                    };
-                    let b_weight = match b.priority {
-                        TaskPriority::Critical => 4,
-                        TaskPriority::High => 3,
-                        TaskPriority::Medium => 2,
-                        TaskPriority::Low => 1,
-                    };
-                    b_weight.cmp(&a_weight)
-                });
+    /// Validates the form and persists it; on invalid input the form stays open with an error.
+    pub fn save_edit_form(&mut self) -> Result<()> {
+        let Some(form) = self.edit_form.as_mut() else {
+            return Ok(());
+        };
+        let Some(task) = self.tasks.iter().find(|t| t.id == form.task_id) else {
+            self.cancel_edit_form();
+            return Ok(());
+        };
 
-                // Extract just the indexes
-                let sorted_column_task_indexes: Vec<usize> = prioritized_column_tasks.iter()
-                    .map(|(idx, _)| *idx)
-                    .collect();
-
-                // Find position of current task in this sorted list
-                if let Some(pos) = sorted_column_task_indexes.iter().position(|&idx| idx == current_idx) {
-                    // If not at the beginning of column, move to previous task in same column
-                    if pos > 0 {
-                        self.selected_task_index = Some(sorted_column_task_indexes[pos - 1]);
-                        return;
-                    } else {
-                        // At the beginning of column, find previous column
-                        let prev_status = match current_status {
-                            TaskStatus::Todo => TaskStatus::Done,
-                            TaskStatus::InProgress => TaskStatus::Todo,
-                            TaskStatus::Done => TaskStatus::InProgress,
-                        };
-
-                        // Find tasks in previous column, also sorted by priority
-                        let prev_column_tasks: Vec<(usize, &&Task)> = tasks.iter()
-                            .enumerate()
-                            .filter(|(_, t)| t.status == prev_status)
-                            .collect();
-
-                        if !prev_column_tasks.is_empty() {
-                            // Sort by priority
-                            let mut prioritized_prev = prev_column_tasks.clone();
-                            prioritized_prev.sort_by(|(_, a), (_, b)| {
-                                let a_weight = match a.priority {
-                                    TaskPriority::Critical => 4,
-                                    TaskPriority::High => 3,
-                                    TaskPriority::Medium => 2,
-                                    TaskPriority::Low => 1,
-                                };
-                                let b_weight = match b.priority {
-                                    TaskPriority::Critical => 4,
-                                    TaskPriority::High => 3,
-                                    TaskPriority::Medium => 2,
-                                    TaskPriority::Low => 1,
-                                };
-                                b_weight.cmp(&a_weight)
-                            });
-
-                            // Take last task in previous column
-                            if let Some((idx, _)) = prioritized_prev.last() {
-                                self.selected_task_index = Some(*idx);
-                                return;
-                            }
-                        }
-                    }
-                }
-            }
+        let mut updated = task.clone();
+        if let Err(err) = form.apply(&mut updated, &self.tasks) {
+            form.error = Some(err);
+            return Ok(());
+        }
+        if updated.status != task.status
+            && let Err(err) = task_tree::check_can_finish(&self.tasks, &updated, &updated.status, &self.workflow)
+        {
+            form.error = Some(err);
+            return Ok(());
+        }
 
-            // Fallback to simple previous
-            self.selected_task_index = Some(current_idx - 1);
+        let description = format!("Edit \"{}\"", updated.title);
+        self.status_message = Some("Task updated".to_string());
+        self.save_update(description, task.clone(), updated)?;
+        self.cancel_edit_form();
+        Ok(())
+    }
+
+    pub fn toggle_help(&mut self) {
+        self.show_help = !self.show_help;
+        self.help_scroll = 0;
+    }
+
+    /// Whether any task `task` waits on is still open.
+    pub fn is_blocked(&self, task: &Task) -> bool {
+        dependencies::is_blocked(&self.tasks, task, &self.workflow)
+    }
+
+    /// Tasks that pass the active filters, in the order the current view shows them.
+    pub fn filtered_tasks(&self) -> Vec<&Task> {
+        if self.mode == AppMode::List {
+            self.tree_rows().into_iter().map(|(task, _)| task).collect()
         } else {
-            self.selected_task_index = Some(tasks.len() - 1);
+            self.matching_tasks()
         }
     }
 
-    pub fn move_task_status(&mut self) -> Result<()> {
-        if let Some(index) = self.selected_task_index {
-            let tasks = self.filtered_tasks();
-            if let Some(task_ref) = tasks.get(index) {
-                // Find the actual index in the main tasks vector
-                if let Some(actual_index) = self.tasks.iter().position(|t| t.id == task_ref.id) {
-                    let next_status = match self.tasks[actual_index].status {
-                        TaskStatus::Todo => TaskStatus::InProgress,
-                        TaskStatus::InProgress => TaskStatus::Done,
-                        TaskStatus::Done => TaskStatus::Todo,
-                    };
-                    self.tasks[actual_index].status = next_status;
-                    self.save_tasks()?;
-                }
-            }
+    /// Tasks that pass the active filters, in board order or the `:sort` order.
+    fn matching_tasks(&self) -> Vec<&Task> {
+        let mut tasks: Vec<&Task> = self
+            .tasks
+            .iter()
+            .filter(|task| task_search::matches(task, &self.search_query) && self.passes_filters(task))
+            .collect();
+        match self.sort {
+            None => {}
+            Some(SortKey::Priority) => tasks.sort_by_key(|task| Reverse(task.priority)),
+            // Tasks without a due date go last
+            Some(SortKey::Due) => tasks.sort_by_key(|task| (task.due_date.is_none(), task.due_date)),
+            Some(SortKey::Title) => tasks.sort_by_key(|task| task.title.to_lowercase()),
+            Some(SortKey::Created) => tasks.sort_by_key(|task| task.created_at),
+            Some(SortKey::Status) => tasks.sort_by_key(|task| self.workflow.column_of(&task.status)),
         }
-        Ok(())
+        tasks
     }
 
-    // Navigates to the next column in Kanban view
-    pub fn next_column(&mut self) {
-        if self.mode != AppMode::Kanban {
+    /// Indexes into `filtered_tasks` of one Kanban column, highest priority first
+    /// unless another order was chosen with `:sort`.
+    pub fn column_task_indexes(&self, column: usize) -> Vec<usize> {
+        let tasks = self.filtered_tasks();
+        let mut indexes: Vec<usize> = (0..tasks.len())
+            .filter(|&i| self.workflow.column_of(&tasks[i].status) == column)
+            .collect();
+        if self.sort.is_none() {
+            indexes.sort_by_key(|&i| Reverse(tasks[i].priority));
+        }
+        indexes
+    }
+
+    fn selected_column(&self) -> usize {
+        self.selected_task()
+            .map_or(0, |task| self.workflow.column_of(&task.status))
+    }
+
+    /// The first non-empty column after (or before) `column`, wrapping around.
+    fn nearest_column(&self, column: usize, forward: bool) -> Option<Vec<usize>> {
+        let count = self.workflow.statuses.len();
+        (1..=count)
+            .map(|offset| if forward { (column + offset) % count } else { (column + count - offset) % count })
+            .map(|c| self.column_task_indexes(c))
+            .find(|indexes| !indexes.is_empty())
+    }
+
+    pub fn select_next_task(&mut self) {
+        let count = self.filtered_tasks().len();
+        if count == 0 {
+            self.selected_task_index = None;
             return;
         }
+        let Some(current) = self.selected_task_index else {
+            self.selected_task_index = Some(0);
+            return;
+        };
 
-        let tasks = self.filtered_tasks();
-        if tasks.is_empty() {
+        if self.mode != AppMode::Kanban {
+            self.selected_task_index = Some((current + 1) % count);
             return;
         }
 
-        // Determine which column to navigate to
-        let current_status = if let Some(idx) = self.selected_task_index {
-            if idx < tasks.len() {
-                tasks[idx].status
-            } else {
-                TaskStatus::Todo
-            }
-        } else {
-            TaskStatus::Todo
+        // Walk down the column, then continue at the top of the next non-empty one
+        let column = self.selected_column();
+        let indexes = self.column_task_indexes(column);
+        let pos = indexes.iter().position(|&i| i == current);
+        self.selected_task_index = match pos {
+            Some(pos) if pos + 1 < indexes.len() => Some(indexes[pos + 1]),
+            _ => self.nearest_column(column, true).map(|next| next[0]),
         };
+    }
 
-        // Find the next column status
-        let next_status = match current_status {
-            TaskStatus::Todo => TaskStatus::InProgress,
-            TaskStatus::InProgress => TaskStatus::Done,
-            TaskStatus::Done => TaskStatus::Todo,
-        };
+    /// Jumps to the first task, or the top of the column in the Kanban view.
+    pub fn select_first_task(&mut self) {
+        self.select_end_task(false);
+    }
 
-        // Find the first task in the next column
-        let next_column_tasks: Vec<usize> = tasks.iter()
-            .enumerate()
-            .filter(|(_, t)| t.status == next_status)
-            .map(|(i, _)| i)
-            .collect();
+    /// Jumps to the last task, or the bottom of the column in the Kanban view.
+    pub fn select_last_task(&mut self) {
+        self.select_end_task(true);
+    }
 
-        if !next_column_tasks.is_empty() {
-            self.selected_task_index = Some(next_column_tasks[0]);
+    fn select_end_task(&mut self, last: bool) {
+        let count = self.filtered_tasks().len();
+        if count == 0 {
+            self.selected_task_index = None;
+            return;
+        }
+        if self.mode != AppMode::Kanban {
+            self.selected_task_index = Some(if last { count - 1 } else { 0 });
+            return;
+        }
+        let indexes = self.column_task_indexes(self.selected_column());
+        let end = if last { indexes.last() } else { indexes.first() };
+        if let Some(&index) = end {
+            self.selected_task_index = Some(index);
         }
     }
 
-    // Navigates to the previous column in Kanban view
-    pub fn previous_column(&mut self) {
-        if self.mode != AppMode::Kanban {
+    pub fn select_previous_task(&mut self) {
+        let count = self.filtered_tasks().len();
+        if count == 0 {
+            self.selected_task_index = None;
             return;
         }
+        let Some(current) = self.selected_task_index else {
+            self.selected_task_index = Some(count - 1);
+            return;
+        };
 
-        let tasks = self.filtered_tasks();
-        if tasks.is_empty() {
+        if self.mode != AppMode::Kanban {
+            self.selected_task_index = Some((current + count - 1) % count);
             return;
         }
 
-        // Determine which column to navigate to
-        let current_status = if let Some(idx) = self.selected_task_index {
-            if idx < tasks.len() {
-                tasks[idx].status
-            } else {
-                TaskStatus::Todo
-            }
-        } else {
-            TaskStatus::Todo
+        // Walk up the column, then continue at the bottom of the previous non-empty one
+        let column = self.selected_column();
+        let indexes = self.column_task_indexes(column);
+        let pos = indexes.iter().position(|&i| i == current);
+        self.selected_task_index = match pos {
+            Some(pos) if pos > 0 => Some(indexes[pos - 1]),
+            _ => self.nearest_column(column, false).and_then(|prev| prev.last().copied()),
         };
+    }
 
-        // Find the previous column status
-        let prev_status = match current_status {
-            TaskStatus::Todo => TaskStatus::Done,
-            TaskStatus::InProgress => TaskStatus::Todo,
-            TaskStatus::Done => TaskStatus::InProgress,
+    /// Moves the selected task to the next status its workflow allows.
+    pub fn move_task_status(&mut self) -> Result<()> {
+        let Some(task) = self.selected_task() else {
+            return Ok(());
         };
+        match self.workflow.next_status(&task.status) {
+            Some(next_status) => self.set_selected_status(next_status),
+            None => {
+                self.status_message = Some(format!("No transitions allowed from {}", task.status));
+                Ok(())
+            }
+        }
+    }
 
-        // Find the first task in the previous column
-        let prev_column_tasks: Vec<usize> = tasks.iter()
-            .enumerate()
-            .filter(|(_, t)| t.status == prev_status)
-            .map(|(i, _)| i)
-            .collect();
+    /// Moves the selected task to `status`, unless it has unfinished subtasks to finish first.
+    pub fn set_selected_status(&mut self, status: TaskStatus) -> Result<()> {
+        let Some(before) = self.selected_task().cloned() else {
+            return Ok(());
+        };
+        if let Err(err) = task_tree::check_can_finish(&self.tasks, &before, &status, &self.workflow) {
+            self.status_message = Some(err);
+            return Ok(());
+        }
 
-        if !prev_column_tasks.is_empty() {
-            self.selected_task_index = Some(prev_column_tasks[0]);
+        let mut after = before.clone();
+        after.set_status(status.clone());
+        let description = format!("Move \"{}\" to {}", after.title, status);
+        self.save_update(description, before, after)
+    }
+
+    /// Saves an edited task as one undoable command. Moving a task to done stops
+    /// its timer, and finishing a recurring task also adds its next occurrence.
+    fn save_update(&mut self, description: String, before: Task, mut after: Task) -> Result<()> {
+        let now = Utc::now();
+        if let Some(spent) = timers::stop_when_done(&before, &mut after, &self.workflow, now) {
+            self.status_message = Some(format!("Stopped timer on \"{}\" after {}", after.title, stats::format_duration(spent)));
+        }
+        let next = recurrence::next_occurrence(&before, &mut after, &self.workflow, now);
+        let mut changes = vec![Change::Updated { before: Box::new(before), after: Box::new(after) }];
+        if let Some(next) = next {
+            let due = next.due_date.map(utils::format_date);
+            self.status_message = Some(format!("Next \"{}\" is due {}", next.title, due.unwrap_or_default()));
+            changes.push(Change::Added(next));
         }
+        self.execute(Command::new(description, changes))
     }
-}
\ No newline at end of file
+
+    // Navigates to the first task of the next non-empty column in Kanban view
+    pub fn next_column(&mut self) {
+        if self.mode != AppMode::Kanban {
+            return;
+        }
+        if let Some(next) = self.nearest_column(self.selected_column(), true) {
+            self.selected_task_index = Some(next[0]);
+        }
+    }
+
+    // Navigates to the first task of the previous non-empty column in Kanban view
+    pub fn previous_column(&mut self) {
+        if self.mode != AppMode::Kanban {
+            return;
+        }
+        if let Some(prev) = self.nearest_column(self.selected_column(), false) {
+            self.selected_task_index = Some(prev[0]);
+        }
+    }
+}
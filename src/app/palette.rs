@@ -0,0 +1,463 @@
+use anyhow::Result;
+use std::path::Path;
+use strum::IntoEnumIterator;
+
+use crate::app::{App, AppMode, AppTab, InputMode, SortKey};
+use crate::config;
+use crate::models::recurrence::Recurrence;
+use crate::models::{dependencies, ChecklistItem, TaskPriority, TaskStatus};
+use crate::storage::export::{self, ExportFormat};
+use crate::storage::{projects, storage_manager};
+use crate::utils;
+
+pub const COMMAND_HISTORY_FILE: &str = "command_history.json";
+
+/// A command the `:` palette knows, with its arguments and what it does.
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub args: &'static str,
+    pub description: &'static str,
+}
+
+/// Every palette command, in the order the palette lists them.
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec { name: "new", args: "<title>", description: "Create a task" },
+    CommandSpec { name: "subtask", args: "<title>", description: "Create a subtask of the selected task" },
+    CommandSpec { name: "edit", args: "", description: "Open the edit form" },
+    CommandSpec { name: "delete", args: "", description: "Move the selected task to the trash" },
+    CommandSpec { name: "move", args: "<status>", description: "Move the selected task to a status" },
+    CommandSpec { name: "next", args: "", description: "Move the selected task to its next status" },
+    CommandSpec { name: "priority", args: "<low|medium|high|critical>", description: "Set the priority" },
+    CommandSpec { name: "tag", args: "add|rm <tag>", description: "Add or remove a tag" },
+    CommandSpec { name: "due", args: "<YYYY-MM-DD|none>", description: "Set or clear the due date" },
+    CommandSpec { name: "points", args: "<n|none>", description: "Set or clear the story points" },
+    CommandSpec { name: "estimate", args: "<90m|2h|none>", description: "Set or clear the estimate" },
+    CommandSpec { name: "repeat", args: "<rule|none>", description: "Set or clear the repeat rule" },
+    CommandSpec { name: "check", args: "<item>", description: "Add a checklist item" },
+    CommandSpec { name: "block", args: "<id>", description: "Make the task wait on another task" },
+    CommandSpec { name: "unblock", args: "<id>", description: "Drop a prerequisite" },
+    CommandSpec { name: "timer", args: "[note]", description: "Start or stop the timer" },
+    CommandSpec { name: "undo", args: "", description: "Undo the last change" },
+    CommandSpec { name: "redo", args: "", description: "Redo the last undone change" },
+    CommandSpec { name: "filter", args: "<query|off>", description: "Filter the board with a query" },
+    CommandSpec { name: "view", args: "<name>|save <name>", description: "Open a saved view, or save the filter" },
+    CommandSpec { name: "search", args: "<text|off>", description: "Search titles, descriptions and tags" },
+    CommandSpec { name: "sort", args: "<priority|due|title|created|status|off>", description: "Order the tasks" },
+    CommandSpec { name: "export", args: "<csv|json> <file>", description: "Write the shown tasks to a file" },
+    CommandSpec { name: "project", args: "<name>|new <name>|move <name>", description: "Switch, create or move to a project" },
+    CommandSpec { name: "tab", args: "<tasks|calendar|statistics|trash>", description: "Go to a tab" },
+    CommandSpec { name: "mode", args: "<kanban|list|detail>", description: "Switch the task view" },
+    CommandSpec { name: "theme", args: "[name]", description: "Switch theme, or pick one from the list" },
+    CommandSpec { name: "find", args: "", description: "Open the fuzzy task finder" },
+    CommandSpec { name: "details", args: "", description: "Toggle the task details" },
+    CommandSpec { name: "graph", args: "", description: "Show the dependency graph" },
+    CommandSpec { name: "empty-trash", args: "", description: "Delete everything in the trash forever" },
+    CommandSpec { name: "help", args: "", description: "Toggle help" },
+    CommandSpec { name: "quit", args: "", description: "Quit TaskX" },
+];
+
+/// The `:` command line while it is open.
+pub struct CommandPalette {
+    pub input: String,
+    /// Highlighted completion, cycled with Tab.
+    pub selected: usize,
+    /// Position in the history while browsing it with Up and Down; `None` when typing.
+    pub history_index: Option<usize>,
+}
+
+/// Something the word under the cursor can be completed to.
+pub struct Completion {
+    pub text: String,
+    pub hint: String,
+}
+
+impl App {
+    pub fn open_palette(&mut self) {
+        self.palette = Some(CommandPalette { input: String::new(), selected: 0, history_index: None });
+        self.input_mode = InputMode::Command;
+    }
+
+    pub fn close_palette(&mut self) {
+        self.palette = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn palette_input_char(&mut self, c: char) {
+        if let Some(palette) = self.palette.as_mut() {
+            palette.input.push(c);
+            palette.selected = 0;
+        }
+    }
+
+    pub fn palette_backspace(&mut self) {
+        if let Some(palette) = self.palette.as_mut() {
+            palette.input.pop();
+            palette.selected = 0;
+        }
+    }
+
+    pub fn browse_command_history(&mut self, older: bool) {
+        let Some(palette) = self.palette.as_mut() else {
+            return;
+        };
+        if palette.history_index.is_none() && !older {
+            return;
+        }
+        palette.history_index = self.command_history.browse(palette.history_index, older);
+        palette.input = self.command_history.entry(palette.history_index);
+        palette.selected = 0;
+    }
+
+    /// Replaces the word being typed with the highlighted completion; pressed
+    /// again, moves on to the next one.
+    pub fn complete_palette(&mut self, forward: bool) {
+        let completions = self.palette_completions();
+        let Some(palette) = self.palette.as_mut() else {
+            return;
+        };
+        if completions.is_empty() {
+            return;
+        }
+
+        let count = completions.len();
+        let current = palette.selected % count;
+        let word_start = palette.input.rfind(' ').map_or(0, |i| i + 1);
+        // Once the word is complete, Tab cycles through the other candidates
+        let index = if palette.input[word_start..] == completions[current].text {
+            if forward { (current + 1) % count } else { (current + count - 1) % count }
+        } else {
+            current
+        };
+        palette.selected = index;
+        palette.input.truncate(word_start);
+        palette.input.push_str(&completions[index].text);
+    }
+
+    /// Candidates for the word being typed: command names first, then the values
+    /// a command's argument can take.
+    pub fn palette_completions(&self) -> Vec<Completion> {
+        let Some(palette) = self.palette.as_ref() else {
+            return Vec::new();
+        };
+        let words: Vec<&str> = palette.input.split(' ').collect();
+        let typed = words.last().copied().unwrap_or("").to_lowercase();
+
+        if words.len() <= 1 {
+            return COMMANDS
+                .iter()
+                .filter(|spec| spec.name.starts_with(&typed))
+                .map(|spec| Completion { text: spec.name.to_string(), hint: format!("{}  {}", spec.args, spec.description) })
+                .collect();
+        }
+
+        let choices: Vec<String> = match (words[0], words.len()) {
+            ("move", 2) => self.workflow.statuses().map(|status| status.to_string()).collect(),
+            ("priority", 2) => TaskPriority::iter().map(|p| p.to_string().to_lowercase()).collect(),
+            ("tag", 2) => vec!["add".to_string(), "rm".to_string()],
+            ("tag", 3) if words[1] == "rm" => self.selected_task().map_or_else(Vec::new, |task| task.tags.clone()),
+            ("sort", 2) => ["priority", "due", "title", "created", "status", "off"].map(String::from).to_vec(),
+            ("export", 2) => vec!["csv".to_string(), "json".to_string()],
+            ("tab", 2) => ["tasks", "calendar", "statistics", "trash"].map(String::from).to_vec(),
+            ("mode", 2) => ["kanban", "list", "detail"].map(String::from).to_vec(),
+            ("theme", 2) => config::themes::names(),
+            ("filter" | "search" | "due" | "points" | "estimate" | "repeat", 2) => vec!["off".to_string()],
+            ("view", 2) => {
+                let mut names: Vec<String> = self.views.views.iter().map(|view| view.name.clone()).collect();
+                names.push("save".to_string());
+                names
+            }
+            ("project", 2) => {
+                let mut names = self.project_names();
+                names.extend(["new".to_string(), "move".to_string()]);
+                names
+            }
+            ("project", 3) if words[1] == "move" => self.project_names(),
+            _ => Vec::new(),
+        };
+        choices
+            .into_iter()
+            .filter(|choice| choice.to_lowercase().starts_with(&typed))
+            .map(|text| Completion { text, hint: String::new() })
+            .collect()
+    }
+
+    /// Runs the palette's command line; returns true when it asks to quit.
+    pub fn submit_palette(&mut self) -> Result<bool> {
+        let Some(palette) = self.palette.take() else {
+            return Ok(false);
+        };
+        self.input_mode = InputMode::Normal;
+        let line = palette.input.trim().to_string();
+        if line.is_empty() {
+            return Ok(false);
+        }
+
+        self.command_history.push(&line);
+        self.command_history.save(&storage_manager::get_data_dir()?, COMMAND_HISTORY_FILE)?;
+        self.run_command(&line)
+    }
+
+    /// Runs one palette command such as `priority high` or `tag add backend`.
+    /// Mistakes and failures end up in the status bar, as with the other prompts.
+    pub fn run_command(&mut self, line: &str) -> Result<bool> {
+        let line = line.trim().trim_start_matches(':');
+        let (name, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let rest = rest.trim();
+        let Some(spec) = COMMANDS.iter().find(|spec| spec.name == name.to_lowercase()) else {
+            self.status_message = Some(format!("Unknown command \"{}\" (: and Tab list them)", name));
+            return Ok(false);
+        };
+
+        let needs_task = match spec.name {
+            "project" => rest.starts_with("move"),
+            "new" | "undo" | "redo" | "filter" | "view" | "search" | "sort" | "export" | "tab" | "mode" | "theme" | "find"
+            | "empty-trash" | "help" | "quit" => false,
+            _ => true,
+        };
+        if needs_task && self.selected_task().is_none() {
+            self.status_message = Some(format!(":{} needs a selected task", spec.name));
+            return Ok(false);
+        }
+        if let Err(err) = self.dispatch(spec, rest) {
+            self.status_message = Some(err);
+        }
+        Ok(spec.name == "quit")
+    }
+
+    /// Carries out a command whose name is known; `Err` holds a message for the user.
+    fn dispatch(&mut self, spec: &CommandSpec, rest: &str) -> Result<(), String> {
+        let usage = || format!("Usage: :{} {}", spec.name, spec.args);
+        let required = |value: &str| if value.is_empty() { Err(usage()) } else { Ok(value.to_string()) };
+        let task_id = self.selected_task().map(|task| task.id.clone()).unwrap_or_default();
+
+        match spec.name {
+            "new" => {
+                let title = required(rest)?;
+                self.add_task(title, String::new(), self.workflow.initial(), config::current().default_priority()).map_err(failed)?;
+            }
+            "subtask" => self.add_subtask(&task_id, &required(rest)?).map_err(failed)?,
+            "edit" => self.open_edit_form(),
+            "delete" => self.delete_selected_task().map_err(failed)?,
+            "move" => {
+                let status: TaskStatus = self.workflow.resolve(&required(rest)?)?;
+                let from = self.selected_task().map(|task| task.status.clone()).unwrap_or_else(|| status.clone());
+                if !self.workflow.can_transition(&from, &status) {
+                    return Err(format!("{} cannot move to {}", from, status));
+                }
+                self.set_selected_status(status).map_err(failed)?;
+            }
+            "next" => self.move_task_status().map_err(failed)?,
+            "priority" => {
+                let priority: TaskPriority = required(rest)?.parse()?;
+                self.update_task(&task_id, |task| {
+                    task.priority = priority;
+                    format!("Set priority of \"{}\" to {}", task.title, priority)
+                })
+                .map_err(failed)?;
+            }
+            "tag" => {
+                let (action, tag) = rest.split_once(' ').map(|(a, t)| (a, t.trim())).ok_or_else(usage)?;
+                let tag = required(tag)?;
+                match action {
+                    "add" => self.update_task(&task_id, |task| {
+                        if !task.tags.contains(&tag) {
+                            task.tags.push(tag.clone());
+                        }
+                        format!("Tag \"{}\" with {}", task.title, tag)
+                    }),
+                    "rm" => self.update_task(&task_id, |task| {
+                        task.tags.retain(|t| *t != tag);
+                        format!("Untag {} from \"{}\"", tag, task.title)
+                    }),
+                    _ => return Err(usage()),
+                }
+                .map_err(failed)?;
+            }
+            "due" => {
+                let due = match required(rest)?.as_str() {
+                    "none" | "off" => None,
+                    date => Some(utils::parse_due_date(date)?),
+                };
+                self.update_task(&task_id, |task| {
+                    task.due_date = due;
+                    format!("Set due date of \"{}\"", task.title)
+                })
+                .map_err(failed)?;
+            }
+            "points" => {
+                let points = match required(rest)?.as_str() {
+                    "none" | "off" => None,
+                    n => Some(n.parse::<u32>().map_err(|_| format!("\"{}\" is not a number of points", n))?),
+                };
+                self.update_task(&task_id, |task| {
+                    task.points = points;
+                    format!("Set points of \"{}\"", task.title)
+                })
+                .map_err(failed)?;
+            }
+            "estimate" => {
+                let estimate = match required(rest)?.as_str() {
+                    "none" | "off" => None,
+                    value => Some(utils::parse_estimate(value)?),
+                };
+                self.update_task(&task_id, |task| {
+                    task.estimate_minutes = estimate;
+                    format!("Set estimate of \"{}\"", task.title)
+                })
+                .map_err(failed)?;
+            }
+            "repeat" => {
+                let rule = match required(rest)?.as_str() {
+                    "none" | "off" => None,
+                    rule => Some(rule.parse::<Recurrence>()?),
+                };
+                self.update_task(&task_id, |task| {
+                    task.recurrence = rule;
+                    format!("Set repeat rule of \"{}\"", task.title)
+                })
+                .map_err(failed)?;
+            }
+            "check" => {
+                let text = required(rest)?;
+                self.update_task(&task_id, |task| {
+                    task.checklist.push(ChecklistItem { text: text.clone(), done: false });
+                    format!("Add \"{}\" to \"{}\"", text, task.title)
+                })
+                .map_err(failed)?;
+            }
+            "block" => {
+                let ids = dependencies::resolve_prerequisites(&self.tasks, &task_id, &required(rest)?)?;
+                self.update_task(&task_id, |task| {
+                    for id in ids {
+                        if !task.blocked_by.contains(&id) {
+                            task.blocked_by.push(id);
+                        }
+                    }
+                    format!("Block \"{}\"", task.title)
+                })
+                .map_err(failed)?;
+            }
+            "unblock" => {
+                let prefix = required(rest)?;
+                let task = self.selected_task().ok_or_else(usage)?;
+                let matches: Vec<String> = task.blocked_by.iter().filter(|id| id.starts_with(&prefix)).cloned().collect();
+                let [id] = matches.as_slice() else {
+                    return Err(format!("\"{}\" does not match exactly one prerequisite", prefix));
+                };
+                let id = id.clone();
+                self.update_task(&task_id, |task| {
+                    task.blocked_by.retain(|b| *b != id);
+                    format!("Unblock \"{}\"", task.title)
+                })
+                .map_err(failed)?;
+            }
+            "timer" => self.toggle_timer_on(&task_id, rest).map_err(failed)?,
+            "undo" => self.undo().map_err(failed)?,
+            "redo" => self.redo().map_err(failed)?,
+            "filter" => match required(rest)?.as_str() {
+                "off" => {
+                    self.filter_active = false;
+                    self.reselect(None);
+                }
+                query => self.apply_filter(query),
+            },
+            "view" => match rest.split_once(' ') {
+                Some(("save", name)) => self.save_view(name).map_err(failed)?,
+                _ => {
+                    let name = required(rest)?;
+                    let index = self
+                        .views
+                        .views
+                        .iter()
+                        .position(|view| view.name.eq_ignore_ascii_case(&name))
+                        .ok_or_else(|| format!("No view named \"{}\"", name))?;
+                    if !self.views.views[index].pinned {
+                        self.views.set_pinned(&name, true).map_err(failed)?;
+                        self.views.save(self.storage.data_dir()).map_err(failed)?;
+                    }
+                    self.open_view(Some(index));
+                }
+            },
+            "search" => match required(rest)?.as_str() {
+                "off" => self.clear_search(),
+                text => {
+                    self.search_query = text.to_string();
+                    self.reselect(None);
+                }
+            },
+            "sort" => {
+                self.sort = match required(rest)?.as_str() {
+                    "off" | "none" => None,
+                    key => Some(key.parse::<SortKey>()?),
+                };
+                self.reselect(None);
+            }
+            "export" => {
+                let (format, file) = rest.split_once(' ').ok_or_else(usage)?;
+                let format: ExportFormat = format.parse()?;
+                let tasks = self.filtered_tasks();
+                let count = tasks.len();
+                export::export(&tasks, format, Path::new(file.trim())).map_err(failed)?;
+                self.status_message = Some(format!("Exported {} tasks to {}", count, file.trim()));
+            }
+            "project" => match rest.split_once(' ') {
+                Some(("new", name)) => self.create_project(name.trim()).map_err(failed)?,
+                Some(("move", name)) => {
+                    let name = name.trim().to_string();
+                    self.open_project_switcher(true).map_err(failed)?;
+                    if let Some(switcher) = self.project_switcher.as_mut() {
+                        match switcher.projects.iter().position(|p| *p == name) {
+                            Some(index) => switcher.index = index,
+                            None => {
+                                self.close_project_switcher();
+                                return Err(format!("No project named \"{}\"", name));
+                            }
+                        }
+                    }
+                    self.confirm_project_switcher().map_err(failed)?;
+                }
+                _ => self.switch_project(&required(rest)?).map_err(failed)?,
+            },
+            "tab" => match required(rest)?.to_lowercase().as_str() {
+                "tasks" => self.open_view(None),
+                "calendar" => self.current_tab = AppTab::Calendar,
+                "statistics" | "stats" => self.current_tab = AppTab::Statistics,
+                "trash" => self.current_tab = AppTab::Trash,
+                _ => return Err(usage()),
+            },
+            "mode" => match required(rest)?.to_lowercase().as_str() {
+                "kanban" => self.set_mode(AppMode::Kanban),
+                "list" => self.set_mode(AppMode::List),
+                "detail" => self.set_mode(AppMode::Normal),
+                _ => return Err(usage()),
+            },
+            "theme" if rest.is_empty() => self.open_theme_switcher(),
+            "theme" => self.set_theme(rest).map_err(failed)?,
+            "find" => self.open_finder(),
+            "details" => {
+                self.show_task_details = !self.show_task_details;
+                self.checklist_index = 0;
+            }
+            "graph" => {
+                self.show_task_details = true;
+                self.show_dependency_graph = true;
+            }
+            "empty-trash" => self.empty_trash().map_err(failed)?,
+            "help" => self.toggle_help(),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+impl App {
+    fn project_names(&self) -> Vec<String> {
+        storage_manager::get_data_dir()
+            .and_then(|root| projects::list(&root))
+            .unwrap_or_default()
+    }
+}
+
+fn failed(err: anyhow::Error) -> String {
+    format!("{:#}", err)
+}
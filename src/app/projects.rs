@@ -0,0 +1,142 @@
+use anyhow::Result;
+use std::collections::HashSet;
+
+use crate::app::subtasks::InputTarget;
+use crate::app::{App, InputMode};
+use crate::storage::projects::{self, DEFAULT_PROJECT};
+use crate::storage::{self, storage_manager};
+
+/// The project popup, either to switch boards or to pick where a task goes.
+pub struct ProjectSwitcher {
+    pub projects: Vec<String>,
+    pub index: usize,
+    /// Id of the task being moved; `None` when switching boards.
+    pub moving: Option<String>,
+}
+
+impl App {
+    /// Opens the project popup with the current project selected. With `moving`
+    /// set, choosing a project moves the selected task there instead of switching.
+    pub fn open_project_switcher(&mut self, moving: bool) -> Result<()> {
+        let moving = if moving {
+            let Some(task) = self.selected_task() else {
+                return Ok(());
+            };
+            Some(task.id.clone())
+        } else {
+            None
+        };
+
+        let projects = projects::list(&storage_manager::get_data_dir()?)?;
+        let index = projects.iter().position(|name| *name == self.project).unwrap_or(0);
+        self.project_switcher = Some(ProjectSwitcher { projects, index, moving });
+        Ok(())
+    }
+
+    pub fn close_project_switcher(&mut self) {
+        self.project_switcher = None;
+    }
+
+    pub fn select_project(&mut self, forward: bool) {
+        if let Some(switcher) = self.project_switcher.as_mut() {
+            let count = switcher.projects.len();
+            switcher.index = if forward { (switcher.index + 1) % count } else { (switcher.index + count - 1) % count };
+        }
+    }
+
+    /// Switches to the highlighted project, or moves the task there.
+    pub fn confirm_project_switcher(&mut self) -> Result<()> {
+        let Some(switcher) = self.project_switcher.take() else {
+            return Ok(());
+        };
+        let name = switcher.projects[switcher.index].clone();
+        match switcher.moving {
+            Some(task_id) => self.move_task_to_project(&task_id, &name),
+            None => self.switch_project(&name),
+        }
+    }
+
+    /// Opens the input bar to name a new project. Not offered while moving a task.
+    pub fn start_project_input(&mut self) {
+        if self.project_switcher.as_ref().is_some_and(|switcher| switcher.moving.is_some()) {
+            return;
+        }
+        self.project_switcher = None;
+        self.input_target = InputTarget::NewProject;
+        self.new_task_input.clear();
+        self.input_mode = InputMode::Editing;
+    }
+
+    pub fn create_project(&mut self, name: &str) -> Result<()> {
+        if let Err(err) = projects::create(&storage_manager::get_data_dir()?, name) {
+            self.status_message = Some(format!("{:#}", err));
+            return Ok(());
+        }
+        self.switch_project(name)
+    }
+
+    /// Replaces the board with another project's tasks, workflow and undo history.
+    pub fn switch_project(&mut self, name: &str) -> Result<()> {
+        if name == self.project {
+            return Ok(());
+        }
+        let storage = match storage::open(name) {
+            Ok(storage) => storage,
+            Err(err) => {
+                self.status_message = Some(format!("{:#}", err));
+                return Ok(());
+            }
+        };
+
+        self.storage = storage;
+        self.project = name.to_string();
+        self.selected_task_index = None;
+        self.selected_trash_index = None;
+        self.collapsed = HashSet::new();
+        self.filter = Default::default();
+        self.filter_text.clear();
+        self.filter_active = false;
+        self.show_task_details = false;
+        self.load_tasks()?;
+        if self.status_message.is_none() {
+            self.status_message = Some(format!("Switched to project {}", name));
+        }
+        Ok(())
+    }
+
+    /// Moves a task to another project's board. Moves are not undoable, since the
+    /// undo history belongs to a single project, and undoing earlier changes to the
+    /// task here would bring it back, so those are dropped from the history.
+    fn move_task_to_project(&mut self, task_id: &str, name: &str) -> Result<()> {
+        if name == self.project {
+            return Ok(());
+        }
+        let Some(task) = self.tasks.iter().find(|task| task.id == task_id).cloned() else {
+            return Ok(());
+        };
+
+        let mut target = match storage::open(name) {
+            Ok(target) => target,
+            Err(err) => {
+                self.status_message = Some(format!("{:#}", err));
+                return Ok(());
+            }
+        };
+        let workflow = storage::load_workflow(target.data_dir())?;
+        projects::move_task(self.storage.as_mut(), target.as_mut(), &task, &workflow)?;
+        self.reload_journal();
+        self.journal.forget(&task.id);
+        self.journal.save(self.storage.data_dir())?;
+
+        self.tasks.retain(|t| t.id != task.id);
+        self.show_task_details = false;
+        self.clamp_selections();
+        self.status_message = Some(format!("Moved \"{}\" to project {}", task.title, name));
+        Ok(())
+    }
+
+    /// Whether the board shows the project kept directly in the data directory.
+    pub fn in_default_project(&self) -> bool {
+        self.project == DEFAULT_PROJECT
+    }
+}
@@ -0,0 +1,109 @@
+use anyhow::Result;
+
+use crate::app::{App, InputMode};
+use crate::storage::storage_manager;
+
+pub const SEARCH_HISTORY_FILE: &str = "search_history.json";
+
+/// The `/` prompt while it is open.
+pub struct SearchPrompt {
+    /// Query to go back to when the search is cancelled.
+    pub previous_query: String,
+    /// Position in the history while browsing it with Up and Down; `None` when typing.
+    pub history_index: Option<usize>,
+}
+
+impl App {
+    /// Opens the `/` prompt. The board filters live as the query is typed.
+    pub fn start_search(&mut self) {
+        self.search_prompt = Some(SearchPrompt { previous_query: self.search_query.clone(), history_index: None });
+        self.input_mode = InputMode::Search;
+    }
+
+    pub fn search_input_char(&mut self, c: char) {
+        let mut query = self.search_query.clone();
+        query.push(c);
+        self.set_search_query(query);
+    }
+
+    pub fn search_backspace(&mut self) {
+        let mut query = self.search_query.clone();
+        query.pop();
+        self.set_search_query(query);
+    }
+
+    /// Keeps the query as the active search and remembers it.
+    pub fn confirm_search(&mut self) -> Result<()> {
+        self.search_prompt = None;
+        self.input_mode = InputMode::Normal;
+        let query = self.search_query.trim().to_string();
+        if query.is_empty() {
+            self.set_search_query(String::new());
+            return Ok(());
+        }
+
+        self.search_history.push(&query);
+        self.search_history.save(&storage_manager::get_data_dir()?, SEARCH_HISTORY_FILE)?;
+        self.status_message = Some(self.match_summary());
+        Ok(())
+    }
+
+    /// Closes the prompt and goes back to the search that was active before it.
+    pub fn cancel_search(&mut self) {
+        self.input_mode = InputMode::Normal;
+        if let Some(prompt) = self.search_prompt.take() {
+            self.set_search_query(prompt.previous_query);
+        }
+    }
+
+    /// Drops the active search so the whole board shows again.
+    pub fn clear_search(&mut self) {
+        self.set_search_query(String::new());
+        self.status_message = Some("Search cleared".to_string());
+    }
+
+    /// Steps through earlier queries (`older`) or back towards the newest one.
+    pub fn browse_search_history(&mut self, older: bool) {
+        let Some(prompt) = self.search_prompt.as_mut() else {
+            return;
+        };
+        if prompt.history_index.is_none() && !older {
+            return;
+        }
+        prompt.history_index = self.search_history.browse(prompt.history_index, older);
+        let query = self.search_history.entry(prompt.history_index);
+        self.set_search_query(query);
+    }
+
+    /// Selects the next (or previous) task matching the active search, in the
+    /// order the current view shows them.
+    pub fn jump_to_match(&mut self, forward: bool) {
+        if forward {
+            self.select_next_task();
+        } else {
+            self.select_previous_task();
+        }
+        self.status_message = Some(self.match_summary());
+    }
+
+    pub fn is_searching(&self) -> bool {
+        !self.search_query.is_empty()
+    }
+
+    /// Replaces the query, keeping the selected task selected if it still matches.
+    fn set_search_query(&mut self, query: String) {
+        let selected_id = self.selected_task().map(|task| task.id.clone());
+        self.search_query = query;
+        self.reselect(selected_id);
+    }
+
+    /// "Match 2 of 5 for \"query\"", or a note that nothing matched.
+    fn match_summary(&self) -> String {
+        let count = self.filtered_tasks().len();
+        match self.selected_task_index {
+            _ if count == 0 => format!("No tasks match \"{}\"", self.search_query),
+            Some(index) => format!("Match {} of {} for \"{}\" (n/N to jump, Esc to clear)", index + 1, count, self.search_query),
+            None => format!("{} tasks match \"{}\"", count, self.search_query),
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use anyhow::Result;
+use std::collections::HashSet;
+
+use crate::app::undo::{Change, Command};
+use crate::app::{App, InputMode};
+use crate::config;
+use crate::models::{subtasks, ChecklistItem, Task};
+
+/// What the input bar's text becomes when Enter is pressed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputTarget {
+    NewTask,
+    /// A subtask of the task with this id.
+    Subtask(String),
+    /// A checklist item on the task with this id.
+    ChecklistItem(String),
+    /// The note of a timer started or stopped on the task with this id.
+    TimerNote(String),
+    /// The name of a new project.
+    NewProject,
+    /// A filter query.
+    Filter,
+    /// The name to save the current filter under.
+    ViewName,
+}
+
+impl App {
+    /// Filtered tasks as a tree, each with its depth: subtasks follow their parent,
+    /// and collapsed parents hide everything below them. A subtask whose parent is
+    /// filtered out is shown at the top level.
+    pub fn tree_rows(&self) -> Vec<(&Task, usize)> {
+        let tasks = self.matching_tasks();
+        let visible: HashSet<&str> = tasks.iter().map(|task| task.id.as_str()).collect();
+        let is_root = |task: &Task| task.parent_id.as_deref().is_none_or(|id| !visible.contains(id));
+
+        let mut rows = Vec::new();
+        let mut placed: HashSet<&str> = HashSet::new();
+        let mut stack: Vec<(&Task, usize)> = tasks.iter().rev().filter(|t| is_root(t)).map(|&t| (t, 0)).collect();
+
+        while let Some((task, depth)) = stack.pop() {
+            if !placed.insert(task.id.as_str()) {
+                continue;
+            }
+            rows.push((task, depth));
+            if !self.collapsed.contains(&task.id) {
+                let children = tasks.iter().rev().filter(|t| t.parent_id.as_deref() == Some(task.id.as_str()));
+                stack.extend(children.map(|&child| (child, depth + 1)));
+            }
+        }
+
+        // Tasks caught in a parent cycle have no root; list them rather than lose them
+        let hidden: HashSet<&str> = rows
+            .iter()
+            .filter(|(task, _)| self.collapsed.contains(&task.id))
+            .flat_map(|(task, _)| self.descendant_ids(task))
+            .collect();
+        for &task in &tasks {
+            if !placed.contains(task.id.as_str()) && !hidden.contains(task.id.as_str()) {
+                placed.insert(task.id.as_str());
+                rows.push((task, 0));
+            }
+        }
+        rows
+    }
+
+    fn descendant_ids<'a>(&'a self, task: &'a Task) -> Vec<&'a str> {
+        let mut ids = Vec::new();
+        let mut stack = vec![task.id.as_str()];
+        while let Some(id) = stack.pop() {
+            for child in subtasks::children(&self.tasks, id) {
+                if !ids.contains(&child.id.as_str()) {
+                    ids.push(child.id.as_str());
+                    stack.push(child.id.as_str());
+                }
+            }
+        }
+        ids
+    }
+
+    pub fn has_subtasks(&self, task: &Task) -> bool {
+        subtasks::children(&self.tasks, &task.id).next().is_some()
+    }
+
+    /// Finished and total checklist items and subtasks of `task`.
+    pub fn progress(&self, task: &Task) -> Option<(usize, usize)> {
+        subtasks::progress(&self.tasks, task, &self.workflow)
+    }
+
+    /// Folds the selected task's subtasks away, or moves up to its parent
+    /// when there is nothing to fold.
+    pub fn collapse_selected(&mut self) {
+        let Some(task) = self.selected_task() else {
+            return;
+        };
+        let id = task.id.clone();
+        if self.has_subtasks(task) && !self.collapsed.contains(&id) {
+            self.collapsed.insert(id);
+        } else if let Some(parent_id) = task.parent_id.clone() {
+            self.select_task_by_id(&parent_id);
+        }
+    }
+
+    pub fn expand_selected(&mut self) {
+        if let Some(task) = self.selected_task() {
+            let id = task.id.clone();
+            self.collapsed.remove(&id);
+        }
+    }
+
+    /// Opens the input bar to type a subtask of the selected task.
+    pub fn start_subtask_input(&mut self) {
+        if let Some(task) = self.selected_task() {
+            self.input_target = InputTarget::Subtask(task.id.clone());
+            self.new_task_input.clear();
+            self.input_mode = InputMode::Editing;
+        }
+    }
+
+    /// Opens the input bar to type a checklist item for the selected task.
+    pub fn start_checklist_input(&mut self) {
+        if let Some(task) = self.selected_task() {
+            self.input_target = InputTarget::ChecklistItem(task.id.clone());
+            self.new_task_input.clear();
+            self.input_mode = InputMode::Editing;
+        }
+    }
+
+    /// Turns the input bar's text into whatever `input_target` asks for.
+    pub fn submit_input(&mut self) -> Result<()> {
+        let text = self.new_task_input.trim().to_string();
+        match std::mem::replace(&mut self.input_target, InputTarget::NewTask) {
+            InputTarget::NewTask => {
+                self.add_task(text, String::new(), self.workflow.initial(), config::current().default_priority())?;
+            }
+            InputTarget::Subtask(parent_id) => self.add_subtask(&parent_id, &text)?,
+            InputTarget::ChecklistItem(task_id) => {
+                self.update_task(&task_id, |task| {
+                    task.checklist.push(ChecklistItem { text: text.clone(), done: false });
+                    format!("Add \"{}\" to \"{}\"", text, task.title)
+                })?;
+                if let Some(task) = self.tasks.iter().find(|task| task.id == task_id) {
+                    self.checklist_index = task.checklist.len().saturating_sub(1);
+                }
+            }
+            InputTarget::TimerNote(task_id) => self.toggle_timer_on(&task_id, &text)?,
+            InputTarget::NewProject => self.create_project(&text)?,
+            InputTarget::Filter => self.apply_filter(&text),
+            InputTarget::ViewName => self.save_view(&text)?,
+        }
+        Ok(())
+    }
+
+    pub fn add_subtask(&mut self, parent_id: &str, title: &str) -> Result<()> {
+        let mut task = Task::new(title, "", self.workflow.initial(), config::current().default_priority());
+        task.parent_id = Some(parent_id.to_string());
+        let description = format!("Add subtask \"{}\"", task.title);
+        self.execute(Command::new(description, vec![Change::Added(task)]))?;
+        // Make sure the new subtask is visible under its parent
+        self.collapsed.remove(parent_id);
+        Ok(())
+    }
+
+    pub fn select_checklist_item(&mut self, forward: bool) {
+        let count = self.selected_task().map_or(0, |task| task.checklist.len());
+        if count == 0 {
+            return;
+        }
+        self.checklist_index = if forward {
+            (self.checklist_index + 1) % count
+        } else {
+            (self.checklist_index + count - 1) % count
+        };
+    }
+
+    pub fn toggle_checklist_item(&mut self) -> Result<()> {
+        let index = self.checklist_index;
+        let Some(id) = self.selected_checklist_task() else {
+            return Ok(());
+        };
+        self.update_task(&id, |task| {
+            let item = &mut task.checklist[index];
+            item.done = !item.done;
+            format!("{} \"{}\"", if item.done { "Check" } else { "Uncheck" }, item.text)
+        })
+    }
+
+    pub fn remove_checklist_item(&mut self) -> Result<()> {
+        let index = self.checklist_index;
+        let Some(id) = self.selected_checklist_task() else {
+            return Ok(());
+        };
+        self.update_task(&id, |task| {
+            let item = task.checklist.remove(index);
+            format!("Remove \"{}\" from \"{}\"", item.text, task.title)
+        })?;
+        self.checklist_index = self.checklist_index.saturating_sub(1);
+        Ok(())
+    }
+
+    /// Id of the selected task if the checklist cursor points at one of its items.
+    fn selected_checklist_task(&self) -> Option<String> {
+        self.selected_task()
+            .filter(|task| self.checklist_index < task.checklist.len())
+            .map(|task| task.id.clone())
+    }
+
+    /// Applies `edit` to a copy of the task and records it as one undoable change.
+    /// `edit` returns the description shown in the undo history.
+    pub(crate) fn update_task(&mut self, id: &str, edit: impl FnOnce(&mut Task) -> String) -> Result<()> {
+        let Some(before) = self.tasks.iter().find(|task| task.id == id).cloned() else {
+            return Ok(());
+        };
+        let mut after = before.clone();
+        let description = edit(&mut after);
+        after.record_changes(&before);
+        self.execute(Command::new(description, vec![Change::Updated { before: Box::new(before), after: Box::new(after) }]))
+    }
+}
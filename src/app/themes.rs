@@ -0,0 +1,104 @@
+use anyhow::Result;
+
+use crate::app::App;
+use crate::config::{self, Source};
+use crate::models::theme::Theme;
+
+/// The theme popup. Moving through it previews each theme on the whole board.
+pub struct ThemeSwitcher {
+    pub names: Vec<String>,
+    pub index: usize,
+    /// The theme in use when the popup opened, put back when it is closed.
+    pub previous: Theme,
+}
+
+impl App {
+    /// Loads the theme the settings name; returns a warning and uses the default
+    /// theme when it cannot be read.
+    pub fn load_theme(&mut self) -> Option<String> {
+        let name = config::current().theme().to_string();
+        match config::themes::load(&name) {
+            Ok(theme) => {
+                self.theme = theme;
+                None
+            }
+            Err(err) => {
+                self.theme = Theme::default();
+                Some(format!("{:#}; using the dark theme", err))
+            }
+        }
+    }
+
+    pub fn open_theme_switcher(&mut self) {
+        let names = config::themes::names();
+        let index = names.iter().position(|name| *name == self.theme.name).unwrap_or(0);
+        self.theme_switcher = Some(ThemeSwitcher { names, index, previous: self.theme.clone() });
+    }
+
+    /// Closes the popup and goes back to the theme it started with.
+    pub fn close_theme_switcher(&mut self) {
+        if let Some(switcher) = self.theme_switcher.take() {
+            self.theme = switcher.previous;
+        }
+    }
+
+    /// Highlights the next or previous theme and shows the board in it.
+    pub fn select_theme(&mut self, forward: bool) {
+        let Some(switcher) = self.theme_switcher.as_mut() else {
+            return;
+        };
+        let count = switcher.names.len();
+        switcher.index = if forward { (switcher.index + 1) % count } else { (switcher.index + count - 1) % count };
+        match config::themes::load(&switcher.names[switcher.index]) {
+            Ok(theme) => self.theme = theme,
+            Err(err) => self.status_message = Some(format!("{:#}", err)),
+        }
+    }
+
+    /// Keeps the highlighted theme and saves it.
+    pub fn confirm_theme_switcher(&mut self) -> Result<()> {
+        let Some(switcher) = self.theme_switcher.take() else {
+            return Ok(());
+        };
+        let name = switcher.names[switcher.index].clone();
+        if let Err(err) = self.set_theme(&name) {
+            self.theme = switcher.previous;
+            self.status_message = Some(format!("{:#}", err));
+        }
+        Ok(())
+    }
+
+    /// Switches to a theme and writes it to the config.toml the current one
+    /// came from: the board's if it sets one, else the global file.
+    pub fn set_theme(&mut self, name: &str) -> Result<()> {
+        let theme = config::themes::load(name)?;
+        let current = config::current();
+        let source = current.entry("theme")?.source;
+        let path = match source {
+            Source::Board => current.file(Source::Board),
+            _ => current.file(Source::Global),
+        };
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => config::global_config_path()?,
+        };
+        config::set(&path, "theme", &theme.name)?;
+        // Take in the file just written so it is not announced as an outside edit
+        let reload = config::reload_if_changed();
+
+        self.status_message = Some(match source {
+            Source::Env | Source::Flag => format!(
+                "Theme: {} (saved to {}, but the {} picks the theme at startup)",
+                theme.name,
+                path.display(),
+                source
+            ),
+            _ => format!("Theme: {}", theme.name),
+        });
+        if let Some(Err(err)) = reload {
+            self.status_message = Some(format!("{:#}; keeping the previous settings", err));
+        }
+        self.theme = theme;
+        Ok(())
+    }
+}
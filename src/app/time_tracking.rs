@@ -0,0 +1,56 @@
+use anyhow::Result;
+use chrono::Utc;
+
+use crate::app::subtasks::InputTarget;
+use crate::app::undo::{Change, Command};
+use crate::app::{App, InputMode};
+use crate::models::time_tracking;
+use crate::stats;
+
+impl App {
+    /// Starts a timer on the selected task, or stops it if it is already running there.
+    pub fn toggle_timer(&mut self) -> Result<()> {
+        if let Some(task) = self.selected_task() {
+            let id = task.id.clone();
+            self.toggle_timer_on(&id, "")?;
+        }
+        Ok(())
+    }
+
+    /// Opens the input bar to type the note of the timer started or stopped next.
+    pub fn start_timer_note_input(&mut self) {
+        if let Some(task) = self.selected_task() {
+            self.input_target = InputTarget::TimerNote(task.id.clone());
+            self.new_task_input.clear();
+            self.input_mode = InputMode::Editing;
+        }
+    }
+
+    /// Stops the timer running on task `id`, or starts one there and stops whichever
+    /// other timer was running, all as one undoable command.
+    pub(crate) fn toggle_timer_on(&mut self, id: &str, note: &str) -> Result<()> {
+        let Some(before) = self.tasks.iter().find(|task| task.id == id).cloned() else {
+            return Ok(());
+        };
+        let now = Utc::now();
+        let mut after = before.clone();
+
+        if let Some(spent) = after.stop_timer(now, note) {
+            self.status_message = Some(format!("Stopped timer on \"{}\" after {}", after.title, stats::format_duration(spent)));
+            let description = format!("Stop timer on \"{}\"", after.title);
+            return self.execute(Command::new(description, vec![Change::Updated { before: Box::new(before), after: Box::new(after) }]));
+        }
+
+        let mut changes = Vec::new();
+        if let Some(running) = time_tracking::running(&self.tasks).cloned() {
+            let mut stopped = running.clone();
+            stopped.stop_timer(now, "");
+            changes.push(Change::Updated { before: Box::new(running), after: Box::new(stopped) });
+        }
+        after.start_timer(now, note);
+        self.status_message = Some(format!("Started timer on \"{}\" (t to stop)", after.title));
+        let description = format!("Start timer on \"{}\"", after.title);
+        changes.push(Change::Updated { before: Box::new(before), after: Box::new(after) });
+        self.execute(Command::new(description, changes))
+    }
+}
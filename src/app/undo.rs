@@ -0,0 +1,163 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::models::{Task, TrashedTask};
+use crate::storage::{storage_manager, Write};
+
+/// How many commands the journal keeps in each direction.
+const MAX_JOURNAL_LEN: usize = 100;
+
+/// One reversible edit to the board or the trash bin.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Change {
+    Added(Task),
+    Removed(Task),
+    /// Boxed so the journal's other changes stay small.
+    Updated { before: Box<Task>, after: Box<Task> },
+    Trashed(TrashedTask),
+    Restored(TrashedTask),
+    Purged(Vec<TrashedTask>),
+    Unpurged(Vec<TrashedTask>),
+}
+
+impl Change {
+    /// What storage has to write to make this change.
+    pub fn writes(&self) -> Vec<Write> {
+        match self {
+            Change::Added(task) => vec![Write::Upsert(task.clone())],
+            Change::Updated { after, .. } => vec![Write::Upsert((**after).clone())],
+            Change::Removed(task) => vec![Write::Delete(task.id.clone())],
+            Change::Trashed(entry) => vec![Write::Trash(entry.clone())],
+            Change::Restored(entry) => vec![Write::Restore(entry.task.clone())],
+            Change::Purged(entries) => vec![Write::Purge(entries.iter().map(|e| e.task.id.clone()).collect())],
+            Change::Unpurged(entries) => entries.iter().cloned().map(Write::Trash).collect(),
+        }
+    }
+
+    /// The change that reverts this one. Applying it swaps whole snapshots; undo and
+    /// redo keep the task's current history instead, see `App::keep_history`.
+    pub fn inverse(&self) -> Change {
+        match self {
+            Change::Added(task) => Change::Removed(task.clone()),
+            Change::Removed(task) => Change::Added(task.clone()),
+            Change::Updated { before, after } => Change::Updated {
+                before: after.clone(),
+                after: before.clone(),
+            },
+            Change::Trashed(entry) => Change::Restored(entry.clone()),
+            Change::Restored(entry) => Change::Trashed(entry.clone()),
+            Change::Purged(entries) => Change::Unpurged(entries.clone()),
+            Change::Unpurged(entries) => Change::Purged(entries.clone()),
+        }
+    }
+}
+
+/// Where a change leaves a task, so undo can tell whether something else changed it since.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Expected<'a> {
+    OnBoard(&'a Task),
+    InTrash(&'a Task),
+    OffBoard,
+    OffTrash,
+}
+
+impl Change {
+    /// The tasks this change touches, by id, and the state it leaves each in.
+    fn expected(&self) -> Vec<(&str, Expected<'_>)> {
+        match self {
+            Change::Added(task) => vec![(task.id.as_str(), Expected::OnBoard(task))],
+            Change::Removed(task) => vec![(task.id.as_str(), Expected::OffBoard)],
+            Change::Updated { after, .. } => vec![(after.id.as_str(), Expected::OnBoard(after))],
+            Change::Trashed(entry) => vec![(entry.task.id.as_str(), Expected::InTrash(&entry.task))],
+            Change::Restored(entry) => vec![(entry.task.id.as_str(), Expected::OnBoard(&entry.task))],
+            Change::Purged(entries) => entries.iter().map(|entry| (entry.task.id.as_str(), Expected::OffTrash)).collect(),
+            Change::Unpurged(entries) => {
+                entries.iter().map(|entry| (entry.task.id.as_str(), Expected::InTrash(&entry.task))).collect()
+            }
+        }
+    }
+}
+
+/// The state `changes` leave each task they touch in; a later change to a task wins.
+pub fn expected_state(changes: &[Change]) -> BTreeMap<&str, Expected<'_>> {
+    changes.iter().flat_map(Change::expected).collect()
+}
+
+/// A user-level action made of one or more changes, undone and redone as a unit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Command {
+    pub description: String,
+    pub changes: Vec<Change>,
+}
+
+impl Command {
+    pub fn new(description: impl Into<String>, changes: Vec<Change>) -> Self {
+        Self {
+            description: description.into(),
+            changes,
+        }
+    }
+
+    /// The changes that revert this command, in the order they must be applied.
+    pub fn inverse_changes(&self) -> Vec<Change> {
+        self.changes.iter().rev().map(Change::inverse).collect()
+    }
+}
+
+/// Undo and redo stacks, persisted to journal.json so they survive restarts.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Journal {
+    pub undo: Vec<Command>,
+    pub redo: Vec<Command>,
+}
+
+impl Journal {
+    pub fn load(data_dir: &Path) -> Result<Self> {
+        let path = data_dir.join("journal.json");
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(&path)?;
+        serde_json::from_str(&contents).with_context(|| format!("failed to parse {}", path.display()))
+    }
+
+    pub fn save(&self, data_dir: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        storage_manager::write_atomic(&data_dir.join("journal.json"), json.as_bytes())
+    }
+
+    /// Records a freshly executed command; a new action invalidates anything to redo.
+    pub fn record(&mut self, command: Command) {
+        self.undo.push(command);
+        self.redo.clear();
+        trim(&mut self.undo);
+    }
+
+    pub fn push_redo(&mut self, command: Command) {
+        self.redo.push(command);
+        trim(&mut self.redo);
+    }
+
+    pub fn push_undo(&mut self, command: Command) {
+        self.undo.push(command);
+        trim(&mut self.undo);
+    }
+
+    /// Drops every command that touches task `id`, for a task that has left the board.
+    pub fn forget(&mut self, id: &str) {
+        let touches = |command: &Command| expected_state(&command.changes).contains_key(id);
+        self.undo.retain(|command| !touches(command));
+        self.redo.retain(|command| !touches(command));
+    }
+}
+
+// Drop the oldest entries once a stack grows past the limit
+fn trim(stack: &mut Vec<Command>) {
+    if stack.len() > MAX_JOURNAL_LEN {
+        stack.drain(..stack.len() - MAX_JOURNAL_LEN);
+    }
+}
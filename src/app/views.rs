@@ -0,0 +1,165 @@
+use anyhow::Result;
+use chrono::Utc;
+
+use crate::app::subtasks::InputTarget;
+use crate::app::{App, AppTab, InputMode};
+use crate::models::filter::Filter;
+use crate::models::Task;
+use crate::storage::views::SavedViews;
+
+/// A pinned saved view whose tab is open, with its query parsed once.
+pub struct ActiveView {
+    /// Index into `App::views`.
+    pub index: usize,
+    pub filter: Filter,
+}
+
+impl App {
+    /// Opens the input bar with the current filter query to edit.
+    pub fn start_filter_input(&mut self) {
+        self.input_target = InputTarget::Filter;
+        self.new_task_input = self.filter_text.clone();
+        self.input_mode = InputMode::Editing;
+    }
+
+    /// Parses `text` and makes it the active filter; a bad query leaves the old one.
+    pub fn apply_filter(&mut self, text: &str) {
+        let filter = match text.parse::<Filter>().and_then(|filter| filter.check(&self.workflow).map(|_| filter)) {
+            Ok(filter) => filter,
+            Err(err) => {
+                self.status_message = Some(format!("Invalid filter: {}", err));
+                return;
+            }
+        };
+
+        let selected_id = self.selected_task().map(|task| task.id.clone());
+        self.filter = filter;
+        self.filter_text = text.to_string();
+        self.filter_active = true;
+        self.reselect(selected_id);
+        self.status_message = Some(format!("{} tasks match", self.filtered_tasks().len()));
+    }
+
+    /// Filters the board down to one workflow status.
+    pub fn filter_by_status(&mut self, index: usize) {
+        let Some(status) = self.workflow.statuses().nth(index) else {
+            return;
+        };
+        let name = status.as_str();
+        let text = if name.contains(char::is_whitespace) { format!("status:\"{}\"", name) } else { format!("status:{}", name) };
+        self.apply_filter(&text);
+    }
+
+    pub fn toggle_filter(&mut self) {
+        let selected_id = self.selected_task().map(|task| task.id.clone());
+        self.filter_active = !self.filter_active && !self.filter.is_empty();
+        self.reselect(selected_id);
+    }
+
+    /// Opens the input bar to name a view for the current filter.
+    pub fn start_view_input(&mut self) {
+        if !self.filter_active || self.filter.is_empty() {
+            self.status_message = Some("Set a filter with F before saving it as a view".to_string());
+            return;
+        }
+        self.input_target = InputTarget::ViewName;
+        self.new_task_input.clear();
+        self.input_mode = InputMode::Editing;
+    }
+
+    /// Saves the current filter as a pinned view and opens its tab.
+    pub fn save_view(&mut self, name: &str) -> Result<()> {
+        if let Err(err) = self.views.save_view(name, &self.filter_text, true) {
+            self.status_message = Some(format!("{:#}", err));
+            return Ok(());
+        }
+        self.views.save(self.storage.data_dir())?;
+
+        // The view's tab now does what the ad hoc filter did
+        self.filter_active = false;
+        if let Some(index) = self.views.views.iter().position(|view| view.name.eq_ignore_ascii_case(name.trim())) {
+            self.open_view(Some(index));
+        }
+        self.status_message = Some(format!("Saved view \"{}\"", name.trim()));
+        Ok(())
+    }
+
+    /// Takes the open view's tab off the tab bar; the view itself stays saved.
+    pub fn unpin_active_view(&mut self) -> Result<()> {
+        let Some(active) = self.active_view.take() else {
+            return Ok(());
+        };
+        let name = self.views.views[active.index].name.clone();
+        self.views.set_pinned(&name, false)?;
+        self.views.save(self.storage.data_dir())?;
+        self.reselect(None);
+        self.status_message = Some(format!("Unpinned view \"{}\" (task_x views --pin to restore)", name));
+        Ok(())
+    }
+
+    /// Reads the project's saved views, closing a view tab that no longer exists.
+    pub(crate) fn load_views(&mut self) {
+        self.active_view = None;
+        self.views = match SavedViews::load(self.storage.data_dir()) {
+            Ok(views) => views,
+            Err(err) => {
+                self.status_message = Some(format!("{:#}", err));
+                SavedViews::default()
+            }
+        };
+    }
+
+    /// Switches the Tasks tab to a pinned view, or back to the whole board with `None`.
+    pub(crate) fn open_view(&mut self, index: Option<usize>) {
+        self.current_tab = AppTab::Tasks;
+        self.active_view = None;
+        if let Some(index) = index {
+            match self.views.views[index].filter() {
+                Ok(filter) => self.active_view = Some(ActiveView { index, filter }),
+                Err(err) => self.status_message = Some(format!("{:#}", err)),
+            }
+        }
+        self.reselect(None);
+    }
+
+    /// Whether `task` passes the open view and the active filter.
+    pub(crate) fn passes_filters(&self, task: &Task) -> bool {
+        let now = Utc::now();
+        let view_match = self
+            .active_view
+            .as_ref()
+            .is_none_or(|view| view.filter.matches(task, &self.tasks, &self.workflow, now));
+        let filter_match = !self.filter_active || self.filter.matches(task, &self.tasks, &self.workflow, now);
+        view_match && filter_match
+    }
+
+    /// Names on the tab bar: Tasks, the pinned views, then the other tabs.
+    pub fn tab_titles(&self) -> Vec<String> {
+        let mut titles = vec!["Tasks".to_string()];
+        titles.extend(self.views.pinned().into_iter().map(|i| self.views.views[i].name.clone()));
+        titles.extend(["Calendar", "Statistics", "Trash"].map(String::from));
+        titles
+    }
+
+    /// Position of the open tab in `tab_titles`.
+    pub fn tab_index(&self) -> usize {
+        let pinned = self.views.pinned();
+        match self.current_tab {
+            AppTab::Tasks => self
+                .active_view
+                .as_ref()
+                .and_then(|view| pinned.iter().position(|&i| i == view.index))
+                .map_or(0, |position| position + 1),
+            tab => pinned.len() + tab as usize,
+        }
+    }
+
+    /// Keeps the task with `id` selected if it is still visible, else the first one.
+    pub(crate) fn reselect(&mut self, id: Option<String>) {
+        let count = self.filtered_tasks().len();
+        self.selected_task_index = (count > 0).then_some(0);
+        if let Some(id) = id {
+            self.select_task_by_id(&id);
+        }
+    }
+}
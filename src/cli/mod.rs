@@ -0,0 +1,985 @@
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Local, Utc};
+use clap::{Parser, Subcommand};
+use std::process::ExitCode;
+use thiserror::Error;
+
+use crate::app::undo::{Change, Command as UndoCommand, Journal};
+use crate::config::{self, CONFIG_FILE};
+use crate::models::workflow::Workflow;
+use crate::models::dependencies::{self, Direction};
+use crate::models::filter::Filter;
+use crate::models::recurrence::{self, Recurrence};
+use crate::models::time_tracking;
+use crate::models::{subtasks, ChecklistItem, Task, TaskPriority, TaskStatus, TrashedTask};
+use crate::stats;
+use crate::storage::projects::{self, DEFAULT_PROJECT};
+use crate::storage::views::SavedViews;
+use crate::storage::{self, storage_manager, Storage, TaskQuery};
+use crate::utils;
+
+#[derive(Parser)]
+#[command(name = "task_x", version, about = "Keyboard-driven terminal task manager")]
+pub struct Cli {
+    /// Run a command instead of opening the interactive board
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Project to work in
+    #[arg(long, global = true, default_value = DEFAULT_PROJECT)]
+    pub project: String,
+    /// Override a setting for this run; repeat for several
+    #[arg(long = "set", global = true, value_name = "KEY=VALUE", value_parser = config::parse_override)]
+    pub overrides: Vec<(String, String)>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Create a new task
+    Add {
+        title: String,
+        #[arg(short, long, default_value = "")]
+        description: String,
+        /// Priority; the default_priority setting when omitted
+        #[arg(short, long)]
+        priority: Option<TaskPriority>,
+        /// Starting status; the workflow's first status by default
+        #[arg(short, long)]
+        status: Option<String>,
+        /// Due date as YYYY-MM-DD
+        #[arg(long, value_parser = utils::parse_due_date)]
+        due: Option<DateTime<Utc>>,
+        /// Tag to attach; repeat for several tags
+        #[arg(short, long = "tag")]
+        tags: Vec<String>,
+        /// Make it a subtask of this task (id or unique id prefix)
+        #[arg(long)]
+        parent: Option<String>,
+        /// Checklist item to add; repeat for several items
+        #[arg(short, long = "check")]
+        checks: Vec<String>,
+        /// Task that must be done first (id or unique id prefix); repeat for several
+        #[arg(long = "blocked-by")]
+        blocked_by: Vec<String>,
+        /// Repeat rule: daily, "every N days", "weekly mon,thu", "monthly N" or "N days after done"
+        #[arg(long)]
+        repeat: Option<Recurrence>,
+        /// Story points
+        #[arg(long)]
+        points: Option<u32>,
+        /// Expected effort, e.g. 90m, 2h or 1h30m
+        #[arg(long, value_parser = utils::parse_estimate)]
+        estimate: Option<u32>,
+    },
+    /// List tasks, optionally filtered
+    List {
+        #[arg(short, long)]
+        status: Option<String>,
+        #[arg(short, long)]
+        priority: Option<TaskPriority>,
+        /// Only tasks due before this date (YYYY-MM-DD)
+        #[arg(long, value_parser = utils::parse_due_date)]
+        due_before: Option<DateTime<Utc>>,
+        #[arg(short, long)]
+        tag: Option<String>,
+        /// Only tasks matching this query, e.g. "status:todo priority>=high due<7d -tag:later"
+        #[arg(short, long)]
+        query: Option<Filter>,
+        /// Only tasks matching this saved view
+        #[arg(long)]
+        view: Option<String>,
+        /// Print tasks as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// List saved views, or save, pin, unpin or delete one
+    Views {
+        /// Save a view under this name (with --query)
+        #[arg(long, requires = "query", conflicts_with_all = ["pin", "unpin", "delete"])]
+        save: Option<String>,
+        /// Query of the view to save
+        #[arg(short, long, requires = "save")]
+        query: Option<String>,
+        /// Show a view as a tab in the TUI
+        #[arg(long, conflicts_with_all = ["unpin", "delete"])]
+        pin: Option<String>,
+        /// Take a view's tab off the tab bar
+        #[arg(long, conflicts_with = "delete")]
+        unpin: Option<String>,
+        /// Delete a saved view
+        #[arg(long)]
+        delete: Option<String>,
+    },
+    /// Mark a task as done
+    Done {
+        /// Task id or unique id prefix
+        id: String,
+    },
+    /// Change fields of an existing task
+    Edit {
+        /// Task id or unique id prefix
+        id: String,
+        #[arg(long)]
+        title: Option<String>,
+        #[arg(short, long)]
+        description: Option<String>,
+        #[arg(short, long)]
+        priority: Option<TaskPriority>,
+        #[arg(short, long)]
+        status: Option<String>,
+        /// Due date as YYYY-MM-DD
+        #[arg(long, value_parser = utils::parse_due_date, conflicts_with = "no_due")]
+        due: Option<DateTime<Utc>>,
+        /// Remove the due date
+        #[arg(long)]
+        no_due: bool,
+        /// Tag to add; repeat for several tags
+        #[arg(short, long = "tag")]
+        tags: Vec<String>,
+        /// Tag to remove; repeat for several tags
+        #[arg(long = "untag")]
+        untags: Vec<String>,
+        /// Make it a subtask of this task (id or unique id prefix)
+        #[arg(long, conflicts_with = "no_parent")]
+        parent: Option<String>,
+        /// Make it a top-level task again
+        #[arg(long)]
+        no_parent: bool,
+        /// Checklist item to add; repeat for several items
+        #[arg(short, long = "check")]
+        checks: Vec<String>,
+        /// Toggle the checklist item at this position (1-based); repeat for several items
+        #[arg(long = "tick")]
+        ticks: Vec<usize>,
+        /// Task that must be done first (id or unique id prefix); repeat for several
+        #[arg(long = "blocked-by")]
+        blocked_by: Vec<String>,
+        /// Drop a prerequisite (id or unique id prefix); repeat for several
+        #[arg(long)]
+        unblock: Vec<String>,
+        /// Repeat rule: daily, "every N days", "weekly mon,thu", "monthly N" or "N days after done"
+        #[arg(long, conflicts_with = "no_repeat")]
+        repeat: Option<Recurrence>,
+        /// Stop repeating the task
+        #[arg(long)]
+        no_repeat: bool,
+        /// Story points
+        #[arg(long, conflicts_with = "no_points")]
+        points: Option<u32>,
+        /// Remove the story points
+        #[arg(long)]
+        no_points: bool,
+        /// Expected effort, e.g. 90m, 2h or 1h30m
+        #[arg(long, value_parser = utils::parse_estimate, conflicts_with = "no_estimate")]
+        estimate: Option<u32>,
+        /// Remove the estimate
+        #[arg(long)]
+        no_estimate: bool,
+    },
+    /// Start a timer on a task, stopping any other running timer
+    Start {
+        /// Task id or unique id prefix
+        id: String,
+        /// What the time is spent on
+        #[arg(short, long, default_value = "")]
+        note: String,
+    },
+    /// Stop the running timer
+    Stop {
+        /// Replace the note of the stopped time entry
+        #[arg(short, long, default_value = "")]
+        note: String,
+    },
+    /// Show time tracked per task, or the time entries of one task
+    Time {
+        /// Task id or unique id prefix
+        id: Option<String>,
+        /// Print as JSON (durations in seconds)
+        #[arg(long)]
+        json: bool,
+    },
+    /// Compare estimated with tracked time to calibrate future estimates
+    Estimates {
+        /// Print the report as JSON (durations in seconds)
+        #[arg(long)]
+        json: bool,
+    },
+    /// Create a .taskx board in the current directory, used from here and below
+    Init,
+    /// List projects, or create one
+    Projects {
+        /// Create a project with this name
+        #[arg(long)]
+        new: Option<String>,
+    },
+    /// Move a task to another project
+    Move {
+        /// Task id or unique id prefix
+        id: String,
+        /// Project to move it to
+        to: String,
+    },
+    /// Show what a task waits on and what waits on it
+    Deps {
+        /// Task id or unique id prefix
+        id: String,
+    },
+    /// Show the history of a task
+    History {
+        /// Task id or unique id prefix
+        id: String,
+        /// Print events as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Report how long tasks have spent in each status
+    Report {
+        /// Only tasks currently in this status
+        #[arg(short, long)]
+        status: Option<String>,
+        /// Print the report as JSON (durations in seconds)
+        #[arg(long)]
+        json: bool,
+    },
+    /// Move a task to the trash bin
+    Rm {
+        /// Task id or unique id prefix
+        id: String,
+        /// Delete permanently instead of moving to the trash
+        #[arg(long)]
+        purge: bool,
+    },
+    /// Put a task from the trash bin back on the board
+    Restore {
+        /// Task id or unique id prefix
+        id: String,
+    },
+    /// List the trash bin
+    Trash {
+        /// Permanently delete everything in the trash
+        #[arg(long)]
+        empty: bool,
+    },
+    /// Show or change settings
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    /// Print the value of a setting
+    Get { key: String },
+    /// Write a setting to the global config.toml, or the board's with --board
+    Set {
+        key: String,
+        value: String,
+        /// Write to the config.toml of the current board
+        #[arg(long)]
+        board: bool,
+    },
+    /// Remove a setting from the global config.toml, or the board's with --board
+    Unset {
+        key: String,
+        /// Remove from the config.toml of the current board
+        #[arg(long)]
+        board: bool,
+    },
+    /// List every setting with its value and where it comes from
+    List,
+}
+
+/// Failures that map to dedicated exit codes so scripts can tell them apart.
+#[derive(Debug, Error)]
+pub enum CliError {
+    #[error("no task matches id \"{0}\"")]
+    NotFound(String),
+    #[error("id \"{prefix}\" is ambiguous; it matches {count} tasks")]
+    Ambiguous { prefix: String, count: usize },
+}
+
+/// 0 on success, 3 for an unknown id, 4 for an ambiguous id, 1 for anything else.
+/// Argument errors exit with 2 from clap before we get here.
+pub fn exit_code(err: &anyhow::Error) -> ExitCode {
+    match err.downcast_ref::<CliError>() {
+        Some(CliError::NotFound(_)) => ExitCode::from(3),
+        Some(CliError::Ambiguous { .. }) => ExitCode::from(4),
+        None => ExitCode::FAILURE,
+    }
+}
+
+pub fn run(command: Command, project: &str) -> Result<()> {
+    // Invalid settings fall back to the other layers; say so without failing the command
+    for warning in config::current().warnings() {
+        eprintln!("warning: {}", warning);
+    }
+
+    // A new board has nothing to open yet
+    if let Command::Init = command {
+        let board = storage_manager::init_local_board(&std::env::current_dir()?)?;
+        println!("Created board {}", board.display());
+        return Ok(());
+    }
+
+    // Settings may be what keeps the board from opening, so they never need it
+    if let Command::Config { action } = command {
+        return run_config(action);
+    }
+
+    let mut storage = storage::open(project)?;
+    let workflow = storage::load_workflow(storage.data_dir())?;
+    let resolve = |name: Option<String>| -> Result<Option<TaskStatus>> {
+        name.map(|name| workflow.resolve(&name).map_err(|err| anyhow!(err))).transpose()
+    };
+
+    match command {
+        Command::Add {
+            title, description, priority, status, due, tags, parent, checks, blocked_by, repeat, points, estimate,
+        } => {
+            let status = resolve(status)?.unwrap_or_else(|| workflow.initial());
+            let priority = priority.unwrap_or_else(|| config::current().default_priority());
+            let mut task = Task::new(&title, &description, status, priority);
+            task.due_date = due;
+            task.tags = tags;
+            task.checklist = checks.into_iter().map(|text| ChecklistItem { text, done: false }).collect();
+            task.recurrence = repeat;
+            task.points = points;
+            task.estimate_minutes = estimate;
+            if let Some(parent) = parent {
+                task.parent_id = Some(find_task(storage.as_mut(), &parent)?.id);
+            }
+            // A brand-new task cannot be part of a cycle yet
+            for prefix in blocked_by {
+                let prerequisite = find_task(storage.as_mut(), &prefix)?.id;
+                if !task.blocked_by.contains(&prerequisite) {
+                    task.blocked_by.push(prerequisite);
+                }
+            }
+            storage.upsert(&task)?;
+            println!("{}", short_id(&task));
+            let description = format!("Add \"{}\"", task.title);
+            record(storage.as_ref(), description, vec![Change::Added(task)])?;
+        }
+        Command::List { status, priority, due_before, tag, query, view, json } => {
+            let mut filters = Vec::new();
+            filters.extend(query);
+            if let Some(name) = view {
+                filters.push(SavedViews::load(storage.data_dir())?.get(&name)?.filter()?);
+            }
+            for filter in &filters {
+                filter.check(&workflow).map_err(|err| anyhow!(err))?;
+            }
+
+            let candidates = storage.query(&TaskQuery { status: resolve(status)?, priority, due_before })?;
+            // Blocked tasks are only known against the whole board, so read it only when asked
+            let board =
+                if filters.iter().any(Filter::needs_board) { Some(storage.query(&TaskQuery::default())?) } else { None };
+            let board = board.as_deref().unwrap_or(&candidates);
+            let now = Utc::now();
+            let tasks: Vec<Task> = candidates
+                .iter()
+                .filter(|task| tag.as_ref().is_none_or(|tag| task.tags.contains(tag)))
+                .filter(|task| filters.iter().all(|filter| filter.matches(task, board, &workflow, now)))
+                .cloned()
+                .collect();
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&tasks)?);
+            } else {
+                for task in &tasks {
+                    println!("{}", format_row(task));
+                }
+            }
+        }
+        Command::Done { id } => {
+            let tasks = storage.query(&TaskQuery::default())?;
+            let mut task = resolve_id(tasks.clone(), &id, |task| &task.id)?;
+            let done = workflow.first_done();
+            check_transition(&workflow, &task, &done)?;
+            subtasks::check_can_finish(&tasks, &task, &done, &workflow).map_err(|err| anyhow!(err))?;
+            let before = task.clone();
+            let description = format!("Move \"{}\" to {}", task.title, done);
+            task.set_status(done);
+            save_update(storage.as_mut(), &workflow, description, &before, task)?;
+        }
+        Command::Edit {
+            id, title, description, priority, status, due, no_due, tags, untags, parent, no_parent, checks, ticks,
+            blocked_by, unblock, repeat, no_repeat, points, no_points, estimate, no_estimate,
+        } => {
+            let tasks = storage.query(&TaskQuery::default())?;
+            let mut task = resolve_id(tasks.clone(), &id, |task| &task.id)?;
+            let before = task.clone();
+            if let Some(title) = title {
+                task.title = title;
+            }
+            if let Some(description) = description {
+                task.description = description;
+            }
+            if let Some(priority) = priority {
+                task.priority = priority;
+            }
+            if let Some(status) = resolve(status)? {
+                check_transition(&workflow, &task, &status)?;
+                if status != task.status {
+                    subtasks::check_can_finish(&tasks, &task, &status, &workflow).map_err(|err| anyhow!(err))?;
+                }
+                task.status = status;
+            }
+            if due.is_some() || no_due {
+                task.due_date = due;
+            }
+            for tag in tags {
+                if !task.tags.contains(&tag) {
+                    task.tags.push(tag);
+                }
+            }
+            task.tags.retain(|tag| !untags.contains(tag));
+            if let Some(parent) = parent {
+                let parent_id = resolve_id(tasks.clone(), &parent, |task| &task.id)?.id;
+                task.parent_id = subtasks::resolve_parent(&tasks, &task.id, &parent_id).map_err(|err| anyhow!(err))?;
+            } else if no_parent {
+                task.parent_id = None;
+            }
+            task.checklist.extend(checks.into_iter().map(|text| ChecklistItem { text, done: false }));
+            let count = task.checklist.len();
+            for position in ticks {
+                let item = position
+                    .checked_sub(1)
+                    .and_then(|index| task.checklist.get_mut(index))
+                    .ok_or_else(|| anyhow!("no checklist item #{} (the task has {})", position, count))?;
+                item.done = !item.done;
+            }
+            for prefix in blocked_by {
+                let prerequisite = resolve_id(tasks.clone(), &prefix, |task| &task.id)?.id;
+                dependencies::check_dependency(&tasks, &task.id, &prerequisite).map_err(|err| anyhow!(err))?;
+                if !task.blocked_by.contains(&prerequisite) {
+                    task.blocked_by.push(prerequisite);
+                }
+            }
+            for prefix in unblock {
+                let prerequisite = resolve_id(tasks.clone(), &prefix, |task| &task.id)?.id;
+                task.blocked_by.retain(|id| *id != prerequisite);
+            }
+            if repeat.is_some() || no_repeat {
+                task.recurrence = repeat;
+            }
+            if points.is_some() || no_points {
+                task.points = points;
+            }
+            if estimate.is_some() || no_estimate {
+                task.estimate_minutes = estimate;
+            }
+            task.record_changes(&before);
+
+            let description = format!("Edit \"{}\"", task.title);
+            save_update(storage.as_mut(), &workflow, description, &before, task)?;
+        }
+        Command::Start { id, note } => {
+            let tasks = storage.query(&TaskQuery::default())?;
+            let mut task = resolve_id(tasks.clone(), &id, |task| &task.id)?;
+            if task.running_timer().is_some() {
+                return Err(anyhow!("a timer is already running on {}", short_id(&task)));
+            }
+            let now = Utc::now();
+            let mut changed = Vec::new();
+            let mut changes = Vec::new();
+            if let Some(running) = time_tracking::running(&tasks) {
+                let mut stopped = running.clone();
+                if let Some(spent) = stopped.stop_timer(now, "") {
+                    println!("Stopped {} after {}", format_row(&stopped), stats::format_duration(spent));
+                }
+                changed.push(stopped.clone());
+                changes.push(Change::Updated { before: Box::new(running.clone()), after: Box::new(stopped) });
+            }
+            let before = task.clone();
+            task.start_timer(now, &note);
+            println!("Started {}", format_row(&task));
+            changed.push(task.clone());
+            let description = format!("Start timer on \"{}\"", task.title);
+            changes.push(Change::Updated { before: Box::new(before), after: Box::new(task) });
+            storage.upsert_many(&changed)?;
+            record(storage.as_ref(), description, changes)?;
+        }
+        Command::Stop { note } => {
+            let tasks = storage.query(&TaskQuery::default())?;
+            let mut task = time_tracking::running(&tasks).cloned().ok_or_else(|| anyhow!("no timer is running"))?;
+            let before = task.clone();
+            let spent = task.stop_timer(Utc::now(), &note).unwrap_or_else(chrono::Duration::zero);
+            storage.upsert(&task)?;
+            println!("Stopped {} after {}", format_row(&task), stats::format_duration(spent));
+            let description = format!("Stop timer on \"{}\"", task.title);
+            record(storage.as_ref(), description, vec![Change::Updated { before: Box::new(before), after: Box::new(task) }])?;
+        }
+        Command::Time { id, json } => {
+            let tasks = storage.query(&TaskQuery::default())?;
+            match id {
+                Some(id) => print_time_entries(&resolve_id(tasks, &id, |task| &task.id)?, json)?,
+                None => print_time_totals(&tasks, json)?,
+            }
+        }
+        Command::Estimates { json } => {
+            let tasks = storage.query(&TaskQuery::default())?;
+            print_estimates(&tasks, &workflow, json)?;
+        }
+        Command::Init | Command::Config { .. } => unreachable!("handled before opening storage"),
+        Command::Projects { new } => {
+            let root = storage_manager::get_data_dir()?;
+            if let Some(name) = new {
+                projects::create(&root, &name)?;
+                println!("Created project {}", name);
+                return Ok(());
+            }
+            println!("Board {}", root.display());
+            for name in projects::list(&root)? {
+                let count = storage::open(&name)?.query(&TaskQuery::default())?.len();
+                let current = if name == project { "*" } else { " " };
+                println!("{} {:<20}  {} tasks", current, name, count);
+            }
+        }
+        Command::Views { save, query, pin, unpin, delete } => {
+            let mut views = SavedViews::load(storage.data_dir())?;
+            let message = if let (Some(name), Some(query)) = (&save, &query) {
+                views.save_view(name, query, false)?;
+                format!("Saved view {}", name)
+            } else if let Some(name) = &pin {
+                views.set_pinned(name, true)?;
+                format!("Pinned view {}", name)
+            } else if let Some(name) = &unpin {
+                views.set_pinned(name, false)?;
+                format!("Unpinned view {}", name)
+            } else if let Some(name) = &delete {
+                let removed = views.remove(name)?;
+                format!("Deleted view {}", removed.name)
+            } else {
+                for view in &views.views {
+                    let pinned = if view.pinned { "*" } else { " " };
+                    println!("{} {:<20}  {}", pinned, view.name, view.query);
+                }
+                return Ok(());
+            };
+            views.save(storage.data_dir())?;
+            println!("{}", message);
+        }
+        Command::Move { id, to } => {
+            if to == project {
+                return Err(anyhow!("the task is already in project {}", to));
+            }
+            let task = find_task(storage.as_mut(), &id)?;
+            let mut target = storage::open(&to)?;
+            let target_workflow = storage::load_workflow(target.data_dir())?;
+            let moved = projects::move_task(storage.as_mut(), target.as_mut(), &task, &target_workflow)?;
+            // Undoing an earlier change here would bring the task back as a copy
+            let mut journal = Journal::load(storage.data_dir()).unwrap_or_default();
+            journal.forget(&task.id);
+            journal.save(storage.data_dir())?;
+            println!("Moved to {}: {}", to, format_row(&moved));
+        }
+        Command::Deps { id } => {
+            let tasks = storage.query(&TaskQuery::default())?;
+            let task = resolve_id(tasks.clone(), &id, |task| &task.id)?;
+            for (heading, direction) in [("Waits on", Direction::Upstream), ("Blocks", Direction::Downstream)] {
+                println!("{}:", heading);
+                for line in dependencies::graph_lines(&tasks, &task, direction) {
+                    let done = if workflow.is_done(&line.task.status) { "✓ " } else { "" };
+                    let repeated = if line.repeated { " (*)" } else { "" };
+                    println!("{}{}  {}{}{}", line.prefix, short_id(line.task), done, line.task.title, repeated);
+                }
+            }
+        }
+        Command::History { id, json } => {
+            let task = find_task(storage.as_mut(), &id)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&task.history)?);
+            } else {
+                for event in &task.history {
+                    let at = utils::format_datetime(event.at);
+                    println!("{}  {}", at, event.kind);
+                }
+            }
+        }
+        Command::Report { status, json } => {
+            let query = TaskQuery { status: resolve(status)?, ..Default::default() };
+            let tasks = storage.query(&query)?;
+            print_report(&tasks, &workflow, json)?;
+        }
+        Command::Rm { id, purge } => {
+            let task = find_task(storage.as_mut(), &id)?;
+            let description = format!("Delete \"{}\"", task.title);
+            if purge {
+                storage.delete(&task.id)?;
+                println!("Deleted {}", format_row(&task));
+                record(storage.as_ref(), description, vec![Change::Removed(task)])?;
+            } else {
+                let entry = TrashedTask { task, deleted_at: Utc::now() };
+                storage.trash(&entry)?;
+                println!("Trashed {}", format_row(&entry.task));
+                record(storage.as_ref(), description, vec![Change::Trashed(entry)])?;
+            }
+        }
+        Command::Restore { id } => {
+            let trash = storage.load()?.trash;
+            let entry = resolve_id(trash, &id, |entry| &entry.task.id)?;
+            storage.restore(&entry.task)?;
+            println!("Restored {}", format_row(&entry.task));
+            let description = format!("Restore \"{}\"", entry.task.title);
+            record(storage.as_ref(), description, vec![Change::Restored(entry)])?;
+        }
+        Command::Trash { empty } => {
+            let loaded = storage.load()?;
+            let trash = storage::purge_expired_trash(storage.as_mut(), loaded.trash)?;
+            if empty {
+                let ids: Vec<String> = trash.iter().map(|entry| entry.task.id.clone()).collect();
+                storage.purge(&ids)?;
+                println!("Purged {} tasks", ids.len());
+                if !trash.is_empty() {
+                    let description = format!("Empty trash ({} tasks)", trash.len());
+                    record(storage.as_ref(), description, vec![Change::Purged(trash)])?;
+                }
+            } else {
+                for entry in &trash {
+                    let deleted = utils::format_date(entry.deleted_at);
+                    println!("{}  deleted {}", format_row(&entry.task), deleted);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Saves an edited task and prints it. Moving a task to done stops its timer, and
+/// finishing a recurring task also creates and prints its next occurrence.
+fn save_update(storage: &mut dyn Storage, workflow: &Workflow, description: String, before: &Task, mut task: Task) -> Result<()> {
+    let now = Utc::now();
+    let stopped = time_tracking::stop_when_done(before, &mut task, workflow, now);
+    let next = recurrence::next_occurrence(before, &mut task, workflow, now);
+    match &next {
+        Some(next) => storage.upsert_many(&[task.clone(), next.clone()])?,
+        None => storage.upsert(&task)?,
+    }
+    println!("{}", format_row(&task));
+    if let Some(spent) = stopped {
+        println!("Stopped timer after {}", stats::format_duration(spent));
+    }
+    if let Some(next) = &next {
+        println!("Next {}", format_row(next));
+    }
+
+    let mut changes = vec![Change::Updated { before: Box::new(before.clone()), after: Box::new(task) }];
+    changes.extend(next.map(Change::Added));
+    record(storage, description, changes)
+}
+
+/// Adds a change to the board's undo journal, so `u` in the board reverts it like
+/// one made there. A damaged journal is replaced, as the board does.
+fn record(storage: &dyn Storage, description: String, changes: Vec<Change>) -> Result<()> {
+    let mut journal = Journal::load(storage.data_dir()).unwrap_or_default();
+    journal.record(UndoCommand::new(description, changes));
+    journal.save(storage.data_dir())
+}
+
+/// Resolves a full id or a unique prefix of one among the board's tasks.
+fn find_task(storage: &mut dyn Storage, prefix: &str) -> Result<Task> {
+    let tasks = storage.query(&TaskQuery::default())?;
+    resolve_id(tasks, prefix, |task| &task.id)
+}
+
+fn resolve_id<T>(items: Vec<T>, prefix: &str, id_of: impl Fn(&T) -> &String) -> Result<T> {
+    let mut matches: Vec<T> = items
+        .into_iter()
+        .filter(|item| id_of(item).starts_with(prefix))
+        .collect();
+
+    match matches.len() {
+        0 => Err(CliError::NotFound(prefix.to_string()).into()),
+        1 => Ok(matches.remove(0)),
+        count => Err(CliError::Ambiguous { prefix: prefix.to_string(), count }.into()),
+    }
+}
+
+fn check_transition(workflow: &Workflow, task: &Task, to: &TaskStatus) -> Result<()> {
+    if workflow.can_transition(&task.status, to) {
+        Ok(())
+    } else {
+        Err(anyhow!("the workflow does not allow moving from {} to {}", task.status, to))
+    }
+}
+
+fn print_report(tasks: &[Task], workflow: &Workflow, json: bool) -> Result<()> {
+    let now = Utc::now();
+
+    if json {
+        let rows: Vec<serde_json::Value> = tasks
+            .iter()
+            .map(|task| {
+                let seconds: serde_json::Map<String, serde_json::Value> = stats::time_in_status(task, workflow, now)
+                    .into_iter()
+                    .map(|(status, time)| (status.to_string(), time.num_seconds().into()))
+                    .collect();
+                serde_json::json!({
+                    "id": task.id,
+                    "title": task.title,
+                    "status": task.status,
+                    "seconds_in_status": seconds,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+        return Ok(());
+    }
+
+    let statuses: Vec<TaskStatus> = workflow.statuses().collect();
+    let header: Vec<String> = statuses.iter().map(|status| format!("{:<12}", status)).collect();
+    println!("{:<8}  {}  TITLE", "ID", header.join("  "));
+
+    let mut totals = vec![chrono::Duration::zero(); statuses.len()];
+    for task in tasks {
+        let times = stats::time_in_status(task, workflow, now);
+        let cells: Vec<String> = times
+            .iter()
+            .zip(totals.iter_mut())
+            .map(|((_, time), total)| {
+                *total += *time;
+                format!("{:<12}", format_time(*time))
+            })
+            .collect();
+        println!("{}  {}  {}", short_id(task), cells.join("  "), task.title);
+    }
+
+    if !tasks.is_empty() {
+        let averages: Vec<String> = totals
+            .iter()
+            .map(|total| format!("{:<12}", format_time(*total / tasks.len() as i32)))
+            .collect();
+        println!("{:<8}  {}", "average", averages.join("  ").trim_end());
+    }
+    Ok(())
+}
+
+fn print_time_entries(task: &Task, json: bool) -> Result<()> {
+    let now = Utc::now();
+    if json {
+        let entries: Vec<serde_json::Value> = task
+            .time_entries
+            .iter()
+            .map(|entry| {
+                serde_json::json!({
+                    "start": entry.start,
+                    "end": entry.end,
+                    "note": entry.note,
+                    "seconds": entry.duration(now).num_seconds(),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    for entry in &task.time_entries {
+        let start = utils::format_datetime(entry.start);
+        let end = entry
+            .end
+            .map_or_else(|| "running".to_string(), |end| end.with_timezone(&Local).format("%H:%M").to_string());
+        println!("{} - {:<7}  {:<8}  {}", start, end, stats::format_duration(entry.duration(now)), entry.note);
+    }
+    println!("Total {}", stats::format_duration(task.time_spent(now)));
+    Ok(())
+}
+
+fn print_time_totals(tasks: &[Task], json: bool) -> Result<()> {
+    let now = Utc::now();
+    let mut tracked: Vec<(&Task, chrono::Duration)> = tasks
+        .iter()
+        .filter(|task| !task.time_entries.is_empty())
+        .map(|task| (task, task.time_spent(now)))
+        .collect();
+    tracked.sort_by_key(|(_, time)| std::cmp::Reverse(*time));
+
+    if json {
+        let rows: Vec<serde_json::Value> = tracked
+            .iter()
+            .map(|(task, time)| {
+                serde_json::json!({
+                    "id": task.id,
+                    "title": task.title,
+                    "seconds": time.num_seconds(),
+                    "running": task.running_timer().is_some(),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+        return Ok(());
+    }
+
+    for (task, time) in &tracked {
+        let running = if task.running_timer().is_some() { "  (running)" } else { "" };
+        println!("{}  {:<8}  {}{}", short_id(task), stats::format_duration(*time), task.title, running);
+    }
+    let today = stats::tracked_per_day(tasks, 1, now).remove(0).1;
+    let week = stats::tracked_per_week(tasks, 1, now).remove(0).1;
+    println!("Today {}, this week {}", stats::format_duration(today), stats::format_duration(week));
+    Ok(())
+}
+
+fn print_estimates(tasks: &[Task], workflow: &Workflow, json: bool) -> Result<()> {
+    let now = Utc::now();
+    let estimated: Vec<&Task> = tasks
+        .iter()
+        .filter(|task| task.points.is_some() || task.estimate_minutes.is_some())
+        .collect();
+    let calibration = stats::estimate_calibration(tasks, workflow, now);
+    let per_point = stats::time_per_point(tasks, workflow, now);
+
+    if json {
+        let rows: Vec<serde_json::Value> = estimated
+            .iter()
+            .map(|task| {
+                serde_json::json!({
+                    "id": task.id,
+                    "title": task.title,
+                    "status": task.status,
+                    "points": task.points,
+                    "estimate_seconds": task.estimate_minutes.map(|minutes| u64::from(minutes) * 60),
+                    "tracked_seconds": task.time_spent(now).num_seconds(),
+                })
+            })
+            .collect();
+        let report = serde_json::json!({
+            "tasks": rows,
+            "finished_tracked_percent_of_estimate": calibration
+                .and_then(|(estimated, tracked, _)| stats::percent_of_estimate(estimated, tracked)),
+            "seconds_per_point": per_point.map(|time| time.num_seconds()),
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!("{:<8}  {:<10}  {:>6}  {:>8}  {:>8}  {:>6}  TITLE", "ID", "STATUS", "POINTS", "ESTIMATE", "TRACKED", "RATIO");
+    for task in &estimated {
+        let tracked = task.time_spent(now);
+        let estimate = task.estimate_minutes.map(|minutes| chrono::Duration::minutes(i64::from(minutes)));
+        let ratio = estimate
+            .and_then(|estimate| stats::percent_of_estimate(estimate, tracked))
+            .filter(|_| !tracked.is_zero())
+            .map_or_else(|| "-".to_string(), |percent| format!("{}%", percent));
+        println!(
+            "{}  {:<10}  {:>6}  {:>8}  {:>8}  {:>6}  {}",
+            short_id(task),
+            task.status,
+            task.points.map_or_else(|| "-".to_string(), |points| points.to_string()),
+            task.estimate_minutes.map_or_else(|| "-".to_string(), |minutes| utils::format_estimate(minutes.into())),
+            format_time(tracked),
+            ratio,
+            task.title
+        );
+    }
+
+    match calibration {
+        Some((estimated, tracked, count)) => println!(
+            "Finished tasks took {}% of their estimates ({} tracked for {} estimated over {} tasks)",
+            stats::percent_of_estimate(estimated, tracked).unwrap_or(0),
+            stats::format_duration(tracked),
+            stats::format_duration(estimated),
+            count
+        ),
+        None => println!("No finished task has both an estimate and tracked time yet"),
+    }
+    if let Some(per_point) = per_point {
+        println!("One story point took {} on average", stats::format_duration(per_point));
+    }
+    Ok(())
+}
+
+fn format_time(time: chrono::Duration) -> String {
+    if time.is_zero() {
+        "-".to_string()
+    } else {
+        stats::format_duration(time)
+    }
+}
+
+fn run_config(action: ConfigAction) -> Result<()> {
+    let config_path = |board: bool| -> Result<std::path::PathBuf> {
+        if board {
+            Ok(storage_manager::get_data_dir()?.join(CONFIG_FILE))
+        } else {
+            config::global_config_path()
+        }
+    };
+
+    match action {
+        ConfigAction::Get { key } => {
+            let entry = config::current().entry(&key)?;
+            println!("{}", effective_value(&entry)?);
+        }
+        ConfigAction::Set { key, value, board } => {
+            if board && !config::is_per_board(&key)? {
+                return Err(anyhow!("{} can only be set in the global config", key));
+            }
+            let path = config_path(board)?;
+            let value = config::set(&path, &key, &value)?;
+            println!("Set {} = {} in {}", key, value, path.display());
+        }
+        ConfigAction::Unset { key, board } => {
+            let path = config_path(board)?;
+            if config::unset(&path, &key)? {
+                println!("Removed {} from {}", key, path.display());
+            } else {
+                println!("{} is not set in {}", key, path.display());
+            }
+        }
+        ConfigAction::List => {
+            println!("Global config: {}", config_path(false)?.display());
+            println!("Board config:  {}", config_path(true)?.display());
+            println!();
+            let entries = config::current().entries();
+            let rows: Vec<(String, String)> = entries
+                .iter()
+                .map(|entry| Ok((effective_value(entry)?, format!("({})", entry.source))))
+                .collect::<Result<_>>()?;
+            let key_width = entries.iter().map(|entry| entry.key.len()).max().unwrap_or(0);
+            let value_width = rows.iter().map(|(value, _)| value.chars().count()).max().unwrap_or(0);
+            for (entry, (value, source)) in entries.iter().zip(&rows) {
+                println!(
+                    "{:<kw$}  {:<vw$}  {:<15} {}",
+                    entry.key, value, source, entry.description, kw = key_width, vw = value_width
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A setting's value, with the directory an empty `data_dir` stands for.
+fn effective_value(entry: &config::Entry) -> Result<String> {
+    if entry.key == "data_dir" && entry.value.is_empty() {
+        return Ok(storage_manager::platform_data_dir()?.display().to_string());
+    }
+    Ok(entry.value.clone())
+}
+
+fn short_id(task: &Task) -> String {
+    task.id.chars().take(8).collect()
+}
+
+fn format_row(task: &Task) -> String {
+    let due = task
+        .due_date
+        .map(utils::format_date)
+        .unwrap_or_else(|| "-".to_string());
+    let tags = if task.tags.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", task.tags.join(", "))
+    };
+
+    format!(
+        "{}  {:<10}  {:<8}  {:<10}  {}{}",
+        short_id(task),
+        task.status,
+        task.priority,
+        due,
+        task.title,
+        tags
+    )
+}
@@ -0,0 +1,19 @@
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+
+use crate::config;
+use crate::models::keymap::{Keymap, KeymapFile};
+
+/// Reads the user's `keymap.toml` from the config directory; the built-in keys
+/// apply when there is none.
+pub fn load() -> Result<Keymap> {
+    let path = config::config_dir()?.join("keymap.toml");
+    if !path.exists() {
+        return Ok(Keymap::default());
+    }
+
+    let contents = fs::read_to_string(&path)?;
+    let file: KeymapFile =
+        toml::from_str(&contents).with_context(|| format!("failed to parse {}", path.display()))?;
+    Keymap::from_file(&file).map_err(|err| anyhow!("invalid keymap in {}: {}", path.display(), err))
+}
@@ -0,0 +1,565 @@
+pub mod keymap;
+pub mod themes;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::format::{Item, StrftimeItems};
+use directories::ProjectDirs;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+use crate::models::TaskPriority;
+use crate::storage::storage_manager;
+
+/// Name of the settings file, both in the config directory and in a board.
+pub const CONFIG_FILE: &str = "config.toml";
+
+/// Where a setting's value came from. Later sources override earlier ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Source {
+    Default,
+    Global,
+    Board,
+    Env,
+    Flag,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Source::Default => "default",
+            Source::Global => "global config",
+            Source::Board => "board config",
+            Source::Env => "environment",
+            Source::Flag => "command line",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// What values a setting takes.
+enum Kind {
+    Number,
+    Flag,
+    Choice(&'static [&'static str]),
+    DateFormat,
+    Path,
+    /// A built-in theme or a file in the `themes` directory.
+    Theme,
+}
+
+struct Setting {
+    key: &'static str,
+    /// Empty when the default is worked out at runtime.
+    default: &'static str,
+    kind: Kind,
+    /// Whether a board's own config.toml may set it.
+    per_board: bool,
+    description: &'static str,
+}
+
+const SETTINGS: &[Setting] = &[
+    Setting {
+        key: "data_dir",
+        default: "",
+        kind: Kind::Path,
+        per_board: false,
+        description: "Directory of the global board (the platform data directory when empty)",
+    },
+    Setting {
+        key: "storage",
+        default: "json",
+        kind: Kind::Choice(&["json", "sqlite"]),
+        per_board: true,
+        description: "Storage backend for tasks",
+    },
+    Setting {
+        key: "max_backups",
+        default: "5",
+        kind: Kind::Number,
+        per_board: true,
+        description: "Rolling backups of tasks.json to keep",
+    },
+    Setting {
+        key: "trash_retention_days",
+        default: "30",
+        kind: Kind::Number,
+        per_board: true,
+        description: "Days a deleted task stays in the trash",
+    },
+    Setting {
+        key: "default_view",
+        default: "kanban",
+        kind: Kind::Choice(&["kanban", "list", "detail"]),
+        per_board: true,
+        description: "View the board opens in",
+    },
+    Setting {
+        key: "default_priority",
+        default: "medium",
+        kind: Kind::Choice(&["low", "medium", "high", "critical"]),
+        per_board: true,
+        description: "Priority of new tasks",
+    },
+    Setting {
+        key: "date_format",
+        default: "%Y-%m-%d",
+        kind: Kind::DateFormat,
+        per_board: true,
+        description: "How dates are shown, in strftime syntax",
+    },
+    Setting {
+        key: "sample_tasks",
+        default: "true",
+        kind: Kind::Flag,
+        per_board: true,
+        description: "Fill an empty global board with example tasks",
+    },
+    Setting {
+        key: "theme",
+        default: "dark",
+        kind: Kind::Theme,
+        per_board: true,
+        description: "Colors of the board: dark, light, high-contrast, ansi or a theme file",
+    },
+];
+
+/// One setting as `config list` shows it.
+pub struct Entry {
+    pub key: &'static str,
+    pub value: String,
+    pub source: Source,
+    pub description: &'static str,
+}
+
+/// The settings in effect: built-in defaults, overridden by the global config.toml,
+/// the board's config.toml, `TASKX_*` environment variables and `--set key=value`
+/// flags, in that order.
+#[derive(Debug, Clone)]
+pub struct Config {
+    values: BTreeMap<&'static str, (String, Source)>,
+    /// `--set` flags, kept to apply them again on reload.
+    flags: Vec<(String, String)>,
+    /// The config files read, with their modification times, to notice edits.
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+    /// Settings from files and the environment that were ignored as invalid.
+    warnings: Vec<String>,
+}
+
+impl Config {
+    /// Reads every layer. An invalid `--set` flag is an error; an invalid value in a
+    /// file or the environment is left out with a warning naming where it came from,
+    /// so the setting keeps the value of the layers below it.
+    pub fn load(flags: &[(String, String)]) -> Result<Self> {
+        let mut config = Self { flags: flags.to_vec(), ..Self::defaults() };
+
+        let global = global_config_path()?;
+        config.apply_file(&global, Source::Global);
+
+        let mut env = Vec::new();
+        for setting in SETTINGS {
+            let var = env_var(setting.key);
+            if let Ok(value) = std::env::var(&var) {
+                match normalize(setting, &value) {
+                    Ok(value) => env.push((setting.key, value)),
+                    Err(err) => config.warnings.push(format!("invalid {}: {}; ignoring it", var, err)),
+                }
+            }
+        }
+        let mut overrides = Vec::new();
+        for (key, value) in flags {
+            let setting = find(key).map_err(|err| anyhow!("invalid --set {}={}: {}", key, value, err))?;
+            let value = normalize(setting, value).map_err(|err| anyhow!("invalid --set {}={}: {}", key, value, err))?;
+            overrides.push((setting.key, value));
+        }
+
+        // The board is found with the data directory the other layers settle on
+        let data_dir = overrides
+            .iter()
+            .chain(env.iter())
+            .find(|(key, _)| *key == "data_dir")
+            .map(|(_, value)| value.clone())
+            .unwrap_or_else(|| config.get("data_dir").to_string());
+        let board = match storage_manager::find_local_board() {
+            Some(board) => board,
+            None if !data_dir.is_empty() => PathBuf::from(&data_dir),
+            None => storage_manager::platform_data_dir()?,
+        };
+        config.apply_file(&board.join(CONFIG_FILE), Source::Board);
+
+        for (key, value) in env {
+            config.values.insert(key, (value, Source::Env));
+        }
+        for (key, value) in overrides {
+            config.values.insert(key, (value, Source::Flag));
+        }
+        Ok(config)
+    }
+
+    /// The built-in settings alone.
+    fn defaults() -> Self {
+        Self {
+            values: SETTINGS.iter().map(|s| (s.key, (s.default.to_string(), Source::Default))).collect(),
+            flags: Vec::new(),
+            files: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    fn apply_file(&mut self, path: &Path, source: Source) {
+        self.files.push((path.to_path_buf(), modified(path)));
+        let settings = match read_file(path) {
+            Ok(settings) => settings,
+            Err(err) => {
+                self.warnings.push(format!("{:#}; ignoring the file", err));
+                return;
+            }
+        };
+        for (key, value) in settings {
+            if let Err(err) = self.apply_setting(&key, value, source) {
+                self.warnings.push(format!("invalid setting in {}: {}; ignoring it", path.display(), err));
+            }
+        }
+    }
+
+    fn apply_setting(&mut self, key: &str, value: Result<String, String>, source: Source) -> Result<(), String> {
+        let setting = find(key)?;
+        if source == Source::Board && !setting.per_board {
+            return Err(format!("{} can only be set in the global config", key));
+        }
+        let value = value
+            .and_then(|value| normalize(setting, &value))
+            .map_err(|err| format!("{}: {}", key, err))?;
+        self.values.insert(setting.key, (value, source));
+        Ok(())
+    }
+
+    /// What was wrong with the settings that were ignored, one line each.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// The value of a known setting.
+    pub fn get(&self, key: &str) -> &str {
+        self.values.get(key).map_or("", |(value, _)| value.as_str())
+    }
+
+    /// Every setting with its value and where it came from.
+    pub fn entries(&self) -> Vec<Entry> {
+        SETTINGS.iter().map(|setting| self.entry_for(setting)).collect()
+    }
+
+    /// One setting by name, suggesting the closest one on a typo.
+    pub fn entry(&self, key: &str) -> Result<Entry> {
+        Ok(self.entry_for(find(key).map_err(|err| anyhow!(err))?))
+    }
+
+    fn entry_for(&self, setting: &Setting) -> Entry {
+        let (value, source) = self.values[setting.key].clone();
+        Entry { key: setting.key, value, source, description: setting.description }
+    }
+
+    /// Whether a config file changed since it was read.
+    fn is_stale(&self) -> bool {
+        self.files.iter().any(|(path, seen)| modified(path) != *seen)
+    }
+
+    pub fn data_dir(&self) -> Option<PathBuf> {
+        Some(self.get("data_dir")).filter(|dir| !dir.is_empty()).map(PathBuf::from)
+    }
+
+    pub fn storage(&self) -> &str {
+        self.get("storage")
+    }
+
+    pub fn max_backups(&self) -> usize {
+        self.get("max_backups").parse().unwrap_or(5)
+    }
+
+    pub fn trash_retention_days(&self) -> i64 {
+        self.get("trash_retention_days").parse().unwrap_or(30)
+    }
+
+    pub fn default_view(&self) -> &str {
+        self.get("default_view")
+    }
+
+    pub fn default_priority(&self) -> TaskPriority {
+        self.get("default_priority").parse().unwrap_or(TaskPriority::Medium)
+    }
+
+    pub fn date_format(&self) -> &str {
+        self.get("date_format")
+    }
+
+    pub fn sample_tasks(&self) -> bool {
+        self.get("sample_tasks") == "true"
+    }
+
+    pub fn theme(&self) -> &str {
+        self.get("theme")
+    }
+
+    /// The config.toml a layer was read from, for the global and board layers.
+    pub fn file(&self, source: Source) -> Option<&Path> {
+        let index = match source {
+            Source::Global => 0,
+            Source::Board => 1,
+            _ => return None,
+        };
+        self.files.get(index).map(|(path, _)| path.as_path())
+    }
+}
+
+static CURRENT: RwLock<Option<Arc<Config>>> = RwLock::new(None);
+
+/// Loads the settings for this run, with the `--set` flags given on the command line.
+pub fn init(flags: &[(String, String)]) -> Result<()> {
+    let config = Config::load(flags)?;
+    *CURRENT.write().unwrap_or_else(|err| err.into_inner()) = Some(Arc::new(config));
+    Ok(())
+}
+
+/// The settings in effect. Before `init`, the files and environment are read
+/// as they are, falling back to the defaults if they are invalid.
+pub fn current() -> Arc<Config> {
+    let mut current = CURRENT.write().unwrap_or_else(|err| err.into_inner());
+    current
+        .get_or_insert_with(|| {
+            Arc::new(Config::load(&[]).unwrap_or_else(|_| Config::defaults()))
+        })
+        .clone()
+}
+
+/// Reads the settings again when a config file has changed since they were
+/// loaded. Returns the previous and the new settings, or the error that kept
+/// the previous ones in place.
+pub fn reload_if_changed() -> Option<Result<(Arc<Config>, Arc<Config>)>> {
+    let old = current();
+    if !old.is_stale() {
+        return None;
+    }
+
+    let mut slot = CURRENT.write().unwrap_or_else(|err| err.into_inner());
+    match Config::load(&old.flags) {
+        Ok(config) => {
+            let new = Arc::new(config);
+            *slot = Some(new.clone());
+            Some(Ok((old, new)))
+        }
+        Err(err) => {
+            // Remember the broken files so the error is reported once, not on every check
+            let mut kept = (*old).clone();
+            kept.files = kept.files.iter().map(|(path, _)| (path.clone(), modified(path))).collect();
+            *slot = Some(Arc::new(kept));
+            Some(Err(err))
+        }
+    }
+}
+
+/// The per-user config directory, holding `config.toml`, `keymap.toml` and `themes/`.
+pub fn config_dir() -> Result<PathBuf> {
+    let proj_dirs = ProjectDirs::from("com", "task_manager", "task_manager")
+        .ok_or_else(|| anyhow!("Could not determine project directories"))?;
+    Ok(proj_dirs.config_dir().to_path_buf())
+}
+
+pub fn global_config_path() -> Result<PathBuf> {
+    Ok(config_dir()?.join(CONFIG_FILE))
+}
+
+/// Checks `value` for `key` and writes it to a config file, keeping its other settings.
+pub fn set(path: &Path, key: &str, value: &str) -> Result<String> {
+    let setting = find(key).map_err(|err| anyhow!(err))?;
+    let value = normalize(setting, value).map_err(|err| anyhow!("{}: {}", key, err))?;
+
+    let mut table = read_table(path)?;
+    let item = match setting.kind {
+        Kind::Number => toml::Value::Integer(value.parse()?),
+        Kind::Flag => toml::Value::Boolean(value == "true"),
+        _ => toml::Value::String(value.clone()),
+    };
+    table.insert(setting.key.to_string(), item);
+    write_table(path, &table)?;
+    Ok(value)
+}
+
+/// Removes `key` from a config file. Returns false when the file did not set it.
+pub fn unset(path: &Path, key: &str) -> Result<bool> {
+    let mut table = read_table(path)?;
+    // An unknown key already in the file can be removed; otherwise it is a typo
+    if table.remove(key).is_none() {
+        find(key).map_err(|err| anyhow!(err))?;
+        return Ok(false);
+    }
+    write_table(path, &table)?;
+    Ok(true)
+}
+
+/// Whether a board's config.toml may hold `key`.
+pub fn is_per_board(key: &str) -> Result<bool> {
+    Ok(find(key).map_err(|err| anyhow!(err))?.per_board)
+}
+
+/// Splits a `--set key=value` flag.
+pub fn parse_override(input: &str) -> Result<(String, String), String> {
+    let (key, value) = input.split_once('=').ok_or_else(|| format!("expected KEY=VALUE, got \"{}\"", input))?;
+    let setting = find(key.trim())?;
+    normalize(setting, value.trim())?;
+    Ok((setting.key.to_string(), value.trim().to_string()))
+}
+
+fn find(key: &str) -> Result<&'static Setting, String> {
+    let key = key.trim().to_lowercase().replace('-', "_");
+    if let Some(setting) = SETTINGS.iter().find(|setting| setting.key == key) {
+        return Ok(setting);
+    }
+    match SETTINGS.iter().min_by_key(|setting| edit_distance(setting.key, &key)) {
+        Some(closest) if edit_distance(closest.key, &key) <= 3 || closest.key.contains(&key) => {
+            Err(format!("unknown setting \"{}\" (did you mean \"{}\"?)", key, closest.key))
+        }
+        _ => {
+            let keys: Vec<&str> = SETTINGS.iter().map(|setting| setting.key).collect();
+            Err(format!("unknown setting \"{}\" (known settings: {})", key, keys.join(", ")))
+        }
+    }
+}
+
+/// Checks a raw value and returns it in its canonical spelling.
+fn normalize(setting: &Setting, value: &str) -> Result<String, String> {
+    let value = value.trim();
+    match &setting.kind {
+        Kind::Number => value
+            .parse::<u32>()
+            .map(|n| n.to_string())
+            .map_err(|_| format!("expected a whole number, got \"{}\"", value)),
+        Kind::Flag => match value.to_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Ok("true".to_string()),
+            "false" | "no" | "off" | "0" => Ok("false".to_string()),
+            _ => Err(format!("expected true or false, got \"{}\"", value)),
+        },
+        Kind::Choice(choices) => choices
+            .iter()
+            .find(|choice| choice.eq_ignore_ascii_case(value))
+            .map(|choice| choice.to_string())
+            .ok_or_else(|| format!("expected one of {}, got \"{}\"", choices.join(", "), value)),
+        Kind::DateFormat => {
+            if value.is_empty() || StrftimeItems::new(value).any(|item| matches!(item, Item::Error)) {
+                Err(format!("\"{}\" is not a valid date format (try \"%Y-%m-%d\" or \"%d.%m.%Y\")", value))
+            } else {
+                Ok(value.to_string())
+            }
+        }
+        Kind::Path => Ok(match value.strip_prefix("~/") {
+            Some(rest) => std::env::var("HOME").map_or_else(|_| value.to_string(), |home| format!("{}/{}", home, rest)),
+            None => value.to_string(),
+        }),
+        Kind::Theme => {
+            let names = themes::names();
+            names
+                .iter()
+                .find(|name| name.eq_ignore_ascii_case(value))
+                .cloned()
+                .ok_or_else(|| format!("unknown theme \"{}\" (available: {})", value, names.join(", ")))
+        }
+    }
+}
+
+/// `TASKX_` and the key in capitals, such as `TASKX_MAX_BACKUPS`.
+fn env_var(key: &str) -> String {
+    format!("TASKX_{}", key.to_uppercase())
+}
+
+/// The settings in a config file as text, whatever TOML type they were written as,
+/// or why a setting is not a single value.
+fn read_file(path: &Path) -> Result<Vec<(String, Result<String, String>)>> {
+    let table = read_table(path)?;
+    Ok(table
+        .into_iter()
+        .map(|(key, value)| {
+            let text = match value {
+                toml::Value::String(text) => Ok(text),
+                toml::Value::Integer(_) | toml::Value::Boolean(_) | toml::Value::Float(_) => Ok(value.to_string()),
+                _ => Err("must be a single value".to_string()),
+            };
+            (key, text)
+        })
+        .collect())
+}
+
+fn read_table(path: &Path) -> Result<toml::Table> {
+    if !path.exists() {
+        return Ok(toml::Table::new());
+    }
+    let contents = fs::read_to_string(path)?;
+    toml::from_str(&contents).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+fn write_table(path: &Path, table: &toml::Table) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    storage_manager::write_atomic(path, toml::to_string_pretty(table)?.as_bytes())
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// Levenshtein distance, for suggesting a setting on a typo.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb { previous } else { 1 + previous.min(row[j]).min(row[j + 1]) };
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_settings_in_a_file_are_ignored_with_a_warning() {
+        let dir = std::env::temp_dir().join(format!("taskx-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(CONFIG_FILE);
+        fs::write(&path, "storage = \"mysql\"\nmax_backups = [1]\ndata_dir = \"/tmp\"\nbogus = 1\ntheme = \"light\"\n").unwrap();
+
+        let mut config = Config::defaults();
+        config.apply_file(&path, Source::Board);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(config.storage(), "json");
+        assert_eq!(config.max_backups(), 5);
+        assert_eq!(config.data_dir(), None);
+        assert_eq!(config.theme(), "light");
+        assert_eq!(config.warnings().len(), 4);
+        assert!(config.warnings().iter().all(|warning| warning.ends_with("; ignoring it")));
+    }
+
+    #[test]
+    fn an_unreadable_file_is_ignored_with_a_warning() {
+        let dir = std::env::temp_dir().join(format!("taskx-config-broken-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(CONFIG_FILE);
+        fs::write(&path, "storage = ").unwrap();
+
+        let mut config = Config::defaults();
+        config.apply_file(&path, Source::Global);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(config.storage(), "json");
+        assert_eq!(config.warnings().len(), 1);
+    }
+}
@@ -0,0 +1,56 @@
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+
+use crate::config;
+use crate::models::theme::{self, Theme, ThemeFile};
+
+/// Loads a theme by name: a built-in one, or `themes/<name>.toml` in the config
+/// directory, on top of the theme it extends.
+pub fn load(name: &str) -> Result<Theme> {
+    load_from(name, &mut Vec::new())
+}
+
+fn load_from(name: &str, seen: &mut Vec<String>) -> Result<Theme> {
+    let path = config::config_dir()?.join("themes").join(format!("{}.toml", name));
+    if !path.exists() {
+        return Theme::built_in(name)
+            .ok_or_else(|| anyhow!("unknown theme \"{}\" (available: {})", name, names().join(", ")));
+    }
+    if seen.iter().any(|other| other == name) {
+        return Err(anyhow!("themes extend each other in a loop: {} -> {}", seen.join(" -> "), name));
+    }
+    seen.push(name.to_string());
+
+    let contents = fs::read_to_string(&path)?;
+    let file: ThemeFile =
+        toml::from_str(&contents).with_context(|| format!("failed to parse {}", path.display()))?;
+    // A file named after a built-in theme overrides it, so extending its own name means the built-in one
+    let extends = file.extends.as_deref().unwrap_or("dark");
+    let base = if extends == name {
+        Theme::built_in(name)
+            .ok_or_else(|| anyhow!("themes extend each other in a loop: {} -> {}", seen.join(" -> "), name))?
+    } else {
+        load_from(extends, seen)?
+    };
+    Theme::from_file(name, base, &file).map_err(|err| anyhow!("invalid theme in {}: {}", path.display(), err))
+}
+
+/// The built-in themes, then the user's theme files, by name.
+pub fn names() -> Vec<String> {
+    let mut names: Vec<String> = theme::BUILT_IN.iter().map(|name| name.to_string()).collect();
+    let dir = config::config_dir().map(|dir| dir.join("themes"));
+    let mut files: Vec<String> = dir
+        .ok()
+        .and_then(|dir| fs::read_dir(dir).ok())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            (path.extension()? == "toml").then(|| path.file_stem()?.to_str().map(String::from))?
+        })
+        .filter(|name| !names.contains(name))
+        .collect();
+    files.sort();
+    names.extend(files);
+    names
+}
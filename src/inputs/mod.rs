@@ -1,94 +1,255 @@
-use crossterm::event::{self, Event, KeyCode, KeyEvent};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
 use std::time::Duration;
 
-use crate::app::{App, AppResult, InputMode};
-use crate::models::{TaskStatus, TaskPriority};
+use crate::app::{App, AppMode, AppResult, AppTab, InputMode};
+use crate::models::keymap::{Action, KeyChord, KeyMatch, KeyMode};
 
 pub fn event_handler(app: &mut App) -> AppResult<bool> {
-    if event::poll(Duration::from_millis(50))? {
-        if let Event::Key(key) = event::read()? {
-            return match app.input_mode {
-                InputMode::Normal => handle_normal_mode(app, key),
-                InputMode::Editing => handle_editing_mode(app, key),
-            };
-        }
+    if event::poll(Duration::from_millis(50))?
+        && let Event::Key(key) = event::read()?
+    {
+        // Any key press dismisses the current status message
+        app.status_message = None;
+
+        return match app.input_mode {
+            InputMode::Normal => handle_normal_mode(app, key),
+            InputMode::Editing => handle_editing_mode(app, key),
+            InputMode::EditForm => handle_edit_form_mode(app, key),
+            InputMode::Search => handle_search_mode(app, key),
+            InputMode::Command => handle_command_mode(app, key),
+        };
     }
     Ok(false)
 }
 
 fn handle_normal_mode(app: &mut App, key: KeyEvent) -> AppResult<bool> {
-    // If task details is open, handle Escape key differently
-    if app.show_task_details {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+    // The finder takes typing, so it is handled before any key binding
+    if app.finder.is_some() {
         match key.code {
-            KeyCode::Esc | KeyCode::Enter => {
-                app.show_task_details = false;
-                return Ok(false);
-            },
-            _ => return Ok(false), // Ignore other keys when task details is open
+            KeyCode::Esc => app.close_finder(),
+            KeyCode::Enter => app.confirm_finder(),
+            KeyCode::Down => app.select_finder_result(true),
+            KeyCode::Up => app.select_finder_result(false),
+            KeyCode::Char('n') if ctrl => app.select_finder_result(true),
+            KeyCode::Char('p') if ctrl => app.select_finder_result(false),
+            KeyCode::Char(c) if !ctrl => app.finder_input_char(c),
+            KeyCode::Backspace => app.finder_backspace(),
+            _ => {}
         }
+        return Ok(false);
     }
 
-    match key.code {
-        // Quit application
-        KeyCode::Char('q') => return Ok(true),
+    let chord = KeyChord::from(key);
+
+    // Help scrolls with the movement keys and closes with its own key or Esc
+    if app.show_help {
+        match app.keymap.lookup(KeyMode::Normal, &[chord]) {
+            _ if key.code == KeyCode::Esc => app.toggle_help(),
+            KeyMatch::Action(Action::Help) => app.toggle_help(),
+            KeyMatch::Action(Action::Down) => app.help_scroll = app.help_scroll.saturating_add(1),
+            KeyMatch::Action(Action::Up) => app.help_scroll = app.help_scroll.saturating_sub(1),
+            _ => {}
+        }
+        return Ok(false);
+    }
+
+    // The dependency graph sits on top of the task details
+    if app.show_dependency_graph {
+        let graph_key = app.keymap.lookup(KeyMode::Details, &[chord]) == KeyMatch::Action(Action::Graph);
+        if matches!(key.code, KeyCode::Esc | KeyCode::Enter) || graph_key {
+            app.show_dependency_graph = false;
+        }
+        return Ok(false);
+    }
 
-        // Help
-        KeyCode::Char('?') => app.toggle_help(),
+    // Esc abandons a half-typed sequence such as the first `g` of `gg`
+    if key.code == KeyCode::Esc && !app.pending_keys.is_empty() {
+        app.pending_keys.clear();
+        return Ok(false);
+    }
+
+    press_key(app, chord)
+}
+
+/// Adds a key to the pending sequence and runs the action it completes.
+fn press_key(app: &mut App, chord: KeyChord) -> AppResult<bool> {
+    app.pending_keys.push(chord);
+    match lookup_pending(app) {
+        (mode, KeyMatch::Action(action)) => {
+            app.pending_keys.clear();
+            run_action(app, mode, action)
+        }
+        (_, KeyMatch::Pending) => Ok(false),
+        (_, KeyMatch::None) => {
+            // A sequence that went nowhere starts over from its last key
+            let retry = app.pending_keys.len() > 1;
+            app.pending_keys.clear();
+            if retry { press_key(app, chord) } else { Ok(false) }
+        }
+    }
+}
+
+/// The keymap modes in effect, most specific first. Popups take every key; tabs
+/// and the list view or a search add their keys on top of the board's.
+fn active_modes(app: &App) -> Vec<KeyMode> {
+    if app.project_switcher.is_some() {
+        return vec![KeyMode::Projects];
+    }
+    if app.theme_switcher.is_some() {
+        return vec![KeyMode::Themes];
+    }
+    if app.show_task_details {
+        return vec![KeyMode::Details];
+    }
+
+    let mut modes = Vec::new();
+    match app.current_tab {
+        AppTab::Calendar => modes.push(KeyMode::Calendar),
+        AppTab::Trash => modes.push(KeyMode::Trash),
+        _ => {}
+    }
+    if app.is_searching() {
+        modes.push(KeyMode::Search);
+    }
+    if app.mode == AppMode::List {
+        modes.push(KeyMode::List);
+    }
+    modes.push(KeyMode::Normal);
+    modes
+}
+
+/// Looks up the pending keys in each active mode; the first mode that knows them decides.
+fn lookup_pending(app: &App) -> (KeyMode, KeyMatch) {
+    active_modes(app)
+        .into_iter()
+        .map(|mode| (mode, app.keymap.lookup(mode, &app.pending_keys)))
+        .find(|(_, found)| *found != KeyMatch::None)
+        .unwrap_or((KeyMode::Normal, KeyMatch::None))
+}
+
+/// Performs a bound action. Returns true when the app should quit.
+fn run_action(app: &mut App, mode: KeyMode, action: Action) -> AppResult<bool> {
+    match (mode, action) {
+        (_, Action::Quit) => return Ok(true),
+        (_, Action::Help) => app.toggle_help(),
+        (_, Action::Palette) => app.open_palette(),
+        (_, Action::Find) => {
+            app.close_project_switcher();
+            app.show_task_details = false;
+            app.show_dependency_graph = false;
+            app.open_finder();
+        }
+
+        // Project list
+        (KeyMode::Projects, Action::Close) => app.close_project_switcher(),
+        (KeyMode::Projects, Action::Down) => app.select_project(true),
+        (KeyMode::Projects, Action::Up) => app.select_project(false),
+        (KeyMode::Projects, Action::Open) => app.confirm_project_switcher()?,
+        (_, Action::NewProject) => app.start_project_input(),
+
+        // Theme list
+        (KeyMode::Themes, Action::Close) => app.close_theme_switcher(),
+        (KeyMode::Themes, Action::Down) => app.select_theme(true),
+        (KeyMode::Themes, Action::Up) => app.select_theme(false),
+        (KeyMode::Themes, Action::Open) => app.confirm_theme_switcher()?,
+
+        // Task details and its checklist
+        (KeyMode::Details, Action::Close) => app.show_task_details = false,
+        (KeyMode::Details, Action::Down) => app.select_checklist_item(true),
+        (KeyMode::Details, Action::Up) => app.select_checklist_item(false),
+        (_, Action::ToggleItem) => app.toggle_checklist_item()?,
+        (_, Action::AddItem) => app.start_checklist_input(),
+        (_, Action::RemoveItem) => app.remove_checklist_item()?,
+        (_, Action::Graph) => app.show_dependency_graph = true,
+
+        // Calendar tab
+        (KeyMode::Calendar, Action::Left) => app.move_calendar_cursor(-1),
+        (KeyMode::Calendar, Action::Right) => app.move_calendar_cursor(1),
+        (KeyMode::Calendar, Action::Up) => app.move_calendar_cursor(-7),
+        (KeyMode::Calendar, Action::Down) => app.move_calendar_cursor(7),
+        (_, Action::PreviousMonth) => app.move_calendar_month(false),
+        (_, Action::NextMonth) => app.move_calendar_month(true),
+        (_, Action::Today) => app.calendar_today(),
+        (_, Action::ToggleWeek) => app.toggle_calendar_view(),
+        (_, Action::NextTask) => app.cycle_calendar_task(true),
+        (_, Action::PreviousTask) => app.cycle_calendar_task(false),
+        (KeyMode::Calendar, Action::Open) => app.open_calendar_task(),
+        (_, Action::Earlier) => app.reschedule_calendar_task(-1)?,
+        (_, Action::Later) => app.reschedule_calendar_task(1)?,
+
+        // Trash tab
+        (KeyMode::Trash, Action::Down) => app.select_next_trash(),
+        (KeyMode::Trash, Action::Up) => app.select_previous_trash(),
+        (_, Action::Restore) => app.restore_selected_trash()?,
+        (_, Action::Purge) => app.purge_selected_trash()?,
+        (_, Action::EmptyTrash) => app.empty_trash()?,
+
+        // Jump between matches while a search is active
+        (_, Action::NextMatch) => app.jump_to_match(true),
+        (_, Action::PreviousMatch) => app.jump_to_match(false),
+        (_, Action::ClearSearch) => app.clear_search(),
+        (_, Action::Search) => app.start_search(),
+
+        // Fold subtasks in the list view
+        (_, Action::Collapse) => app.collapse_selected(),
+        (_, Action::Expand) => app.expand_selected(),
 
         // Navigation
-        KeyCode::Down | KeyCode::Char('j') => app.select_next_task(),
-        KeyCode::Up | KeyCode::Char('k') => app.select_previous_task(),
-        KeyCode::Right | KeyCode::Char('l') => app.next_column(),
-        KeyCode::Left | KeyCode::Char('h') => app.previous_column(),
+        (_, Action::Down) => app.select_next_task(),
+        (_, Action::Up) => app.select_previous_task(),
+        (_, Action::Right) => app.next_column(),
+        (_, Action::Left) => app.previous_column(),
+        (_, Action::First) => app.select_first_task(),
+        (_, Action::Last) => app.select_last_task(),
 
         // Toggle task details
-        KeyCode::Enter => {
+        (_, Action::Details) => {
             app.show_task_details = !app.show_task_details;
-        },
+            app.checklist_index = 0;
+        }
 
         // Tab navigation
-        KeyCode::Tab => app.next_tab(),
-        KeyCode::BackTab => app.previous_tab(),
+        (_, Action::NextTab) => app.next_tab(),
+        (_, Action::PreviousTab) => app.previous_tab(),
 
-        // Toggle view mode
-        KeyCode::Char('v') => app.toggle_mode(),
+        (_, Action::ToggleMode) => app.toggle_mode(),
+        (_, Action::Edit) => app.open_edit_form(),
+        (_, Action::NextStatus) => app.move_task_status()?,
+        (_, Action::Undo) => app.undo()?,
+        (_, Action::Redo) => app.redo()?,
+        (_, Action::Delete) => app.delete_selected_task()?,
 
-        // Enter edit mode
-        KeyCode::Char('e') | KeyCode::Char('i') => {
+        // New task, or a subtask of the selected one
+        (_, Action::NewTask) => {
+            app.new_task_input.clear();
             app.toggle_input_mode();
         }
+        (_, Action::NewSubtask) => app.start_subtask_input(),
 
-        // Move task status
-        KeyCode::Char(' ') => {
-            app.move_task_status()?;
-        }
+        // Switch project, or move the selected task to another one
+        (_, Action::SwitchProject) => app.open_project_switcher(false)?,
+        (_, Action::MoveToProject) => app.open_project_switcher(true)?,
+        (_, Action::SwitchTheme) => app.open_theme_switcher(),
 
-        // New task
-        KeyCode::Char('n') => {
-            app.new_task_input.clear();
-            app.toggle_input_mode();
-        }
+        // Start / stop the timer, optionally with a note
+        (_, Action::Timer) => app.toggle_timer()?,
+        (_, Action::TimerNote) => app.start_timer_note_input(),
 
-        // Toggle filter
-        KeyCode::Char('f') => {
-            app.filter_active = !app.filter_active;
-        }
+        // Toggle filter, or edit its query
+        (_, Action::ToggleFilter) => app.toggle_filter(),
+        (_, Action::EditFilter) => app.start_filter_input(),
 
-        // Filter by status
-        KeyCode::Char('1') => {
-            app.status_filter = Some(TaskStatus::Todo);
-            app.filter_active = true;
-        }
-        KeyCode::Char('2') => {
-            app.status_filter = Some(TaskStatus::InProgress);
-            app.filter_active = true;
-        }
-        KeyCode::Char('3') => {
-            app.status_filter = Some(TaskStatus::Done);
-            app.filter_active = true;
-        }
+        // Save the filter as a pinned view, or unpin the open view
+        (_, Action::SaveView) if app.active_view.is_some() && !app.filter_active => app.unpin_active_view()?,
+        (_, Action::SaveView) => app.start_view_input(),
 
-        _ => {}
+        // Filter by the nth workflow status
+        (_, Action::FilterStatus(n)) => app.filter_by_status(usize::from(n).saturating_sub(1)),
+
+        // Only reachable from a mode the action is not offered in
+        (_, Action::Close | Action::Open) => {}
     }
     Ok(false)
 }
@@ -100,19 +261,11 @@ fn handle_editing_mode(app: &mut App, key: KeyEvent) -> AppResult<bool> {
             app.toggle_input_mode();
         }
 
-        // Submit new task
-        KeyCode::Enter => {
-            if !app.new_task_input.is_empty() {
-                app.add_task(
-                    app.new_task_input.clone(),
-                    "".to_string(),
-                    TaskStatus::Todo,
-                    TaskPriority::Medium,
-                );
-                app.save_tasks()?;
-                app.new_task_input.clear();
-                app.toggle_input_mode();
-            }
+        // Submit new task, subtask or checklist item
+        KeyCode::Enter if !app.new_task_input.trim().is_empty() => {
+            app.submit_input()?;
+            app.new_task_input.clear();
+            app.input_mode = InputMode::Normal;
         }
 
         // Edit text
@@ -127,4 +280,70 @@ fn handle_editing_mode(app: &mut App, key: KeyEvent) -> AppResult<bool> {
         _ => {}
     }
     Ok(false)
-}
\ No newline at end of file
+}
+
+fn handle_search_mode(app: &mut App, key: KeyEvent) -> AppResult<bool> {
+    match key.code {
+        KeyCode::Esc => app.cancel_search(),
+        KeyCode::Enter => app.confirm_search()?,
+
+        // Earlier searches
+        KeyCode::Up => app.browse_search_history(true),
+        KeyCode::Down => app.browse_search_history(false),
+
+        // Edit the query; the board filters as it changes
+        KeyCode::Char(c) => app.search_input_char(c),
+        KeyCode::Backspace => app.search_backspace(),
+
+        _ => {}
+    }
+    Ok(false)
+}
+
+fn handle_command_mode(app: &mut App, key: KeyEvent) -> AppResult<bool> {
+    match key.code {
+        KeyCode::Esc => app.close_palette(),
+        KeyCode::Enter => return app.submit_palette(),
+
+        // Complete the command or argument being typed
+        KeyCode::Tab => app.complete_palette(true),
+        KeyCode::BackTab => app.complete_palette(false),
+
+        // Earlier commands
+        KeyCode::Up => app.browse_command_history(true),
+        KeyCode::Down => app.browse_command_history(false),
+
+        KeyCode::Char(c) => app.palette_input_char(c),
+        KeyCode::Backspace => app.palette_backspace(),
+
+        _ => {}
+    }
+    Ok(false)
+}
+
+fn handle_edit_form_mode(app: &mut App, key: KeyEvent) -> AppResult<bool> {
+    let Some(form) = app.edit_form.as_mut() else {
+        app.cancel_edit_form();
+        return Ok(false);
+    };
+
+    match key.code {
+        KeyCode::Esc => app.cancel_edit_form(),
+        KeyCode::Enter => app.save_edit_form()?,
+
+        // Move between fields
+        KeyCode::Tab | KeyCode::Down => form.next_field(),
+        KeyCode::BackTab | KeyCode::Up => form.previous_field(),
+
+        // Status and priority pickers
+        KeyCode::Right | KeyCode::Char(' ') if form.focused.is_picker() => form.cycle_option(true),
+        KeyCode::Left if form.focused.is_picker() => form.cycle_option(false),
+
+        // Edit text
+        KeyCode::Char(c) => form.input_char(c),
+        KeyCode::Backspace => form.backspace(),
+
+        _ => {}
+    }
+    Ok(false)
+}
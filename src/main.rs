@@ -1,12 +1,16 @@
 mod app;
+mod cli;
+mod config;
 mod models;
 mod ui;
 mod inputs;
+mod stats;
 mod storage;
 mod utils;
 
-use anyhow::{Result, anyhow};
+use anyhow::Result;
 use app::{App, AppResult};
+use clap::Parser;
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture},
     execute,
@@ -14,9 +18,31 @@ use crossterm::{
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::io;
+use std::process::ExitCode;
 use inputs::event_handler;
 
-fn main() -> Result<()> {
+fn main() -> ExitCode {
+    let cli = cli::Cli::parse();
+
+    // Subcommands run headless; without one we open the board
+    let result = config::init(&cli.overrides).and_then(|_| match cli.command {
+        Some(command) => cli::run(command, &cli.project),
+        None => run_tui(&cli.project),
+    });
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {:#}", err);
+            cli::exit_code(&err)
+        }
+    }
+}
+
+fn run_tui(project: &str) -> Result<()> {
+    // Open storage before touching the terminal so errors print normally
+    let storage = storage::open(project)?;
+
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -25,12 +51,8 @@ fn main() -> Result<()> {
     let mut terminal = Terminal::new(backend)?;
 
     // Create app state
-    let mut app = App::new();
-    app.load_tasks()?;
-
-
-    // Main loop
-    let res = run_app(&mut terminal, &mut app);
+    let mut app = App::new(storage, project);
+    let res = app.load_tasks().and_then(|_| run_app(&mut terminal, &mut app));
 
     // Restore terminal
     disable_raw_mode()?;
@@ -41,12 +63,7 @@ fn main() -> Result<()> {
     )?;
     terminal.show_cursor()?;
 
-    if let Err(err) = res {
-        eprintln!("{:?}", err);
-        return Err(anyhow!(err));
-    }
-
-    Ok(())
+    res
 }
 
 fn run_app<B: ratatui::backend::Backend>(
@@ -54,6 +71,7 @@ fn run_app<B: ratatui::backend::Backend>(
     app: &mut App,
 ) -> AppResult<()> {
     loop {
+        app.check_config();
         terminal.draw(|frame| ui::render(frame, app))?;
 
         if event_handler(app)? {
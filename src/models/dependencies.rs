@@ -0,0 +1,132 @@
+use std::collections::HashSet;
+
+use crate::models::workflow::Workflow;
+use crate::models::Task;
+
+/// Tasks `task` waits on. Ids of tasks that no longer exist are skipped.
+pub fn prerequisites<'a>(tasks: &'a [Task], task: &'a Task) -> impl Iterator<Item = &'a Task> + 'a {
+    task.blocked_by
+        .iter()
+        .filter_map(move |id| tasks.iter().find(|t| &t.id == id))
+}
+
+/// Tasks that wait on `task`.
+pub fn dependents<'a>(tasks: &'a [Task], task: &'a Task) -> impl Iterator<Item = &'a Task> + 'a {
+    tasks.iter().filter(move |t| t.blocked_by.contains(&task.id))
+}
+
+/// Prerequisites of `task` that are not in a done status yet.
+pub fn open_prerequisites<'a>(tasks: &'a [Task], task: &'a Task, workflow: &'a Workflow) -> Vec<&'a Task> {
+    prerequisites(tasks, task)
+        .filter(|t| !workflow.is_done(&t.status))
+        .collect()
+}
+
+pub fn is_blocked(tasks: &[Task], task: &Task, workflow: &Workflow) -> bool {
+    prerequisites(tasks, task).any(|t| !workflow.is_done(&t.status))
+}
+
+/// Whether `from` waits on `target`, directly or through other tasks.
+pub fn depends_on(tasks: &[Task], from: &str, target: &str) -> bool {
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut stack = vec![from];
+    while let Some(id) = stack.pop() {
+        if !seen.insert(id) {
+            continue;
+        }
+        let Some(task) = tasks.iter().find(|t| t.id == id) else {
+            continue;
+        };
+        for prerequisite in &task.blocked_by {
+            if prerequisite == target {
+                return true;
+            }
+            stack.push(prerequisite);
+        }
+    }
+    false
+}
+
+/// Checks that `task_id` may wait on `prerequisite_id` without creating a cycle.
+pub fn check_dependency(tasks: &[Task], task_id: &str, prerequisite_id: &str) -> Result<(), String> {
+    if task_id == prerequisite_id {
+        return Err("A task cannot block itself".to_string());
+    }
+    if depends_on(tasks, prerequisite_id, task_id) {
+        let title = tasks.iter().find(|t| t.id == prerequisite_id).map_or("", |t| t.title.as_str());
+        return Err(format!("\"{}\" already waits on this task; that would be a cycle", title));
+    }
+    Ok(())
+}
+
+/// Turns comma or space separated id prefixes into prerequisite ids for `task_id`,
+/// rejecting unknown or ambiguous prefixes and cycles.
+pub fn resolve_prerequisites(tasks: &[Task], task_id: &str, input: &str) -> Result<Vec<String>, String> {
+    let mut ids: Vec<String> = Vec::new();
+    for prefix in input.split([',', ' ']).map(|p| p.trim().trim_start_matches('#')).filter(|p| !p.is_empty()) {
+        let matches: Vec<&Task> = tasks.iter().filter(|task| task.id.starts_with(prefix)).collect();
+        let prerequisite = match matches.as_slice() {
+            [] => return Err(format!("No task id starts with \"{}\"", prefix)),
+            [task] => *task,
+            _ => return Err(format!("Task id prefix \"{}\" is ambiguous", prefix)),
+        };
+        check_dependency(tasks, task_id, &prerequisite.id)?;
+        if !ids.contains(&prerequisite.id) {
+            ids.push(prerequisite.id.clone());
+        }
+    }
+    Ok(ids)
+}
+
+/// One line of a dependency tree: the connector drawn before the task, and
+/// whether the task was already expanded higher up.
+pub struct GraphLine<'a> {
+    pub prefix: String,
+    pub task: &'a Task,
+    pub repeated: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// What the task waits on.
+    Upstream,
+    /// What waits on the task.
+    Downstream,
+}
+
+/// The dependency graph around `root` drawn as a tree in the style of `cargo tree`.
+/// A task reached a second time is listed once more, marked `repeated`, and not expanded again.
+pub fn graph_lines<'a>(tasks: &'a [Task], root: &'a Task, direction: Direction) -> Vec<GraphLine<'a>> {
+    let mut lines = vec![GraphLine { prefix: String::new(), task: root, repeated: false }];
+    let mut expanded: HashSet<&str> = HashSet::from([root.id.as_str()]);
+    walk(tasks, root, direction, "", &mut expanded, &mut lines);
+    lines
+}
+
+fn walk<'a>(
+    tasks: &'a [Task],
+    task: &'a Task,
+    direction: Direction,
+    indent: &str,
+    expanded: &mut HashSet<&'a str>,
+    lines: &mut Vec<GraphLine<'a>>,
+) {
+    let next: Vec<&Task> = match direction {
+        Direction::Upstream => prerequisites(tasks, task).collect(),
+        Direction::Downstream => dependents(tasks, task).collect(),
+    };
+
+    for (i, child) in next.iter().enumerate() {
+        let last = i + 1 == next.len();
+        let repeated = !expanded.insert(child.id.as_str());
+        lines.push(GraphLine {
+            prefix: format!("{}{}", indent, if last { "└── " } else { "├── " }),
+            task: child,
+            repeated,
+        });
+        if !repeated {
+            let indent = format!("{}{}", indent, if last { "    " } else { "│   " });
+            walk(tasks, child, direction, &indent, expanded, lines);
+        }
+    }
+}
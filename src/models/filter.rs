@@ -0,0 +1,366 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use crate::models::workflow::Workflow;
+use crate::models::{dependencies, search, Task, TaskPriority};
+
+/// A parsed task query such as `status:todo priority>=high tag:backend due<7d
+/// -tag:blocked "login bug"`. Every term must hold for a task to match; a leading
+/// `-` negates a term, and bare words or quoted phrases search the text.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Filter {
+    pub terms: Vec<Term>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Term {
+    pub negated: bool,
+    pub condition: Condition,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    /// Status name as typed; resolved against the workflow when matching.
+    Status(String),
+    Priority(Comparison, TaskPriority),
+    Tag(String),
+    Due(Comparison, DueDate),
+    /// Tasks without a due date.
+    NoDue,
+    Points(Comparison, u32),
+    Is(State),
+    /// Text in the title, description or tags.
+    Text(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Equal,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+/// A due date bound, either fixed or relative to the day the filter is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DueDate {
+    On(NaiveDate),
+    /// This many days from today; negative for the past.
+    InDays(i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    Done,
+    Open,
+    Blocked,
+    Overdue,
+    Recurring,
+    Running,
+}
+
+impl Filter {
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Fails on a status the workflow does not know, which would otherwise match nothing.
+    pub fn check(&self, workflow: &Workflow) -> Result<(), String> {
+        for term in &self.terms {
+            if let Condition::Status(name) = &term.condition {
+                workflow.resolve(name)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether matching needs the whole board rather than just the candidate tasks.
+    pub fn needs_board(&self) -> bool {
+        self.terms.iter().any(|term| term.condition == Condition::Is(State::Blocked))
+    }
+
+    /// Whether `task` satisfies every term. `tasks` is the whole board, needed to
+    /// tell whether a task is blocked.
+    pub fn matches(&self, task: &Task, tasks: &[Task], workflow: &Workflow, now: DateTime<Utc>) -> bool {
+        self.terms.iter().all(|term| term.condition.matches(task, tasks, workflow, now) != term.negated)
+    }
+}
+
+impl Condition {
+    fn matches(&self, task: &Task, tasks: &[Task], workflow: &Workflow, now: DateTime<Utc>) -> bool {
+        match self {
+            Condition::Status(name) => workflow.resolve(name).is_ok_and(|status| status == task.status),
+            Condition::Priority(comparison, priority) => comparison.holds(task.priority.cmp(priority)),
+            Condition::Tag(tag) => task.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)),
+            Condition::Due(comparison, bound) => task.due_date.is_some_and(|due| {
+                let day = due.with_timezone(&Local).date_naive();
+                comparison.holds(day.cmp(&bound.resolve(now)))
+            }),
+            Condition::NoDue => task.due_date.is_none(),
+            Condition::Points(comparison, points) => task.points.is_some_and(|p| comparison.holds(p.cmp(points))),
+            Condition::Is(state) => match state {
+                State::Done => workflow.is_done(&task.status),
+                State::Open => !workflow.is_done(&task.status),
+                State::Blocked => dependencies::is_blocked(tasks, task, workflow),
+                State::Overdue => task.is_overdue(workflow),
+                State::Recurring => task.recurrence.is_some(),
+                State::Running => task.running_timer().is_some(),
+            },
+            Condition::Text(text) => search::matches(task, text),
+        }
+    }
+}
+
+impl Comparison {
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Comparison::Equal => ordering == Ordering::Equal,
+            Comparison::Less => ordering == Ordering::Less,
+            Comparison::LessOrEqual => ordering != Ordering::Greater,
+            Comparison::Greater => ordering == Ordering::Greater,
+            Comparison::GreaterOrEqual => ordering != Ordering::Less,
+        }
+    }
+}
+
+impl DueDate {
+    fn resolve(self, now: DateTime<Utc>) -> NaiveDate {
+        match self {
+            DueDate::On(date) => date,
+            DueDate::InDays(days) => now.with_timezone(&Local).date_naive() + Duration::days(days),
+        }
+    }
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let terms = tokenize(s)?.iter().map(|token| parse_term(token)).collect::<Result<_, _>>()?;
+        Ok(Filter { terms })
+    }
+}
+
+/// Splits a query on whitespace, keeping double-quoted parts together. The quotes
+/// are dropped, so `tag:"needs review"` becomes one token.
+fn tokenize(input: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut in_token = false;
+    for c in input.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_token = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_token {
+                    tokens.push(std::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_token = true;
+            }
+        }
+    }
+    if quoted {
+        return Err("unclosed quote in query".to_string());
+    }
+    if in_token {
+        tokens.push(current);
+    }
+    Ok(tokens)
+}
+
+fn parse_term(token: &str) -> Result<Term, String> {
+    let (negated, body) = match token.strip_prefix('-') {
+        Some(rest) if !rest.is_empty() => (true, rest),
+        _ => (false, token),
+    };
+    let Some((key, comparison, value)) = split_comparison(body) else {
+        return Ok(Term { negated, condition: Condition::Text(body.to_string()) });
+    };
+
+    let exact_only = |condition: Condition| {
+        if comparison == Comparison::Equal {
+            Ok(condition)
+        } else {
+            Err(format!("\"{}\" only supports \":\"", key))
+        }
+    };
+    let condition = match key.to_lowercase().as_str() {
+        "status" | "s" => exact_only(Condition::Status(value.to_string()))?,
+        "priority" | "p" => Condition::Priority(comparison, value.parse()?),
+        "tag" | "t" => exact_only(Condition::Tag(value.to_string()))?,
+        "due" if value.eq_ignore_ascii_case("none") => exact_only(Condition::NoDue)?,
+        "due" => Condition::Due(comparison, parse_due(value)?),
+        "points" | "pts" => {
+            let points = value.parse().map_err(|_| format!("\"{}\" is not a number of points", value))?;
+            Condition::Points(comparison, points)
+        }
+        "is" => exact_only(Condition::Is(parse_state(value)?))?,
+        // Not a field; treat it as text like "10:30"
+        _ => Condition::Text(body.to_string()),
+    };
+    Ok(Term { negated, condition })
+}
+
+/// `key`, comparison and value of a `key:value` or `key>=value` term.
+fn split_comparison(term: &str) -> Option<(&str, Comparison, &str)> {
+    let start = term.find([':', '=', '<', '>'])?;
+    let (key, rest) = term.split_at(start);
+    let (comparison, value) = [
+        (">=", Comparison::GreaterOrEqual),
+        ("<=", Comparison::LessOrEqual),
+        (">", Comparison::Greater),
+        ("<", Comparison::Less),
+        (":", Comparison::Equal),
+        ("=", Comparison::Equal),
+    ]
+    .into_iter()
+    .find_map(|(op, comparison)| rest.strip_prefix(op).map(|value| (comparison, value)))?;
+    (!key.is_empty() && !value.is_empty()).then_some((key, comparison, value))
+}
+
+/// `today`, `tomorrow`, `yesterday`, a number of days or weeks from today such as
+/// `7d`, `-3d` or `2w`, or a date as YYYY-MM-DD.
+fn parse_due(value: &str) -> Result<DueDate, String> {
+    let lower = value.to_lowercase();
+    match lower.as_str() {
+        "today" => return Ok(DueDate::InDays(0)),
+        "tomorrow" => return Ok(DueDate::InDays(1)),
+        "yesterday" => return Ok(DueDate::InDays(-1)),
+        _ => {}
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(DueDate::On(date));
+    }
+
+    let invalid = || format!("invalid due date \"{}\" (expected e.g. today, 7d, 2w or YYYY-MM-DD)", value);
+    let (number, days_per_unit) = match (lower.strip_suffix('d'), lower.strip_suffix('w')) {
+        (Some(number), _) => (number, 1),
+        (_, Some(number)) => (number, 7),
+        _ => return Err(invalid()),
+    };
+    let number: i64 = number.parse().map_err(|_| invalid())?;
+    number.checked_mul(days_per_unit).filter(|days| days.abs() <= 100_000).map(DueDate::InDays).ok_or_else(invalid)
+}
+
+fn parse_state(value: &str) -> Result<State, String> {
+    match value.to_lowercase().as_str() {
+        "done" => Ok(State::Done),
+        "open" => Ok(State::Open),
+        "blocked" => Ok(State::Blocked),
+        "overdue" => Ok(State::Overdue),
+        "recurring" => Ok(State::Recurring),
+        "running" => Ok(State::Running),
+        _ => Err(format!(
+            "unknown state \"{}\" (expected done, open, blocked, overdue, recurring or running)",
+            value
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TaskStatus;
+
+    fn parse(query: &str) -> Vec<Term> {
+        query.parse::<Filter>().unwrap().terms
+    }
+
+    fn term(negated: bool, condition: Condition) -> Term {
+        Term { negated, condition }
+    }
+
+    #[test]
+    fn leading_dash_negates() {
+        assert_eq!(parse("-tag:blocked"), vec![term(true, Condition::Tag("blocked".to_string()))]);
+        assert_eq!(parse("-is:done"), vec![term(true, Condition::Is(State::Done))]);
+        // A lone dash is just text
+        assert_eq!(parse("-"), vec![term(false, Condition::Text("-".to_string()))]);
+    }
+
+    #[test]
+    fn quotes_keep_phrases_together() {
+        assert_eq!(
+            parse("\"login bug\" tag:\"needs review\""),
+            vec![
+                term(false, Condition::Text("login bug".to_string())),
+                term(false, Condition::Tag("needs review".to_string())),
+            ]
+        );
+        assert_eq!(tokenize("  a   b ").unwrap(), vec!["a", "b"]);
+        assert_eq!(tokenize("\"\"").unwrap(), vec![""]);
+    }
+
+    #[test]
+    fn unclosed_quote_is_an_error() {
+        assert_eq!("tag:\"needs review".parse::<Filter>(), Err("unclosed quote in query".to_string()));
+    }
+
+    #[test]
+    fn priority_comparisons() {
+        assert_eq!(parse("priority>=high"), vec![term(false, Condition::Priority(Comparison::GreaterOrEqual, TaskPriority::High))]);
+        assert_eq!(parse("p<medium"), vec![term(false, Condition::Priority(Comparison::Less, TaskPriority::Medium))]);
+        assert_eq!(parse("priority:crit"), vec![term(false, Condition::Priority(Comparison::Equal, TaskPriority::Critical))]);
+        assert!("priority>=urgent".parse::<Filter>().is_err());
+    }
+
+    #[test]
+    fn due_dates() {
+        assert_eq!(parse("due<7d"), vec![term(false, Condition::Due(Comparison::Less, DueDate::InDays(7)))]);
+        assert_eq!(parse("due>=2w"), vec![term(false, Condition::Due(Comparison::GreaterOrEqual, DueDate::InDays(14)))]);
+        assert_eq!(parse("due:-3d"), vec![term(false, Condition::Due(Comparison::Equal, DueDate::InDays(-3)))]);
+        assert_eq!(
+            parse("due<=2026-11-01"),
+            vec![term(false, Condition::Due(Comparison::LessOrEqual, DueDate::On(NaiveDate::from_ymd_opt(2026, 11, 1).unwrap())))]
+        );
+        assert_eq!(parse("due:none"), vec![term(false, Condition::NoDue)]);
+        assert_eq!(parse("-due:NONE"), vec![term(true, Condition::NoDue)]);
+        assert!("due<none".parse::<Filter>().is_err());
+        assert!("due<soon".parse::<Filter>().is_err());
+        assert!("due<99999999d".parse::<Filter>().is_err());
+    }
+
+    #[test]
+    fn unknown_keys_are_text() {
+        assert_eq!(parse("10:30"), vec![term(false, Condition::Text("10:30".to_string()))]);
+        assert_eq!(parse("-owner:me"), vec![term(true, Condition::Text("owner:me".to_string()))]);
+        assert_eq!(parse("a<b"), vec![term(false, Condition::Text("a<b".to_string()))]);
+        // A key without a value is not a comparison either
+        assert_eq!(parse("tag:"), vec![term(false, Condition::Text("tag:".to_string()))]);
+    }
+
+    #[test]
+    fn exact_only_keys_reject_comparisons() {
+        assert!("tag>backend".parse::<Filter>().is_err());
+        assert!("status<=done".parse::<Filter>().is_err());
+        assert!("is:sleeping".parse::<Filter>().is_err());
+    }
+
+    #[test]
+    fn matches_every_term() {
+        let workflow = Workflow::default();
+        let now = Utc::now();
+        let mut urgent = Task::new("Fix login bug", "", TaskStatus::new("Todo"), TaskPriority::High);
+        urgent.tags = vec!["backend".to_string()];
+        urgent.due_date = Some(now + Duration::days(2));
+        let later = Task::new("Write docs", "", TaskStatus::new("Todo"), TaskPriority::Low);
+        let tasks = vec![urgent.clone(), later.clone()];
+
+        let filter: Filter = "priority>=high due<7d -is:done \"login\"".parse().unwrap();
+        assert!(filter.matches(&urgent, &tasks, &workflow, now));
+        assert!(!filter.matches(&later, &tasks, &workflow, now));
+
+        let filter: Filter = "due:none -tag:backend".parse().unwrap();
+        assert!(!filter.matches(&urgent, &tasks, &workflow, now));
+        assert!(filter.matches(&later, &tasks, &workflow, now));
+    }
+}
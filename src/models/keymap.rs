@@ -0,0 +1,710 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// Where a binding applies. Normal holds the board keys; List and Search sit on
+/// top of it while the list view or a search is active, Calendar and Trash while
+/// their tab is open. Details, Projects and Themes are popups that take every key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, EnumIter)]
+pub enum KeyMode {
+    Normal,
+    List,
+    Search,
+    Calendar,
+    Trash,
+    Details,
+    Projects,
+    Themes,
+}
+
+impl KeyMode {
+    /// Table name in `keymap.toml`.
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyMode::Normal => "normal",
+            KeyMode::List => "list",
+            KeyMode::Search => "search",
+            KeyMode::Calendar => "calendar",
+            KeyMode::Trash => "trash",
+            KeyMode::Details => "details",
+            KeyMode::Projects => "projects",
+            KeyMode::Themes => "themes",
+        }
+    }
+
+    /// Heading in the help popup.
+    pub fn title(self) -> &'static str {
+        match self {
+            KeyMode::Normal => "Tasks",
+            KeyMode::List => "List view",
+            KeyMode::Search => "While searching",
+            KeyMode::Calendar => "Calendar tab",
+            KeyMode::Trash => "Trash tab",
+            KeyMode::Details => "Task details",
+            KeyMode::Projects => "Project list",
+            KeyMode::Themes => "Theme list",
+        }
+    }
+
+    /// Actions that can be bound in this mode, in help order.
+    pub fn actions(self) -> &'static [Action] {
+        use Action::*;
+        match self {
+            KeyMode::Normal => &[
+                Up, Down, Left, Right, First, Last, Details, NextTab, PreviousTab, Find,
+                NewTask, NewSubtask, Edit, NextStatus, Delete, Undo, Redo, Timer, TimerNote,
+                SwitchProject, MoveToProject, SwitchTheme,
+                ToggleMode, ToggleFilter, EditFilter, SaveView, Search,
+                FilterStatus(1), FilterStatus(2), FilterStatus(3), FilterStatus(4), FilterStatus(5),
+                FilterStatus(6), FilterStatus(7), FilterStatus(8), FilterStatus(9),
+                Palette, Help, Quit,
+            ],
+            KeyMode::List => &[Collapse, Expand],
+            KeyMode::Search => &[NextMatch, PreviousMatch, ClearSearch],
+            KeyMode::Calendar => &[
+                Left, Right, Up, Down, PreviousMonth, NextMonth, Today, ToggleWeek,
+                NextTask, PreviousTask, Open, Earlier, Later,
+            ],
+            KeyMode::Trash => &[Up, Down, Restore, Purge, EmptyTrash],
+            KeyMode::Details => &[
+                Close, Edit, Up, Down, ToggleItem, AddItem, RemoveItem, Graph, Timer, TimerNote, Find,
+            ],
+            KeyMode::Projects => &[Close, Up, Down, Open, NewProject, Find],
+            KeyMode::Themes => &[Close, Up, Down, Open],
+        }
+    }
+
+    /// Built-in bindings, written the way `keymap.toml` spells keys.
+    fn defaults(self) -> &'static [(&'static str, Action)] {
+        use Action::*;
+        match self {
+            KeyMode::Normal => &[
+                ("up", Up), ("k", Up), ("down", Down), ("j", Down),
+                ("left", Left), ("h", Left), ("right", Right), ("l", Right),
+                ("gg", First), ("G", Last),
+                ("enter", Details), ("tab", NextTab), ("shift-tab", PreviousTab), ("ctrl-p", Find),
+                ("n", NewTask), ("N", NewSubtask), ("e", Edit), ("i", Edit), ("space", NextStatus),
+                ("d", Delete), ("u", Undo), ("ctrl-r", Redo), ("t", Timer), ("T", TimerNote),
+                ("p", SwitchProject), ("M", MoveToProject), ("C", SwitchTheme),
+                ("v", ToggleMode), ("f", ToggleFilter), ("F", EditFilter), ("V", SaveView), ("/", Search),
+                ("1", FilterStatus(1)), ("2", FilterStatus(2)), ("3", FilterStatus(3)),
+                ("4", FilterStatus(4)), ("5", FilterStatus(5)), ("6", FilterStatus(6)),
+                ("7", FilterStatus(7)), ("8", FilterStatus(8)), ("9", FilterStatus(9)),
+                (":", Palette), ("?", Help), ("q", Quit),
+            ],
+            KeyMode::List => &[("left", Collapse), ("h", Collapse), ("right", Expand), ("l", Expand)],
+            KeyMode::Search => &[("n", NextMatch), ("N", PreviousMatch), ("esc", ClearSearch)],
+            KeyMode::Calendar => &[
+                ("left", Left), ("h", Left), ("right", Right), ("l", Right),
+                ("up", Up), ("k", Up), ("down", Down), ("j", Down),
+                ("[", PreviousMonth), ("]", NextMonth), ("t", Today), ("w", ToggleWeek),
+                ("J", NextTask), ("K", PreviousTask), ("enter", Open), ("<", Earlier), (">", Later),
+            ],
+            KeyMode::Trash => &[
+                ("up", Up), ("k", Up), ("down", Down), ("j", Down),
+                ("r", Restore), ("x", Purge), ("X", EmptyTrash),
+            ],
+            KeyMode::Details => &[
+                ("esc", Close), ("enter", Close), ("e", Edit), ("i", Edit),
+                ("up", Up), ("k", Up), ("down", Down), ("j", Down),
+                ("space", ToggleItem), ("a", AddItem), ("x", RemoveItem), ("g", Graph),
+                ("t", Timer), ("T", TimerNote), ("ctrl-p", Find),
+            ],
+            KeyMode::Projects => &[
+                ("esc", Close), ("p", Close), ("up", Up), ("k", Up), ("down", Down), ("j", Down),
+                ("enter", Open), ("n", NewProject), ("ctrl-p", Find),
+            ],
+            KeyMode::Themes => &[
+                ("esc", Close), ("C", Close), ("up", Up), ("k", Up), ("down", Down), ("j", Down), ("enter", Open),
+            ],
+        }
+    }
+}
+
+/// Something a key can do. What a shared action such as `down` moves depends on
+/// the mode it is bound in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    First,
+    Last,
+    Details,
+    NextTab,
+    PreviousTab,
+    Find,
+    NewTask,
+    NewSubtask,
+    Edit,
+    NextStatus,
+    Delete,
+    Undo,
+    Redo,
+    Timer,
+    TimerNote,
+    SwitchProject,
+    MoveToProject,
+    SwitchTheme,
+    ToggleMode,
+    ToggleFilter,
+    EditFilter,
+    SaveView,
+    Search,
+    /// Filter by the nth workflow status, counting from 1.
+    FilterStatus(u8),
+    Palette,
+    Help,
+    Quit,
+    Collapse,
+    Expand,
+    NextMatch,
+    PreviousMatch,
+    ClearSearch,
+    PreviousMonth,
+    NextMonth,
+    Today,
+    ToggleWeek,
+    NextTask,
+    PreviousTask,
+    Open,
+    Earlier,
+    Later,
+    Restore,
+    Purge,
+    EmptyTrash,
+    Close,
+    ToggleItem,
+    AddItem,
+    RemoveItem,
+    Graph,
+    NewProject,
+}
+
+impl Action {
+    /// What the action does in `mode`, for the help popup.
+    pub fn description(self, mode: KeyMode) -> String {
+        use Action::*;
+        if let FilterStatus(n) = self {
+            return format!("Filter by workflow status {}", n);
+        }
+        let text = match (mode, self) {
+            (KeyMode::Calendar, Left) => "Previous day",
+            (KeyMode::Calendar, Right) => "Next day",
+            (KeyMode::Calendar, Up) => "Previous week",
+            (KeyMode::Calendar, Down) => "Next week",
+            (KeyMode::Details, Up) => "Previous checklist item",
+            (KeyMode::Details, Down) => "Next checklist item",
+            (KeyMode::Projects, Open) => "Switch to the project (or move the task there)",
+            (KeyMode::Themes, Up) => "Preview the previous theme",
+            (KeyMode::Themes, Down) => "Preview the next theme",
+            (KeyMode::Themes, Open) => "Keep the theme",
+            (KeyMode::Themes, Close) => "Close and go back to the theme before",
+            (_, Up) => "Move selection up",
+            (_, Down) => "Move selection down",
+            (_, Left) => "Previous column (Kanban view)",
+            (_, Right) => "Next column (Kanban view)",
+            (_, First) => "Go to the first task (in the column)",
+            (_, Last) => "Go to the last task (in the column)",
+            (_, Details) => "Toggle task details",
+            (_, NextTab) => "Next tab",
+            (_, PreviousTab) => "Previous tab",
+            (_, Find) => "Find any task by fuzzy title, tag or id",
+            (_, NewTask) => "Create new task",
+            (_, NewSubtask) => "Create subtask of the selected task",
+            (_, Edit) => "Edit task (Tab to move between fields, Enter to save)",
+            (_, NextStatus) => "Move task to next status",
+            (_, Delete) => "Move task to trash",
+            (_, Undo) => "Undo last change",
+            (_, Redo) => "Redo",
+            (_, Timer) => "Start or stop timer",
+            (_, TimerNote) => "Start or stop timer with a note",
+            (_, SwitchProject) => "Switch project",
+            (_, MoveToProject) => "Move task to another project",
+            (_, SwitchTheme) => "Switch theme",
+            (_, ToggleMode) => "Toggle view mode (Kanban/List/Detail)",
+            (_, ToggleFilter) => "Toggle the filter on / off",
+            (_, EditFilter) => "Edit the filter, e.g. status:todo priority>=high due<7d -tag:x",
+            (_, SaveView) => "Save the filter as a view tab / unpin the open view",
+            (_, Search) => "Search titles, descriptions and tags (↑↓ history)",
+            (_, FilterStatus(_)) => "",
+            (_, Palette) => "Command palette (Tab completes, e.g. :sort due, :export csv f.csv)",
+            (_, Help) => "Toggle help",
+            (_, Quit) => "Quit",
+            (_, Collapse) => "Fold subtasks",
+            (_, Expand) => "Unfold subtasks",
+            (_, NextMatch) => "Next match",
+            (_, PreviousMatch) => "Previous match",
+            (_, ClearSearch) => "Clear the search",
+            (_, PreviousMonth) => "Previous month",
+            (_, NextMonth) => "Next month",
+            (_, Today) => "Go to today",
+            (_, ToggleWeek) => "Month / week view",
+            (_, NextTask) => "Next task on the day",
+            (_, PreviousTask) => "Previous task on the day",
+            (_, Open) => "Open the task",
+            (_, Earlier) => "Reschedule task a day earlier",
+            (_, Later) => "Reschedule task a day later",
+            (_, Restore) => "Restore task",
+            (_, Purge) => "Delete forever",
+            (_, EmptyTrash) => "Empty trash",
+            (_, Close) => "Close",
+            (_, ToggleItem) => "Check off checklist item",
+            (_, AddItem) => "Add checklist item",
+            (_, RemoveItem) => "Remove checklist item",
+            (_, Graph) => "Dependency graph",
+            (_, NewProject) => "Create project",
+        };
+        text.to_string()
+    }
+
+    fn name(self) -> &'static str {
+        use Action::*;
+        match self {
+            Up => "up",
+            Down => "down",
+            Left => "left",
+            Right => "right",
+            First => "first",
+            Last => "last",
+            Details => "details",
+            NextTab => "next_tab",
+            PreviousTab => "previous_tab",
+            Find => "find",
+            NewTask => "new_task",
+            NewSubtask => "new_subtask",
+            Edit => "edit",
+            NextStatus => "next_status",
+            Delete => "delete",
+            Undo => "undo",
+            Redo => "redo",
+            Timer => "timer",
+            TimerNote => "timer_note",
+            SwitchProject => "switch_project",
+            MoveToProject => "move_to_project",
+            SwitchTheme => "switch_theme",
+            ToggleMode => "toggle_mode",
+            ToggleFilter => "toggle_filter",
+            EditFilter => "edit_filter",
+            SaveView => "save_view",
+            Search => "search",
+            FilterStatus(_) => "filter_status",
+            Palette => "palette",
+            Help => "help",
+            Quit => "quit",
+            Collapse => "collapse",
+            Expand => "expand",
+            NextMatch => "next_match",
+            PreviousMatch => "previous_match",
+            ClearSearch => "clear_search",
+            PreviousMonth => "previous_month",
+            NextMonth => "next_month",
+            Today => "today",
+            ToggleWeek => "toggle_week",
+            NextTask => "next_task",
+            PreviousTask => "previous_task",
+            Open => "open",
+            Earlier => "earlier",
+            Later => "later",
+            Restore => "restore",
+            Purge => "purge",
+            EmptyTrash => "empty_trash",
+            Close => "close",
+            ToggleItem => "toggle_item",
+            AddItem => "add_item",
+            RemoveItem => "remove_item",
+            Graph => "graph",
+            NewProject => "new_project",
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::FilterStatus(n) => write!(f, "filter_status_{}", n),
+            _ => write!(f, "{}", self.name()),
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase();
+        KeyMode::iter()
+            .flat_map(|mode| mode.actions().iter().copied())
+            .find(|action| action.to_string() == name)
+            .ok_or_else(|| format!("unknown action \"{}\"", s))
+    }
+}
+
+/// One key press with its modifiers. Shift is folded into the character, so `G`
+/// and `shift-g` are the same chord, and into `shift-tab`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers = modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        if matches!(code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Self { code, modifiers }
+    }
+
+    fn is_plain_char(&self) -> bool {
+        matches!(self.code, KeyCode::Char(c) if c != ' ') && self.modifiers.is_empty()
+    }
+}
+
+impl From<KeyEvent> for KeyChord {
+    fn from(key: KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = String;
+
+    /// Parses `ctrl-r`, `alt-x`, `shift-tab`, `enter`, `G` and the like.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+        // A single character is always a key, even `-` or `+`
+        while rest.chars().count() > 1 {
+            let Some(split) = rest.find(['-', '+']) else { break };
+            let modifier = match rest[..split].to_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => break,
+            };
+            modifiers |= modifier;
+            rest = &rest[split + 1..];
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::Char(c.to_ascii_uppercase()),
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "space" => KeyCode::Char(' '),
+                "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key \"{}\"", s)),
+                },
+            },
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::BackTab => write!(f, "Shift+Tab"),
+            KeyCode::Backspace => write!(f, "Backspace"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// Keys pressed one after another, such as `gg`. Keys are separated by spaces,
+/// or run together when each is a single character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySequence(pub Vec<KeyChord>);
+
+impl FromStr for KeySequence {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chords = Vec::new();
+        for token in s.split_whitespace() {
+            match token.parse::<KeyChord>() {
+                Ok(chord) => chords.push(chord),
+                // `gg`, `dd`: a run of plain characters
+                Err(_) if !token.contains(['-', '+']) => {
+                    chords.extend(token.chars().map(|c| KeyChord::new(KeyCode::Char(c), KeyModifiers::NONE)));
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        // A lone space is written as "space", but " " is unambiguous too
+        if chords.is_empty() && s == " " {
+            chords.push(KeyChord::new(KeyCode::Char(' '), KeyModifiers::NONE));
+        }
+        if chords.is_empty() {
+            return Err("empty key".to_string());
+        }
+        Ok(Self(chords))
+    }
+}
+
+impl fmt::Display for KeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let separator = if self.0.iter().all(KeyChord::is_plain_char) { "" } else { " " };
+        let keys: Vec<String> = self.0.iter().map(ToString::to_string).collect();
+        write!(f, "{}", keys.join(separator))
+    }
+}
+
+/// What the keys pressed so far amount to in a mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyMatch {
+    Action(Action),
+    /// The start of a longer sequence; wait for the next key.
+    Pending,
+    None,
+}
+
+/// The `[mode]` tables of `keymap.toml`: key sequence to action name, with
+/// `"none"` removing a built-in binding.
+pub type KeymapFile = BTreeMap<String, BTreeMap<String, String>>;
+
+/// Key bindings per mode: the built-in ones with the user's `keymap.toml` on top.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: BTreeMap<KeyMode, Vec<(KeySequence, Action)>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = KeyMode::iter()
+            .map(|mode| {
+                let defaults = mode
+                    .defaults()
+                    .iter()
+                    .map(|(keys, action)| (keys.parse().expect("built-in key binding"), *action))
+                    .collect();
+                (mode, defaults)
+            })
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// Applies the user's bindings over the built-in ones. A user binding replaces
+    /// any built-in one it clashes with, either the same keys or one being the start
+    /// of the other; clashes between the user's own bindings are errors.
+    pub fn from_file(file: &KeymapFile) -> Result<Self, String> {
+        let mut keymap = Self::default();
+        for (table, entries) in file {
+            let mode = KeyMode::iter().find(|mode| mode.name() == table.to_lowercase()).ok_or_else(|| {
+                let names: Vec<&str> = KeyMode::iter().map(KeyMode::name).collect();
+                format!("unknown mode [{}] (expected one of {})", table, names.join(", "))
+            })?;
+
+            let mut overrides: Vec<(&str, KeySequence, Option<Action>)> = Vec::new();
+            for (keys, action) in entries {
+                let sequence: KeySequence = keys.parse().map_err(|err| format!("[{}] {}", mode.name(), err))?;
+                let action = match action.trim().to_lowercase().as_str() {
+                    "none" | "" => None,
+                    _ => {
+                        let action: Action = action.parse().map_err(|err| format!("[{}] {}", mode.name(), err))?;
+                        if !mode.actions().contains(&action) {
+                            return Err(format!("action \"{}\" is not available in [{}]", action, mode.name()));
+                        }
+                        Some(action)
+                    }
+                };
+                if let Some((other, other_sequence, _)) = overrides.iter().find(|(_, other, _)| clashes(other, &sequence)) {
+                    return Err(if *other_sequence == sequence {
+                        format!("\"{}\" and \"{}\" in [{}] are the same key", other, keys, mode.name())
+                    } else {
+                        format!("\"{}\" and \"{}\" in [{}] clash: one starts the other", other, keys, mode.name())
+                    });
+                }
+                overrides.push((keys, sequence, action));
+            }
+
+            let bindings = keymap.bindings.entry(mode).or_default();
+            for (_, sequence, action) in overrides {
+                bindings.retain(|(existing, _)| !clashes(existing, &sequence));
+                if let Some(action) = action {
+                    bindings.push((sequence, action));
+                }
+            }
+        }
+        Ok(keymap)
+    }
+
+    /// Looks up the keys pressed so far in one mode.
+    pub fn lookup(&self, mode: KeyMode, keys: &[KeyChord]) -> KeyMatch {
+        let bindings = self.bindings(mode);
+        if let Some((_, action)) = bindings.iter().find(|(sequence, _)| sequence.0 == keys) {
+            return KeyMatch::Action(*action);
+        }
+        if bindings.iter().any(|(sequence, _)| sequence.0.starts_with(keys)) {
+            return KeyMatch::Pending;
+        }
+        KeyMatch::None
+    }
+
+    /// The keys bound to `action` in `mode`, as shown to the user.
+    pub fn keys_for(&self, mode: KeyMode, action: Action) -> Vec<String> {
+        self.bindings(mode)
+            .iter()
+            .filter(|(_, bound)| *bound == action)
+            .map(|(sequence, _)| sequence.to_string())
+            .collect()
+    }
+
+    /// Every bound action of `mode` with its keys, in help order.
+    pub fn describe(&self, mode: KeyMode) -> Vec<(Vec<String>, Action)> {
+        mode.actions()
+            .iter()
+            .map(|&action| (self.keys_for(mode, action), action))
+            .filter(|(keys, _)| !keys.is_empty())
+            .collect()
+    }
+
+    fn bindings(&self, mode: KeyMode) -> &[(KeySequence, Action)] {
+        self.bindings.get(&mode).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Two sequences clash when they are equal or one is the start of the other, as
+/// the shorter one would then always fire first.
+fn clashes(a: &KeySequence, b: &KeySequence) -> bool {
+    a.0.starts_with(&b.0) || b.0.starts_with(&a.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(s: &str) -> KeyChord {
+        s.parse().unwrap()
+    }
+
+    fn keys(s: &str) -> Vec<KeyChord> {
+        s.parse::<KeySequence>().unwrap().0
+    }
+
+    fn file(mode: &str, entries: &[(&str, &str)]) -> KeymapFile {
+        let entries = entries.iter().map(|(keys, action)| (keys.to_string(), action.to_string())).collect();
+        BTreeMap::from([(mode.to_string(), entries)])
+    }
+
+    #[test]
+    fn parses_chords() {
+        assert_eq!(chord("ctrl-r"), KeyChord::new(KeyCode::Char('r'), KeyModifiers::CONTROL));
+        assert_eq!(chord("Ctrl+R"), KeyChord::new(KeyCode::Char('R'), KeyModifiers::CONTROL));
+        assert_eq!(chord("shift-tab"), KeyChord::new(KeyCode::BackTab, KeyModifiers::NONE));
+        assert_eq!(chord("shift-tab"), chord("backtab"));
+        assert_eq!(chord("shift-g"), chord("G"));
+        assert_eq!(chord("-"), KeyChord::new(KeyCode::Char('-'), KeyModifiers::NONE));
+        assert_eq!(chord("alt--"), KeyChord::new(KeyCode::Char('-'), KeyModifiers::ALT));
+        assert_eq!(chord("space"), KeyChord::new(KeyCode::Char(' '), KeyModifiers::NONE));
+        assert_eq!(chord("f5"), KeyChord::new(KeyCode::F(5), KeyModifiers::NONE));
+        assert!("f13".parse::<KeyChord>().is_err());
+        assert!("hyper-x".parse::<KeyChord>().is_err());
+    }
+
+    #[test]
+    fn parses_sequences() {
+        let g = chord("g");
+        assert_eq!(keys("gg"), vec![g, g]);
+        assert_eq!(keys("g g"), vec![g, g]);
+        assert_eq!(keys("ctrl-x ctrl-s"), vec![chord("ctrl-x"), chord("ctrl-s")]);
+        assert_eq!(keys(" "), vec![chord("space")]);
+        assert!("".parse::<KeySequence>().is_err());
+        assert!("ctrl-xx".parse::<KeySequence>().is_err());
+    }
+
+    #[test]
+    fn displays_keys_as_help_shows_them() {
+        assert_eq!("gg".parse::<KeySequence>().unwrap().to_string(), "gg");
+        assert_eq!("ctrl-x s".parse::<KeySequence>().unwrap().to_string(), "Ctrl+x s");
+        assert_eq!(chord("shift-tab").to_string(), "Shift+Tab");
+    }
+
+    #[test]
+    fn defaults_wait_for_sequences() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.lookup(KeyMode::Normal, &keys("g")), KeyMatch::Pending);
+        assert_eq!(keymap.lookup(KeyMode::Normal, &keys("gg")), KeyMatch::Action(Action::First));
+        assert_eq!(keymap.lookup(KeyMode::Normal, &keys("ctrl-r")), KeyMatch::Action(Action::Redo));
+        assert_eq!(keymap.lookup(KeyMode::Normal, &keys("z")), KeyMatch::None);
+    }
+
+    #[test]
+    fn user_binding_replaces_clashing_defaults() {
+        // `g` would always fire before `gg` could, so the built-in `gg` goes
+        let keymap = Keymap::from_file(&file("normal", &[("g", "last")])).unwrap();
+        assert_eq!(keymap.lookup(KeyMode::Normal, &keys("g")), KeyMatch::Action(Action::Last));
+        assert_eq!(keymap.keys_for(KeyMode::Normal, Action::First), Vec::<String>::new());
+        assert_eq!(keymap.keys_for(KeyMode::Normal, Action::Last), vec!["G", "g"]);
+    }
+
+    #[test]
+    fn none_removes_a_binding() {
+        let keymap = Keymap::from_file(&file("normal", &[("q", "none")])).unwrap();
+        assert_eq!(keymap.lookup(KeyMode::Normal, &keys("q")), KeyMatch::None);
+        assert!(keymap.keys_for(KeyMode::Normal, Action::Quit).is_empty());
+        assert!(!keymap.describe(KeyMode::Normal).iter().any(|(_, action)| *action == Action::Quit));
+    }
+
+    #[test]
+    fn user_bindings_must_not_clash() {
+        let err = Keymap::from_file(&file("normal", &[("g", "first"), ("gx", "last")])).unwrap_err();
+        assert!(err.contains("one starts the other"), "{}", err);
+
+        let err = Keymap::from_file(&file("normal", &[("G", "first"), ("shift-g", "last")])).unwrap_err();
+        assert!(err.contains("are the same key"), "{}", err);
+    }
+
+    #[test]
+    fn actions_must_be_offered_by_the_mode() {
+        let err = Keymap::from_file(&file("normal", &[("r", "restore")])).unwrap_err();
+        assert_eq!(err, "action \"restore\" is not available in [normal]");
+        assert!(Keymap::from_file(&file("trash", &[("r", "restore")])).is_ok());
+        assert!(Keymap::from_file(&file("normal", &[("r", "teleport")])).is_err());
+        assert!(Keymap::from_file(&file("nowhere", &[("r", "quit")])).is_err());
+    }
+}
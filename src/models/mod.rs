@@ -1,13 +1,45 @@
+pub mod dependencies;
+pub mod filter;
+pub mod keymap;
+pub mod recurrence;
+pub mod search;
+pub mod subtasks;
+pub mod theme;
+pub mod time_tracking;
+pub mod workflow;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::str::FromStr;
 use strum_macros::{Display, EnumIter};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display, EnumIter)]
-pub enum TaskStatus {
-    Todo,
-    InProgress,
-    Done,
+use recurrence::Recurrence;
+use time_tracking::TimeEntry;
+use workflow::Workflow;
+
+/// Name of a workflow status. The board's `Workflow` decides which names exist,
+/// their order and which ones count as done.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TaskStatus(String);
+
+impl TaskStatus {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for TaskStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&self.0)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display, EnumIter, PartialOrd, Ord)]
@@ -18,54 +50,256 @@ pub enum TaskPriority {
     Critical,
 }
 
+impl FromStr for TaskPriority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "low" => Ok(TaskPriority::Low),
+            "medium" | "med" => Ok(TaskPriority::Medium),
+            "high" => Ok(TaskPriority::High),
+            "critical" | "crit" => Ok(TaskPriority::Critical),
+            _ => Err(format!("unknown priority \"{}\" (expected low, medium, high or critical)", s)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Task {
     pub id: String,
     pub title: String,
+    #[serde(default)]
     pub description: String,
     pub status: TaskStatus,
     pub priority: TaskPriority,
     pub created_at: DateTime<Utc>,
+    #[serde(default)]
     pub due_date: Option<DateTime<Utc>>,
+    #[serde(default)]
     pub tags: Vec<String>,
+    /// Append-only log of what happened to the task, oldest first.
+    #[serde(default)]
+    pub history: Vec<TaskEvent>,
+    #[serde(default)]
+    pub checklist: Vec<ChecklistItem>,
+    /// The task this one is a subtask of.
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    /// Ids of tasks that must be done before this one can start.
+    #[serde(default)]
+    pub blocked_by: Vec<String>,
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    /// Id of the first task of a recurring series, shared by every occurrence after it.
+    #[serde(default)]
+    pub series_id: Option<String>,
+    /// Tracked work, oldest first; at most the last entry is still running.
+    #[serde(default)]
+    pub time_entries: Vec<TimeEntry>,
+    /// Relative size for sprint planning.
+    #[serde(default)]
+    pub points: Option<u32>,
+    /// Expected effort in minutes.
+    #[serde(default)]
+    pub estimate_minutes: Option<u32>,
+    /// Fields written by a newer TaskX, kept as they were so saving does not drop them.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChecklistItem {
+    pub text: String,
+    #[serde(default)]
+    pub done: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TaskEvent {
+    pub at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum EventKind {
+    Created { status: TaskStatus },
+    StatusChanged { from: TaskStatus, to: TaskStatus },
+    PriorityChanged { from: TaskPriority, to: TaskPriority },
+    /// Plain field edits, named by field ("title", "description", "due date").
+    Edited { fields: Vec<String> },
+    Tagged { added: Vec<String>, removed: Vec<String> },
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventKind::Created { status } => write!(f, "Created in {}", status),
+            EventKind::StatusChanged { from, to } => write!(f, "Moved {} → {}", from, to),
+            EventKind::PriorityChanged { from, to } => write!(f, "Priority {} → {}", from, to),
+            EventKind::Edited { fields } => write!(f, "Edited {}", fields.join(", ")),
+            EventKind::Tagged { added, removed } => {
+                let changes: Vec<String> = added
+                    .iter()
+                    .map(|tag| format!("+{}", tag))
+                    .chain(removed.iter().map(|tag| format!("-{}", tag)))
+                    .collect();
+                write!(f, "Tags {}", changes.join(" "))
+            }
+        }
+    }
+}
+
+/// A deleted task kept in the trash bin until it is restored or purged.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrashedTask {
+    pub task: Task,
+    pub deleted_at: DateTime<Utc>,
 }
 
 impl Task {
     pub fn new(title: &str, description: &str, status: TaskStatus, priority: TaskPriority) -> Self {
+        let created_at = Utc::now();
         Self {
             id: Uuid::new_v4().to_string(),
             title: title.to_string(),
             description: description.to_string(),
-            status,
+            status: status.clone(),
             priority,
-            created_at: Utc::now(),
+            created_at,
             due_date: None,
             tags: Vec::new(),
+            history: vec![TaskEvent { at: created_at, kind: EventKind::Created { status } }],
+            checklist: Vec::new(),
+            parent_id: None,
+            blocked_by: Vec::new(),
+            recurrence: None,
+            series_id: None,
+            time_entries: Vec::new(),
+            points: None,
+            estimate_minutes: None,
+            extra: Map::new(),
         }
     }
 
-    pub fn is_overdue(&self) -> bool {
-        if let Some(due) = self.due_date {
-            due < Utc::now() && self.status != TaskStatus::Done
-        } else {
-            false
+    /// Changes the status and records the move; setting the same status is a no-op.
+    pub fn set_status(&mut self, status: TaskStatus) {
+        if self.status != status {
+            let from = std::mem::replace(&mut self.status, status.clone());
+            self.record(EventKind::StatusChanged { from, to: status });
+        }
+    }
+
+    /// Appends events describing how this task differs from `before`.
+    /// Callers edit fields directly and then call this once per user action.
+    pub fn record_changes(&mut self, before: &Task) {
+        if before.status != self.status {
+            self.record(EventKind::StatusChanged { from: before.status.clone(), to: self.status.clone() });
+        }
+        if before.priority != self.priority {
+            self.record(EventKind::PriorityChanged { from: before.priority, to: self.priority });
+        }
+
+        let mut fields = Vec::new();
+        if before.title != self.title {
+            fields.push("title".to_string());
+        }
+        if before.description != self.description {
+            fields.push("description".to_string());
+        }
+        if before.due_date != self.due_date {
+            fields.push("due date".to_string());
+        }
+        if before.checklist != self.checklist {
+            fields.push("checklist".to_string());
+        }
+        if before.parent_id != self.parent_id {
+            fields.push("parent".to_string());
+        }
+        if before.blocked_by != self.blocked_by {
+            fields.push("dependencies".to_string());
+        }
+        if before.recurrence != self.recurrence {
+            fields.push("repeat".to_string());
+        }
+        if before.points != self.points {
+            fields.push("points".to_string());
+        }
+        if before.estimate_minutes != self.estimate_minutes {
+            fields.push("estimate".to_string());
+        }
+        if !fields.is_empty() {
+            self.record(EventKind::Edited { fields });
         }
+
+        let added: Vec<String> = self.tags.iter().filter(|t| !before.tags.contains(t)).cloned().collect();
+        let removed: Vec<String> = before.tags.iter().filter(|t| !self.tags.contains(t)).cloned().collect();
+        if !added.is_empty() || !removed.is_empty() {
+            self.record(EventKind::Tagged { added, removed });
+        }
+    }
+
+    fn record(&mut self, kind: EventKind) {
+        self.history.push(TaskEvent { at: Utc::now(), kind });
+    }
+
+    /// Every status the task has entered with the time it entered it, oldest first.
+    pub fn status_changes(&self) -> impl Iterator<Item = (TaskStatus, DateTime<Utc>)> + '_ {
+        self.history.iter().filter_map(|event| match &event.kind {
+            EventKind::Created { status } => Some((status.clone(), event.at)),
+            EventKind::StatusChanged { to, .. } => Some((to.clone(), event.at)),
+            _ => None,
+        })
+    }
+
+    /// The status the task had at `time`, or `None` if it did not exist yet.
+    /// Tasks saved before status changes were recorded keep their current status throughout.
+    pub fn status_at(&self, time: DateTime<Utc>) -> Option<TaskStatus> {
+        if self.created_at > time {
+            return None;
+        }
+        let mut changes = self.status_changes().peekable();
+        let Some((first, _)) = changes.peek().cloned() else {
+            return Some(self.status.clone());
+        };
+        let status = changes
+            .take_while(|&(_, at)| at <= time)
+            .last()
+            .map_or(first, |(status, _)| status);
+        Some(status)
+    }
+
+    /// When work first started: the first move out of the workflow's first status
+    /// into one that is not done.
+    pub fn started_at(&self, workflow: &Workflow) -> Option<DateTime<Utc>> {
+        self.status_changes()
+            .find(|(status, _)| workflow.column_of(status) > 0 && !workflow.is_done(status))
+            .map(|(_, at)| at)
     }
 
-    pub fn priority_color(&self) -> &str {
-        match self.priority {
-            TaskPriority::Low => "#72B7F0", // Light blue
-            TaskPriority::Medium => "#70C666", // Green
-            TaskPriority::High => "#FF9E3B", // Orange
-            TaskPriority::Critical => "#FF6369", // Red
+    /// When the task last entered a done status, if it is still done.
+    pub fn completed_at(&self, workflow: &Workflow) -> Option<DateTime<Utc>> {
+        if !workflow.is_done(&self.status) {
+            return None;
         }
+        self.status_changes()
+            .filter(|(status, _)| workflow.is_done(status))
+            .last()
+            .map(|(_, at)| at)
     }
 
-    pub fn status_color(&self) -> &str {
-        match self.status {
-            TaskStatus::Todo => "#72B7F0",     // Light blue
-            TaskStatus::InProgress => "#70C666", // Green
-            TaskStatus::Done => "#6E7781",       // Gray
+    pub fn is_overdue(&self, workflow: &Workflow) -> bool {
+        if let Some(due) = self.due_date {
+            due < Utc::now() && !workflow.is_done(&self.status)
+        } else {
+            false
         }
     }
+
+    /// Checked and total checklist items.
+    pub fn checklist_progress(&self) -> (usize, usize) {
+        let done = self.checklist.iter().filter(|item| item.done).count();
+        (done, self.checklist.len())
+    }
 }
\ No newline at end of file
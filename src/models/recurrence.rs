@@ -0,0 +1,218 @@
+use chrono::{DateTime, Datelike, Duration, Local, Months, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::models::workflow::Workflow;
+use crate::models::{ChecklistItem, Task};
+
+/// When a recurring task comes back after it is done. Stored and typed as text:
+///
+/// - `daily`, `every N days`: N days after the previous due date
+/// - `weekly mon,thu`: the next of the given weekdays after the previous due date
+/// - `monthly 15`: day 15 of the next month (the last day in shorter months)
+/// - `N days after done`: N days after the task was actually finished
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Recurrence {
+    Days(u32),
+    Weekly(Vec<Weekday>),
+    Monthly(u32),
+    AfterDone(u32),
+}
+
+impl Recurrence {
+    /// Due date of the next occurrence. Schedules count from the previous due date,
+    /// skipping ahead past `completed` when the task was finished late, and keep
+    /// the previous due date's time of day. None when the date would fall past the
+    /// end of the calendar.
+    pub fn next_due(&self, due: Option<DateTime<Utc>>, completed: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let done_day = completed.with_timezone(&Local).date_naive();
+        let base = due.map_or(done_day, |due| due.with_timezone(&Local).date_naive());
+        let after = base.max(done_day);
+
+        let date = match self {
+            Recurrence::Days(interval) => {
+                let step = Duration::days(i64::from((*interval).max(1)));
+                let mut next = base.checked_add_signed(step)?;
+                while next <= after {
+                    next = next.checked_add_signed(step)?;
+                }
+                next
+            }
+            Recurrence::Weekly(days) => (1..=7)
+                .map_while(|offset| after.checked_add_signed(Duration::days(offset)))
+                .find(|date| days.contains(&date.weekday()))
+                .or_else(|| after.checked_add_signed(Duration::weeks(1)))?,
+            Recurrence::Monthly(day) => {
+                let this_month = day_of_month(after, *day);
+                if this_month > after {
+                    this_month
+                } else {
+                    day_of_month(after.checked_add_months(Months::new(1))?, *day)
+                }
+            }
+            Recurrence::AfterDone(days) => done_day.checked_add_signed(Duration::days(i64::from(*days)))?,
+        };
+
+        let time = due.map_or_else(
+            || NaiveTime::from_hms_opt(23, 59, 59).expect("valid time"),
+            |due| due.with_timezone(&Local).time(),
+        );
+        let local = date.and_time(time);
+        Some(
+            Local
+                .from_local_datetime(&local)
+                .earliest()
+                .map_or_else(|| local.and_utc(), |local| local.with_timezone(&Utc)),
+        )
+    }
+}
+
+/// Longest interval, in days, a repeat rule accepts: ten years.
+const MAX_DAYS: u32 = 3650;
+
+/// Day `day` of the month containing `date`, clamped to the month's last day.
+fn day_of_month(date: NaiveDate, day: u32) -> NaiveDate {
+    (1..=day.clamp(1, 31))
+        .rev()
+        .find_map(|d| date.with_day(d))
+        .unwrap_or(date)
+}
+
+impl FromStr for Recurrence {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.trim().to_lowercase();
+        let words: Vec<&str> = input.split_whitespace().collect();
+        let number = |word: &str| -> Result<u32, String> {
+            word.parse::<u32>()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or_else(|| format!("\"{}\" is not a positive number", word))
+        };
+        let days = |word: &str| -> Result<u32, String> {
+            let n = number(word)?;
+            if n > MAX_DAYS {
+                return Err(format!("{} days is too long (at most {})", n, MAX_DAYS));
+            }
+            Ok(n)
+        };
+
+        match words.as_slice() {
+            ["daily"] => Ok(Recurrence::Days(1)),
+            ["every", n, "days" | "day"] => Ok(Recurrence::Days(days(n)?)),
+            ["weekly", days @ ..] if !days.is_empty() => {
+                let mut weekdays = Vec::new();
+                for name in days.join(",").split(',').filter(|d| !d.is_empty()) {
+                    let day = Weekday::from_str(name).map_err(|_| format!("unknown weekday \"{}\"", name))?;
+                    if !weekdays.contains(&day) {
+                        weekdays.push(day);
+                    }
+                }
+                weekdays.sort_by_key(|day| day.num_days_from_monday());
+                Ok(Recurrence::Weekly(weekdays))
+            }
+            ["monthly", day] => {
+                let day = number(day)?;
+                if day > 31 {
+                    return Err(format!("day {} does not exist in any month", day));
+                }
+                Ok(Recurrence::Monthly(day))
+            }
+            [n, "days" | "day", "after", "done"] => Ok(Recurrence::AfterDone(days(n)?)),
+            _ => Err(format!(
+                "unknown repeat rule \"{}\" (expected daily, every N days, weekly mon,thu, monthly N or N days after done)",
+                s.trim()
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Recurrence::Days(1) => write!(f, "daily"),
+            Recurrence::Days(n) => write!(f, "every {} days", n),
+            Recurrence::Weekly(days) => {
+                let names: Vec<String> = days.iter().map(|day| day.to_string().to_lowercase()).collect();
+                write!(f, "weekly {}", names.join(","))
+            }
+            Recurrence::Monthly(day) => write!(f, "monthly {}", day),
+            Recurrence::AfterDone(1) => write!(f, "1 day after done"),
+            Recurrence::AfterDone(n) => write!(f, "{} days after done", n),
+        }
+    }
+}
+
+impl TryFrom<String> for Recurrence {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Recurrence> for String {
+    fn from(value: Recurrence) -> Self {
+        value.to_string()
+    }
+}
+
+/// When `after` has just moved into a done status and repeats, hands its rule on
+/// to a fresh copy due at the next occurrence and returns that copy. The finished
+/// task keeps its history and stays done as a record of the occurrence.
+pub fn next_occurrence(before: &Task, after: &mut Task, workflow: &Workflow, now: DateTime<Utc>) -> Option<Task> {
+    if workflow.is_done(&before.status) || !workflow.is_done(&after.status) {
+        return None;
+    }
+    let due = after.recurrence.as_ref()?.next_due(after.due_date, now)?;
+    let recurrence = after.recurrence.take()?;
+
+    let mut next = Task::new(&after.title, &after.description, workflow.initial(), after.priority);
+    next.due_date = Some(due);
+    next.tags = after.tags.clone();
+    next.parent_id = after.parent_id.clone();
+    next.points = after.points;
+    next.estimate_minutes = after.estimate_minutes;
+    next.checklist = after
+        .checklist
+        .iter()
+        .map(|item| ChecklistItem { text: item.text.clone(), done: false })
+        .collect();
+    next.series_id = Some(after.series_id.clone().unwrap_or_else(|| after.id.clone()));
+    next.recurrence = Some(recurrence);
+    after.series_id = next.series_id.clone();
+    Some(next)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_intervals_up_to_ten_years() {
+        assert_eq!("every 3650 days".parse(), Ok(Recurrence::Days(3650)));
+        assert_eq!("3650 days after done".parse(), Ok(Recurrence::AfterDone(3650)));
+        assert!("every 3651 days".parse::<Recurrence>().is_err());
+        assert!("4000000000 days after done".parse::<Recurrence>().is_err());
+        assert!("every 0 days".parse::<Recurrence>().is_err());
+    }
+
+    #[test]
+    fn next_due_past_the_calendar_is_none() {
+        let end = NaiveDate::MAX.and_hms_opt(0, 0, 0).unwrap().and_utc() - Duration::days(2);
+        assert_eq!(Recurrence::AfterDone(3650).next_due(None, end), None);
+        assert_eq!(Recurrence::Days(3650).next_due(Some(end), end), None);
+        assert_eq!(Recurrence::Monthly(15).next_due(Some(end), end), None);
+    }
+
+    #[test]
+    fn next_due_steps_past_a_late_completion() {
+        let due = Local.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap().with_timezone(&Utc);
+        let completed = due + Duration::days(5);
+        let next = Recurrence::Days(2).next_due(Some(due), completed).unwrap();
+        assert_eq!(next, due + Duration::days(6));
+    }
+}
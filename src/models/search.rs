@@ -0,0 +1,71 @@
+use crate::models::Task;
+
+/// Whether `query` appears in the title, description or one of the tags of
+/// `task`, ignoring case. An empty query matches everything.
+pub fn matches(task: &Task, query: &str) -> bool {
+    query.is_empty()
+        || !match_ranges(&task.title, query).is_empty()
+        || !match_ranges(&task.description, query).is_empty()
+        || task.tags.iter().any(|tag| !match_ranges(tag, query).is_empty())
+}
+
+/// Byte ranges of every non-overlapping occurrence of `query` in `text`, ignoring
+/// case. The ranges always fall on character boundaries of `text`.
+pub fn match_ranges(text: &str, query: &str) -> Vec<(usize, usize)> {
+    let needle: Vec<char> = query.chars().map(fold).collect();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    let haystack: Vec<(usize, char)> = text.char_indices().map(|(i, c)| (i, fold(c))).collect();
+
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    while start + needle.len() <= haystack.len() {
+        let window = &haystack[start..start + needle.len()];
+        if window.iter().map(|&(_, c)| c).eq(needle.iter().copied()) {
+            let end = haystack.get(start + needle.len()).map_or(text.len(), |&(i, _)| i);
+            ranges.push((haystack[start].0, end));
+            start += needle.len();
+        } else {
+            start += 1;
+        }
+    }
+    ranges
+}
+
+// Compare characters one to one so match positions map back onto the original text
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// How well `query` matches `text` as a subsequence, ignoring case; higher is
+/// better and `None` means not every query character appears in order. Matches
+/// at the start of words and runs of consecutive characters score more, and
+/// characters skipped in between cost a little.
+pub fn fuzzy_score(text: &str, query: &str) -> Option<i64> {
+    let mut needle = query.chars().filter(|c| !c.is_whitespace()).map(fold).peekable();
+    needle.peek()?;
+
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    let mut consecutive = false;
+    let mut gap = 0;
+    for c in text.chars() {
+        let Some(&wanted) = needle.peek() else {
+            break;
+        };
+        if fold(c) == wanted {
+            let word_start = previous.is_none_or(|p| !p.is_alphanumeric());
+            score += 1 + if word_start { 16 } else { 0 } + if consecutive { 8 } else { 0 };
+            score -= gap.min(8);
+            needle.next();
+            consecutive = true;
+            gap = 0;
+        } else {
+            consecutive = false;
+            gap += 1;
+        }
+        previous = Some(c);
+    }
+    needle.peek().is_none().then_some(score)
+}
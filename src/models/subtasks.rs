@@ -0,0 +1,67 @@
+use crate::models::workflow::Workflow;
+use crate::models::{Task, TaskStatus};
+
+/// Direct subtasks of `parent_id`, in board order.
+pub fn children<'a>(tasks: &'a [Task], parent_id: &'a str) -> impl Iterator<Item = &'a Task> + 'a {
+    tasks.iter().filter(move |task| task.parent_id.as_deref() == Some(parent_id))
+}
+
+/// Finished and total work items of a task: its checklist plus its direct subtasks.
+/// `None` when the task has neither.
+pub fn progress(tasks: &[Task], task: &Task, workflow: &Workflow) -> Option<(usize, usize)> {
+    let (mut done, mut total) = task.checklist_progress();
+    for child in children(tasks, &task.id) {
+        total += 1;
+        if workflow.is_done(&child.status) {
+            done += 1;
+        }
+    }
+    (total > 0).then_some((done, total))
+}
+
+/// Refuses to move `task` into a done status while any of its subtasks are still open.
+pub fn check_can_finish(tasks: &[Task], task: &Task, to: &TaskStatus, workflow: &Workflow) -> Result<(), String> {
+    if !workflow.is_done(to) {
+        return Ok(());
+    }
+    let open = children(tasks, &task.id)
+        .filter(|child| !workflow.is_done(&child.status))
+        .count();
+    match open {
+        0 => Ok(()),
+        1 => Err(format!("\"{}\" has an open subtask", task.title)),
+        n => Err(format!("\"{}\" has {} open subtasks", task.title, n)),
+    }
+}
+
+/// Looks up the parent typed for `task_id` by id prefix; an empty string clears it.
+/// Rejects a parent that is the task itself or one of its own subtasks.
+pub fn resolve_parent(tasks: &[Task], task_id: &str, input: &str) -> Result<Option<String>, String> {
+    let input = input.trim().trim_start_matches('#');
+    if input.is_empty() {
+        return Ok(None);
+    }
+
+    let matches: Vec<&Task> = tasks.iter().filter(|task| task.id.starts_with(input)).collect();
+    let parent = match matches.as_slice() {
+        [] => return Err(format!("No task id starts with \"{}\"", input)),
+        [parent] => *parent,
+        _ => return Err(format!("Task id prefix \"{}\" is ambiguous", input)),
+    };
+
+    // Walking up from the new parent must never reach the task itself
+    if ancestors(tasks, parent).any(|ancestor| ancestor.id == task_id) {
+        return Err("A task cannot be nested under itself or its own subtasks".to_string());
+    }
+    Ok(Some(parent.id.clone()))
+}
+
+/// `task` followed by its parent, grandparent and so on. Stops after visiting
+/// every task once, so a cycle in hand-edited data cannot hang the caller.
+pub fn ancestors<'a>(tasks: &'a [Task], task: &'a Task) -> impl Iterator<Item = &'a Task> + 'a {
+    std::iter::successors(Some(task), move |current| {
+        let parent_id = current.parent_id.as_deref()?;
+        tasks.iter().find(|task| task.id == parent_id)
+    })
+    .take(tasks.len() + 1)
+}
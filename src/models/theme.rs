@@ -0,0 +1,304 @@
+use ratatui::style::{Color, Style};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use crate::models::workflow::{self, Workflow};
+use crate::models::TaskPriority;
+use crate::utils;
+
+/// Themes that ship with TaskX, in the order the theme switcher lists them.
+pub const BUILT_IN: &[&str] = &["dark", "light", "high-contrast", "ansi"];
+
+/// Every color the UI uses, named by what it is for rather than where it appears.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    /// Behind everything; `default` leaves the terminal's own background.
+    pub background: Color,
+    pub text: Color,
+    /// Secondary text: hint labels, dates, field names in lists.
+    pub muted: Color,
+    /// Placeholders, ids and tree lines.
+    pub faint: Color,
+    /// Borders of panels inside a view.
+    pub border: Color,
+    /// Popup borders, the selected tab and other things to find at a glance.
+    pub accent: Color,
+    /// Field labels and popup titles.
+    pub label: Color,
+    /// Key names in hints.
+    pub key: Color,
+    /// Hint text in the input bar.
+    pub info: Color,
+    /// Background of the selected row.
+    pub selection: Color,
+    /// Background of table headers.
+    pub header: Color,
+    /// The selected card on the board.
+    pub highlight: Color,
+    /// Background of tag-like badges.
+    pub badge: Color,
+    /// Text on colored backgrounds: priority badges, search matches and bars.
+    pub badge_text: Color,
+    /// Running timers and finished work.
+    pub success: Color,
+    /// Blocked and overdue tasks and errors.
+    pub danger: Color,
+    /// Recurrence marks and keys being typed.
+    pub special: Color,
+    /// Background of search matches.
+    pub search_match: Color,
+    /// Statuses named neither by the theme nor by `workflow.toml`.
+    pub status_fallback: Color,
+    /// Low, medium, high and critical.
+    pub priority: [Color; 4],
+    /// Colors by status name, compared the way the workflow compares names.
+    pub status: BTreeMap<String, Color>,
+}
+
+/// A theme file: the colors it changes on top of the theme it extends.
+#[derive(Debug, Deserialize)]
+pub struct ThemeFile {
+    /// Built-in or another theme file; `dark` when omitted.
+    pub extends: Option<String>,
+    #[serde(default)]
+    pub priority: BTreeMap<String, String>,
+    #[serde(default)]
+    pub status: BTreeMap<String, String>,
+    #[serde(flatten)]
+    pub colors: BTreeMap<String, String>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// A theme that ships with TaskX, by name.
+    pub fn built_in(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            "ansi" => Some(Self::ansi()),
+            _ => None,
+        }
+    }
+
+    fn dark() -> Self {
+        Self {
+            name: "dark".to_string(),
+            background: Color::Reset,
+            text: Color::White,
+            muted: Color::Gray,
+            faint: Color::DarkGray,
+            border: Color::DarkGray,
+            accent: Color::Cyan,
+            label: Color::Yellow,
+            key: Color::Yellow,
+            info: Color::Blue,
+            selection: Color::DarkGray,
+            header: Color::DarkGray,
+            highlight: Color::Yellow,
+            badge: Color::Rgb(60, 60, 60),
+            badge_text: Color::Black,
+            success: Color::Green,
+            danger: Color::Red,
+            special: Color::Magenta,
+            search_match: Color::Yellow,
+            status_fallback: Color::Rgb(0x6E, 0x77, 0x81),
+            priority: [
+                Color::Rgb(0x72, 0xB7, 0xF0),
+                Color::Rgb(0x70, 0xC6, 0x66),
+                Color::Rgb(0xFF, 0x9E, 0x3B),
+                Color::Rgb(0xFF, 0x63, 0x69),
+            ],
+            status: statuses(&[
+                ("Backlog", Color::Rgb(0x8B, 0x94, 0x9E)),
+                ("Todo", Color::Rgb(0x72, 0xB7, 0xF0)),
+                ("Ready", Color::Rgb(0x72, 0xB7, 0xF0)),
+                ("In Progress", Color::Rgb(0x70, 0xC6, 0x66)),
+                ("In Review", Color::Rgb(0xD2, 0xA8, 0xFF)),
+                ("Blocked", Color::Rgb(0xF8, 0x51, 0x49)),
+                ("Done", Color::Rgb(0x6E, 0x77, 0x81)),
+            ]),
+        }
+    }
+
+    /// Dark text on a light background, for light terminals.
+    fn light() -> Self {
+        Self {
+            name: "light".to_string(),
+            background: Color::Rgb(0xFF, 0xFF, 0xFF),
+            text: Color::Rgb(0x1F, 0x23, 0x28),
+            muted: Color::Rgb(0x57, 0x60, 0x6A),
+            faint: Color::Rgb(0x8C, 0x95, 0x9F),
+            border: Color::Rgb(0xD0, 0xD7, 0xDE),
+            accent: Color::Rgb(0x09, 0x69, 0xDA),
+            label: Color::Rgb(0x9A, 0x67, 0x00),
+            key: Color::Rgb(0x82, 0x50, 0xDF),
+            info: Color::Rgb(0x09, 0x69, 0xDA),
+            selection: Color::Rgb(0xDD, 0xF4, 0xFF),
+            header: Color::Rgb(0xEA, 0xEE, 0xF2),
+            highlight: Color::Rgb(0x05, 0x50, 0xAE),
+            badge: Color::Rgb(0xEA, 0xEE, 0xF2),
+            badge_text: Color::Rgb(0xFF, 0xFF, 0xFF),
+            success: Color::Rgb(0x1A, 0x7F, 0x37),
+            danger: Color::Rgb(0xCF, 0x22, 0x2E),
+            special: Color::Rgb(0x82, 0x50, 0xDF),
+            search_match: Color::Rgb(0xBF, 0x87, 0x00),
+            status_fallback: Color::Rgb(0x6E, 0x77, 0x81),
+            priority: [
+                Color::Rgb(0x09, 0x69, 0xDA),
+                Color::Rgb(0x1A, 0x7F, 0x37),
+                Color::Rgb(0xBC, 0x4C, 0x00),
+                Color::Rgb(0xCF, 0x22, 0x2E),
+            ],
+            status: statuses(&[
+                ("Backlog", Color::Rgb(0x57, 0x60, 0x6A)),
+                ("Todo", Color::Rgb(0x09, 0x69, 0xDA)),
+                ("Ready", Color::Rgb(0x09, 0x69, 0xDA)),
+                ("In Progress", Color::Rgb(0x1A, 0x7F, 0x37)),
+                ("In Review", Color::Rgb(0x82, 0x50, 0xDF)),
+                ("Blocked", Color::Rgb(0xCF, 0x22, 0x2E)),
+                ("Done", Color::Rgb(0x6E, 0x77, 0x81)),
+            ]),
+        }
+    }
+
+    /// Bright colors on black, with no grays to squint at.
+    fn high_contrast() -> Self {
+        Self {
+            name: "high-contrast".to_string(),
+            background: Color::Black,
+            text: Color::White,
+            muted: Color::White,
+            faint: Color::Gray,
+            border: Color::White,
+            accent: Color::LightCyan,
+            label: Color::LightYellow,
+            key: Color::LightYellow,
+            info: Color::LightCyan,
+            selection: Color::Blue,
+            header: Color::Blue,
+            highlight: Color::LightYellow,
+            badge: Color::Blue,
+            badge_text: Color::Black,
+            success: Color::LightGreen,
+            danger: Color::LightRed,
+            special: Color::LightMagenta,
+            search_match: Color::LightYellow,
+            status_fallback: Color::White,
+            priority: [Color::LightCyan, Color::LightGreen, Color::LightYellow, Color::LightRed],
+            status: statuses(&[
+                ("Backlog", Color::White),
+                ("Todo", Color::LightCyan),
+                ("Ready", Color::LightCyan),
+                ("In Progress", Color::LightGreen),
+                ("In Review", Color::LightMagenta),
+                ("Blocked", Color::LightRed),
+                ("Done", Color::Gray),
+            ]),
+        }
+    }
+
+    /// The terminal's own sixteen colors, for terminals without true color.
+    fn ansi() -> Self {
+        Self {
+            name: "ansi".to_string(),
+            badge: Color::DarkGray,
+            status_fallback: Color::Gray,
+            priority: [Color::Blue, Color::Green, Color::Yellow, Color::Red],
+            status: statuses(&[
+                ("Backlog", Color::Gray),
+                ("Todo", Color::Blue),
+                ("Ready", Color::Blue),
+                ("In Progress", Color::Green),
+                ("In Review", Color::Magenta),
+                ("Blocked", Color::Red),
+                ("Done", Color::DarkGray),
+            ]),
+            ..Self::dark()
+        }
+    }
+
+    /// `base` with the colors a theme file sets; returns a message for the first
+    /// unknown name or color.
+    pub fn from_file(name: &str, base: Theme, file: &ThemeFile) -> Result<Self, String> {
+        let mut theme = Theme { name: name.to_string(), ..base };
+        for (slot, value) in &file.colors {
+            let color = parse(slot, value)?;
+            *theme.slot_mut(slot).ok_or_else(|| {
+                format!("unknown color \"{}\" (expected one of: {}, or a [priority] or [status] table)", slot, SLOTS.join(", "))
+            })? = color;
+        }
+        for (priority, value) in &file.priority {
+            let priority: TaskPriority = priority.parse()?;
+            theme.priority[priority as usize] = parse(&format!("priority.{}", priority), value)?;
+        }
+        for (status, value) in &file.status {
+            theme.status.insert(workflow::normalize(status), parse(&format!("status.{}", status), value)?);
+        }
+        Ok(theme)
+    }
+
+    fn slot_mut(&mut self, slot: &str) -> Option<&mut Color> {
+        Some(match slot {
+            "background" => &mut self.background,
+            "text" => &mut self.text,
+            "muted" => &mut self.muted,
+            "faint" => &mut self.faint,
+            "border" => &mut self.border,
+            "accent" => &mut self.accent,
+            "label" => &mut self.label,
+            "key" => &mut self.key,
+            "info" => &mut self.info,
+            "selection" => &mut self.selection,
+            "header" => &mut self.header,
+            "highlight" => &mut self.highlight,
+            "badge" => &mut self.badge,
+            "badge_text" => &mut self.badge_text,
+            "success" => &mut self.success,
+            "danger" => &mut self.danger,
+            "special" => &mut self.special,
+            "search_match" => &mut self.search_match,
+            "status_fallback" => &mut self.status_fallback,
+            _ => return None,
+        })
+    }
+
+    /// The plain text style every screen starts from.
+    pub fn base(&self) -> Style {
+        Style::default().fg(self.text).bg(self.background)
+    }
+
+    pub fn priority(&self, priority: TaskPriority) -> Color {
+        self.priority[priority as usize]
+    }
+
+    /// A status's color: the one `workflow.toml` gives it, else the theme's.
+    pub fn status(&self, workflow: &Workflow, name: &str) -> Color {
+        workflow
+            .color(name)
+            .and_then(utils::color_from_str)
+            .or_else(|| self.status.get(&workflow::normalize(name)).copied())
+            .unwrap_or(self.status_fallback)
+    }
+}
+
+/// Names a theme file can set, in the order the README lists them.
+const SLOTS: &[&str] = &[
+    "background", "text", "muted", "faint", "border", "accent", "label", "key", "info", "selection", "header",
+    "highlight", "badge", "badge_text", "success", "danger", "special", "search_match", "status_fallback",
+];
+
+fn statuses(colors: &[(&str, Color)]) -> BTreeMap<String, Color> {
+    colors.iter().map(|(name, color)| (workflow::normalize(name), *color)).collect()
+}
+
+fn parse(slot: &str, value: &str) -> Result<Color, String> {
+    utils::color_from_str(value)
+        .ok_or_else(|| format!("{} has invalid color \"{}\" (expected #RRGGBB or a name such as cyan or light_red)", slot, value))
+}
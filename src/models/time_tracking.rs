@@ -0,0 +1,74 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::workflow::Workflow;
+use crate::models::Task;
+
+/// One stretch of work on a task.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TimeEntry {
+    pub start: DateTime<Utc>,
+    /// `None` while the timer is running.
+    #[serde(default)]
+    pub end: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub note: String,
+}
+
+impl TimeEntry {
+    /// Length of the entry; a running one counts up to `now`.
+    pub fn duration(&self, now: DateTime<Utc>) -> Duration {
+        (self.end.unwrap_or(now) - self.start).max(Duration::zero())
+    }
+
+    /// The part of the entry that falls between `from` and `to`.
+    pub fn overlap(&self, from: DateTime<Utc>, to: DateTime<Utc>, now: DateTime<Utc>) -> Duration {
+        let start = self.start.max(from);
+        let end = self.end.unwrap_or(now).min(to);
+        (end - start).max(Duration::zero())
+    }
+}
+
+impl Task {
+    pub fn running_timer(&self) -> Option<&TimeEntry> {
+        self.time_entries.iter().find(|entry| entry.end.is_none())
+    }
+
+    /// Starts a timer unless one is already running on this task.
+    pub fn start_timer(&mut self, now: DateTime<Utc>, note: &str) {
+        if self.running_timer().is_none() {
+            self.time_entries.push(TimeEntry { start: now, end: None, note: note.to_string() });
+        }
+    }
+
+    /// Stops the running timer, replacing its note when `note` is not empty.
+    /// Returns how long it ran, or `None` if no timer was running.
+    pub fn stop_timer(&mut self, now: DateTime<Utc>, note: &str) -> Option<Duration> {
+        let entry = self.time_entries.iter_mut().find(|entry| entry.end.is_none())?;
+        entry.end = Some(now.max(entry.start));
+        if !note.is_empty() {
+            entry.note = note.to_string();
+        }
+        Some(entry.duration(now))
+    }
+
+    /// Time tracked on the task so far, including a running timer.
+    pub fn time_spent(&self, now: DateTime<Utc>) -> Duration {
+        self.time_entries
+            .iter()
+            .fold(Duration::zero(), |total, entry| total + entry.duration(now))
+    }
+}
+
+/// The task whose timer is running. Only one timer runs at a time.
+pub fn running(tasks: &[Task]) -> Option<&Task> {
+    tasks.iter().find(|task| task.running_timer().is_some())
+}
+
+/// Stops the timer of a task that has just moved into a done status.
+pub fn stop_when_done(before: &Task, after: &mut Task, workflow: &Workflow, now: DateTime<Utc>) -> Option<Duration> {
+    if before.status == after.status || !workflow.is_done(&after.status) {
+        return None;
+    }
+    after.stop_timer(now, "")
+}
@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::TaskStatus;
+use crate::utils;
+
+/// One column of the board.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusDef {
+    pub name: String,
+    /// Overrides the theme's color for this status.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Tasks in a done status count as finished for stats and overdue checks.
+    #[serde(default)]
+    pub done: bool,
+    /// Statuses a task may move to from this one; any status when omitted.
+    #[serde(default)]
+    pub transitions: Option<Vec<String>>,
+}
+
+/// The ordered statuses a board uses, read from `workflow.toml`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workflow {
+    #[serde(rename = "status")]
+    pub statuses: Vec<StatusDef>,
+}
+
+impl Default for Workflow {
+    fn default() -> Self {
+        let status = |name: &str, done: bool| StatusDef {
+            name: name.to_string(),
+            color: None,
+            done,
+            transitions: None,
+        };
+        Self {
+            statuses: vec![
+                status("Todo", false),
+                status("In Progress", false),
+                status("Done", true),
+            ],
+        }
+    }
+}
+
+impl Workflow {
+    /// Checks that the workflow can drive a board; returns a message for the first problem found.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.statuses.is_empty() {
+            return Err("workflow must define at least one status".to_string());
+        }
+        for (i, status) in self.statuses.iter().enumerate() {
+            if status.name.trim().is_empty() {
+                return Err(format!("status #{} has an empty name", i + 1));
+            }
+            if self.statuses[..i].iter().any(|other| same_name(&other.name, &status.name)) {
+                return Err(format!("status \"{}\" is defined twice", status.name));
+            }
+            if let Some(color) = &status.color
+                && !utils::is_hex_color(color)
+            {
+                return Err(format!("status \"{}\" has invalid color \"{}\" (expected #RRGGBB)", status.name, color));
+            }
+            for target in status.transitions.iter().flatten() {
+                if self.position(target).is_none() {
+                    return Err(format!("status \"{}\" allows a transition to unknown status \"{}\"", status.name, target));
+                }
+            }
+        }
+        if !self.statuses.iter().any(|status| status.done) {
+            return Err("workflow needs at least one status with done = true".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn statuses(&self) -> impl Iterator<Item = TaskStatus> + '_ {
+        self.statuses.iter().map(|status| TaskStatus::new(&status.name))
+    }
+
+    /// Where new tasks start.
+    pub fn initial(&self) -> TaskStatus {
+        TaskStatus::new(&self.statuses[0].name)
+    }
+
+    /// Where `done` sends a task.
+    pub fn first_done(&self) -> TaskStatus {
+        self.statuses
+            .iter()
+            .find(|status| status.done)
+            .map_or_else(|| self.initial(), |status| TaskStatus::new(&status.name))
+    }
+
+    /// Column index of a status name, ignoring case, spaces, dashes and underscores.
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.statuses.iter().position(|status| same_name(&status.name, name))
+    }
+
+    /// Column a task is shown in; statuses missing from the workflow land in the first column.
+    pub fn column_of(&self, status: &TaskStatus) -> usize {
+        self.position(status.as_str()).unwrap_or(0)
+    }
+
+    /// The workflow's spelling of a status typed by a user.
+    pub fn resolve(&self, name: &str) -> Result<TaskStatus, String> {
+        self.position(name)
+            .map(|i| TaskStatus::new(&self.statuses[i].name))
+            .ok_or_else(|| {
+                let names: Vec<&str> = self.statuses.iter().map(|s| s.name.as_str()).collect();
+                format!("unknown status \"{}\" (expected one of: {})", name, names.join(", "))
+            })
+    }
+
+    pub fn is_done(&self, status: &TaskStatus) -> bool {
+        self.position(status.as_str()).is_some_and(|i| self.statuses[i].done)
+    }
+
+    /// The color `workflow.toml` gives a status, if any.
+    pub fn color(&self, name: &str) -> Option<&str> {
+        self.position(name).and_then(|i| self.statuses[i].color.as_deref())
+    }
+
+    pub fn can_transition(&self, from: &TaskStatus, to: &TaskStatus) -> bool {
+        if same_name(from.as_str(), to.as_str()) {
+            return true;
+        }
+        // Tasks stuck in a status the workflow no longer knows may go anywhere
+        let Some(i) = self.position(from.as_str()) else {
+            return true;
+        };
+        match &self.statuses[i].transitions {
+            None => true,
+            Some(targets) => targets.iter().any(|target| same_name(target, to.as_str())),
+        }
+    }
+
+    /// Statuses reachable from `from` (including itself), in column order.
+    pub fn allowed_from(&self, from: &TaskStatus) -> Vec<TaskStatus> {
+        self.statuses()
+            .filter(|to| self.can_transition(from, to))
+            .collect()
+    }
+
+    /// The next allowed status after `from` in column order, wrapping around.
+    pub fn next_status(&self, from: &TaskStatus) -> Option<TaskStatus> {
+        let (start, count) = (self.column_of(from), self.statuses.len());
+        (1..count)
+            .map(|offset| TaskStatus::new(&self.statuses[(start + offset) % count].name))
+            .find(|to| self.can_transition(from, to))
+    }
+}
+
+fn same_name(a: &str, b: &str) -> bool {
+    normalize(a) == normalize(b)
+}
+
+/// A status name with case, spaces, dashes and underscores dropped.
+pub fn normalize(name: &str) -> String {
+    name.to_lowercase().replace(['-', '_', ' '], "")
+}
@@ -0,0 +1,232 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use strum::IntoEnumIterator;
+
+use crate::app::calendar;
+use crate::models::workflow::Workflow;
+use crate::models::{Task, TaskPriority, TaskStatus};
+
+/// How many days the burnup and cumulative flow charts cover.
+pub const CHART_DAYS: i64 = 30;
+/// How many weeks the throughput chart covers.
+pub const THROUGHPUT_WEEKS: i64 = 8;
+/// How many days and weeks the time tracking charts cover.
+pub const TRACKED_DAYS: i64 = 7;
+pub const TRACKED_WEEKS: i64 = 6;
+
+/// Task counts by status at the end of one day.
+#[derive(Debug, Clone)]
+pub struct DailySnapshot {
+    pub date: NaiveDate,
+    /// One count per workflow status, in column order.
+    pub counts: Vec<u64>,
+    /// Tasks in any done status.
+    pub done: u64,
+}
+
+impl DailySnapshot {
+    /// Every task that existed on that day, i.e. the scope line of a burnup chart.
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Work left on that day, i.e. the burndown line.
+    pub fn remaining(&self) -> u64 {
+        self.total() - self.done
+    }
+
+    /// Tasks in this column or any later one, i.e. the top edge of its cumulative flow band.
+    pub fn stacked_from(&self, column: usize) -> u64 {
+        self.counts[column..].iter().sum()
+    }
+}
+
+/// One snapshot per day for the last `days` days, ending today.
+pub fn daily_snapshots(tasks: &[Task], workflow: &Workflow, days: i64) -> Vec<DailySnapshot> {
+    let today = Local::now().date_naive();
+    (0..days)
+        .rev()
+        .map(|offset| {
+            let date = today - Duration::days(offset);
+            let end = end_of_day(date);
+            let mut snapshot = DailySnapshot { date, counts: vec![0; workflow.statuses.len()], done: 0 };
+            for status in tasks.iter().filter_map(|task| task.status_at(end)) {
+                snapshot.counts[workflow.column_of(&status)] += 1;
+                if workflow.is_done(&status) {
+                    snapshot.done += 1;
+                }
+            }
+            snapshot
+        })
+        .collect()
+}
+
+/// Tasks completed per week for the last `weeks` weeks, oldest first, keyed by the week's Monday.
+pub fn weekly_throughput(tasks: &[Task], workflow: &Workflow, weeks: i64) -> Vec<(NaiveDate, u64)> {
+    let this_week = calendar::week_start(Local::now().date_naive());
+    (0..weeks)
+        .rev()
+        .map(|offset| {
+            let start = this_week - Duration::weeks(offset);
+            let count = tasks
+                .iter()
+                .filter_map(|task| task.completed_at(workflow))
+                .filter(|done| calendar::week_start(done.with_timezone(&Local).date_naive()) == start)
+                .count();
+            (start, count as u64)
+        })
+        .collect()
+}
+
+/// Average time from creation to completion over finished tasks.
+pub fn average_lead_time(tasks: &[Task], workflow: &Workflow) -> Option<Duration> {
+    average(tasks.iter().filter_map(|task| Some(task.completed_at(workflow)? - task.created_at)))
+}
+
+/// Average time from first starting work to completion over finished tasks.
+pub fn average_cycle_time(tasks: &[Task], workflow: &Workflow) -> Option<Duration> {
+    average(tasks.iter().filter_map(|task| Some(task.completed_at(workflow)? - task.started_at(workflow)?)))
+}
+
+/// How long the task has spent in each status so far, in workflow order.
+pub fn time_in_status(task: &Task, workflow: &Workflow, now: DateTime<Utc>) -> Vec<(TaskStatus, Duration)> {
+    let changes: Vec<(TaskStatus, DateTime<Utc>)> = task.status_changes().collect();
+    let mut totals: Vec<(TaskStatus, Duration)> =
+        workflow.statuses().map(|status| (status, Duration::zero())).collect();
+
+    for (i, (status, entered)) in changes.iter().enumerate() {
+        let left = changes.get(i + 1).map_or(now, |(_, at)| *at);
+        totals[workflow.column_of(status)].1 += left - *entered;
+    }
+    totals
+}
+
+/// Time tracked on any task per day for the last `days` days, oldest first.
+/// Entries that run past midnight count towards both days.
+pub fn tracked_per_day(tasks: &[Task], days: i64, now: DateTime<Utc>) -> Vec<(NaiveDate, Duration)> {
+    let today = now.with_timezone(&Local).date_naive();
+    (0..days)
+        .rev()
+        .map(|offset| {
+            let date = today - Duration::days(offset);
+            (date, tracked_between(tasks, start_of_day(date), start_of_day(date + Duration::days(1)), now))
+        })
+        .collect()
+}
+
+/// Time tracked on any task per week for the last `weeks` weeks, oldest first, keyed by the week's Monday.
+pub fn tracked_per_week(tasks: &[Task], weeks: i64, now: DateTime<Utc>) -> Vec<(NaiveDate, Duration)> {
+    let this_week = calendar::week_start(now.with_timezone(&Local).date_naive());
+    (0..weeks)
+        .rev()
+        .map(|offset| {
+            let start = this_week - Duration::weeks(offset);
+            (start, tracked_between(tasks, start_of_day(start), start_of_day(start + Duration::weeks(1)), now))
+        })
+        .collect()
+}
+
+fn tracked_between(tasks: &[Task], from: DateTime<Utc>, to: DateTime<Utc>, now: DateTime<Utc>) -> Duration {
+    tasks
+        .iter()
+        .flat_map(|task| &task.time_entries)
+        .fold(Duration::zero(), |total, entry| total + entry.overlap(from, to, now))
+}
+
+/// Estimated and tracked time summed over finished tasks that have both, and how
+/// many tasks that is. Open tasks are left out because their time is still growing.
+pub fn estimate_calibration(tasks: &[Task], workflow: &Workflow, now: DateTime<Utc>) -> Option<(Duration, Duration, usize)> {
+    let (estimated, tracked, count) = tasks
+        .iter()
+        .filter(|task| workflow.is_done(&task.status) && !task.time_entries.is_empty())
+        .filter_map(|task| Some((Duration::minutes(i64::from(task.estimate_minutes?)), task.time_spent(now))))
+        .fold((Duration::zero(), Duration::zero(), 0), |(estimated, tracked, count), (e, t)| {
+            (estimated + e, tracked + t, count + 1)
+        });
+    (count > 0).then_some((estimated, tracked, count))
+}
+
+/// Tracked time per story point over finished tasks that have points and tracked time.
+pub fn time_per_point(tasks: &[Task], workflow: &Workflow, now: DateTime<Utc>) -> Option<Duration> {
+    let (tracked, points) = tasks
+        .iter()
+        .filter(|task| workflow.is_done(&task.status) && !task.time_entries.is_empty())
+        .filter_map(|task| Some((task.time_spent(now), task.points.filter(|p| *p > 0)?)))
+        .fold((Duration::zero(), 0u64), |(tracked, points), (t, p)| (tracked + t, points + u64::from(p)));
+    let points = i64::try_from(points).ok().filter(|points| *points > 0)?;
+    tracked.num_seconds().checked_div(points).map(Duration::seconds)
+}
+
+/// Tracked time as a percentage of the estimate.
+pub fn percent_of_estimate(estimated: Duration, tracked: Duration) -> Option<i64> {
+    let estimated = estimated.num_seconds();
+    (estimated > 0).then(|| (tracked.num_seconds() * 100 + estimated / 2) / estimated)
+}
+
+pub fn priority_counts(tasks: &[Task]) -> Vec<(TaskPriority, u64)> {
+    TaskPriority::iter()
+        .map(|priority| (priority, tasks.iter().filter(|t| t.priority == priority).count() as u64))
+        .collect()
+}
+
+/// Renders a duration the way people talk about lead times: "3d 4h", "5h 12m", "40m".
+pub fn format_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes().max(0);
+    let (days, hours, minutes) = (minutes / (24 * 60), minutes / 60 % 24, minutes % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+fn average(durations: impl Iterator<Item = Duration>) -> Option<Duration> {
+    let (total, count) = durations.fold((Duration::zero(), 0), |(total, count), d| (total + d, count + 1));
+    (count > 0).then(|| total / count)
+}
+
+fn start_of_day(date: NaiveDate) -> DateTime<Utc> {
+    local_to_utc(date.and_hms_opt(0, 0, 0).expect("valid time"))
+}
+
+fn end_of_day(date: NaiveDate) -> DateTime<Utc> {
+    local_to_utc(date.and_hms_opt(23, 59, 59).expect("valid time"))
+}
+
+fn local_to_utc(time: NaiveDateTime) -> DateTime<Utc> {
+    Local
+        .from_local_datetime(&time)
+        .earliest()
+        .map_or_else(|| time.and_utc(), |local| local.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::time_tracking::TimeEntry;
+    use crate::models::TaskStatus;
+
+    fn finished(points: u32, hours: i64, now: DateTime<Utc>) -> Task {
+        let mut task = Task::new("Done", "", TaskStatus::new("Done"), TaskPriority::Medium);
+        task.points = Some(points);
+        task.time_entries.push(TimeEntry { start: now - Duration::hours(hours), end: Some(now), note: String::new() });
+        task
+    }
+
+    #[test]
+    fn time_per_point_divides_tracked_time() {
+        let now = Utc::now();
+        let tasks = vec![finished(2, 3, now), finished(1, 3, now)];
+        assert_eq!(time_per_point(&tasks, &Workflow::default(), now), Some(Duration::hours(2)));
+    }
+
+    #[test]
+    fn time_per_point_handles_large_points() {
+        let now = Utc::now();
+        let tasks = vec![finished(u32::MAX, 10, now), finished(u32::MAX, 10, now)];
+        let per_point = time_per_point(&tasks, &Workflow::default(), now).unwrap();
+        assert_eq!(per_point, Duration::zero());
+        assert_eq!(time_per_point(&[], &Workflow::default(), now), None);
+    }
+}
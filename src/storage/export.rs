@@ -0,0 +1,67 @@
+use anyhow::Result;
+use chrono::Local;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::models::Task;
+use crate::storage::storage_manager;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            _ => Err(format!("unknown export format \"{}\" (expected csv or json)", s)),
+        }
+    }
+}
+
+/// Writes `tasks` to `path`: JSON in the storage format, or one CSV row per task
+/// with the fields a spreadsheet needs.
+pub fn export(tasks: &[&Task], format: ExportFormat, path: &Path) -> Result<()> {
+    let contents = match format {
+        ExportFormat::Json => serde_json::to_string_pretty(tasks)?,
+        ExportFormat::Csv => to_csv(tasks),
+    };
+    storage_manager::write_atomic(path, contents.as_bytes())
+}
+
+fn to_csv(tasks: &[&Task]) -> String {
+    let mut csv = String::from("id,title,status,priority,due,tags,points,estimate_minutes,created,description\n");
+    for task in tasks {
+        let due = task.due_date.map(|due| due.with_timezone(&Local).format("%Y-%m-%d").to_string());
+        let fields = [
+            task.id.clone(),
+            task.title.clone(),
+            task.status.to_string(),
+            task.priority.to_string(),
+            due.unwrap_or_default(),
+            task.tags.join(";"),
+            task.points.map(|p| p.to_string()).unwrap_or_default(),
+            task.estimate_minutes.map(|m| m.to_string()).unwrap_or_default(),
+            task.created_at.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string(),
+            task.description.clone(),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+// Quote fields with separators, quotes or line breaks, doubling inner quotes
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
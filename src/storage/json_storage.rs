@@ -0,0 +1,175 @@
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use crate::models::{Task, TrashedTask};
+use crate::storage::storage_manager::{self, Envelope, LoadedTasks};
+use crate::storage::{Storage, TaskQuery, Write};
+
+/// Keeps every task in a single tasks.json file, rewritten on each change.
+pub struct JsonStorage {
+    data_dir: PathBuf,
+    tasks: Vec<Task>,
+    trash: Vec<TrashedTask>,
+    envelope: Envelope,
+    loaded: bool,
+}
+
+impl JsonStorage {
+    pub fn new(data_dir: PathBuf) -> Self {
+        Self {
+            data_dir,
+            tasks: Vec::new(),
+            trash: Vec::new(),
+            envelope: Envelope::default(),
+            loaded: false,
+        }
+    }
+
+    // Writing before the first load would overwrite the file with a partial list
+    fn ensure_loaded(&mut self) -> Result<()> {
+        if !self.loaded {
+            self.load()?;
+        }
+        Ok(())
+    }
+
+    fn merge(&mut self, task: &Task) {
+        match self.tasks.iter_mut().find(|t| t.id == task.id) {
+            Some(existing) => *existing = task.clone(),
+            None => self.tasks.push(task.clone()),
+        }
+    }
+
+    /// Makes a write in memory only; `apply` saves the file once for the batch.
+    fn stage(&mut self, write: &Write) {
+        match write {
+            Write::Upsert(task) => self.merge(task),
+            Write::Delete(id) => self.tasks.retain(|t| &t.id != id),
+            Write::Trash(entry) => {
+                self.tasks.retain(|t| t.id != entry.task.id);
+                self.trash.retain(|e| e.task.id != entry.task.id);
+                self.trash.push(entry.clone());
+            }
+            Write::Restore(task) => {
+                self.trash.retain(|e| e.task.id != task.id);
+                self.merge(task);
+            }
+            Write::Purge(ids) => self.trash.retain(|e| !ids.contains(&e.task.id)),
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        storage_manager::save_tasks(&self.data_dir, &self.tasks, &self.trash, &self.envelope)
+    }
+}
+
+impl Storage for JsonStorage {
+    fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
+    fn load(&mut self) -> Result<LoadedTasks> {
+        let loaded = storage_manager::load_tasks(&self.data_dir)?;
+        self.tasks = loaded.tasks.clone();
+        self.trash = loaded.trash.clone();
+        self.envelope = loaded.envelope.clone();
+        self.loaded = true;
+        Ok(loaded)
+    }
+
+    fn upsert(&mut self, task: &Task) -> Result<()> {
+        self.apply(&[Write::Upsert(task.clone())])
+    }
+
+    fn upsert_many(&mut self, tasks: &[Task]) -> Result<()> {
+        let writes: Vec<Write> = tasks.iter().cloned().map(Write::Upsert).collect();
+        self.apply(&writes)
+    }
+
+    fn delete(&mut self, id: &str) -> Result<()> {
+        self.apply(&[Write::Delete(id.to_string())])
+    }
+
+    fn query(&mut self, query: &TaskQuery) -> Result<Vec<Task>> {
+        self.ensure_loaded()?;
+        Ok(self.tasks.iter().filter(|t| query.matches(t)).cloned().collect())
+    }
+
+    fn trash(&mut self, entry: &TrashedTask) -> Result<()> {
+        self.apply(&[Write::Trash(entry.clone())])
+    }
+
+    fn restore(&mut self, task: &Task) -> Result<()> {
+        self.apply(&[Write::Restore(task.clone())])
+    }
+
+    fn purge(&mut self, ids: &[String]) -> Result<()> {
+        self.apply(&[Write::Purge(ids.to_vec())])
+    }
+
+    fn apply(&mut self, writes: &[Write]) -> Result<()> {
+        self.ensure_loaded()?;
+        let (tasks, trash) = (self.tasks.clone(), self.trash.clone());
+        for write in writes {
+            self.stage(write);
+        }
+        // The file is written once, so a failed save leaves the cache as the file is
+        if let Err(err) = self.save() {
+            self.tasks = tasks;
+            self.trash = trash;
+            return Err(err);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TaskPriority, TaskStatus};
+    use chrono::Utc;
+    use std::fs;
+
+    fn task(title: &str) -> Task {
+        Task::new(title, "", TaskStatus::new("Todo"), TaskPriority::Medium)
+    }
+
+    #[test]
+    fn apply_writes_a_batch_at_once() {
+        let dir = std::env::temp_dir().join(format!("taskx-json-batch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (kept, trashed) = (task("Kept"), task("Trashed"));
+
+        let mut storage = JsonStorage::new(dir.clone());
+        storage.upsert_many(&[kept.clone(), trashed.clone()]).unwrap();
+        storage
+            .apply(&[
+                Write::Trash(TrashedTask { task: trashed.clone(), deleted_at: Utc::now() }),
+                Write::Upsert(Task { title: "Kept and renamed".to_string(), ..kept.clone() }),
+            ])
+            .unwrap();
+        let loaded = JsonStorage::new(dir.clone()).load().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(loaded.tasks.len(), 1);
+        assert_eq!(loaded.tasks[0].title, "Kept and renamed");
+        assert_eq!(loaded.trash[0].task.id, trashed.id);
+    }
+
+    #[test]
+    fn failed_batch_leaves_nothing_applied() {
+        let dir = std::env::temp_dir().join(format!("taskx-json-failed-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut storage = JsonStorage::new(dir.clone());
+        storage.load().unwrap();
+        // A directory where tasks.json belongs makes the save fail
+        fs::create_dir_all(storage_manager::get_tasks_file_path(&dir)).unwrap();
+
+        let result = storage.apply(&[Write::Upsert(task("First")), Write::Upsert(task("Second"))]);
+        let remaining = storage.query(&TaskQuery::default()).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(result.is_err());
+        assert!(remaining.is_empty());
+    }
+}
@@ -0,0 +1,398 @@
+use anyhow::{Result, anyhow};
+use serde_json::{Value, json};
+
+/// Schema version written by this build.
+pub const CURRENT_VERSION: u64 = 3;
+
+/// A single upgrade step from `from` to `from + 1`.
+struct Migration {
+    from: u64,
+    description: &'static str,
+    apply: fn(Value) -> Result<Value>,
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 0,
+        description: "wrap bare task array in a versioned envelope",
+        apply: wrap_in_envelope,
+    },
+    Migration {
+        from: 1,
+        description: "turn recorded status changes into task history",
+        apply: status_changes_to_history,
+    },
+    Migration {
+        from: 2,
+        description: "rename status InProgress to \"In Progress\" for configurable workflows",
+        apply: rename_in_progress,
+    },
+];
+
+/// What happened while bringing a document up to `CURRENT_VERSION`.
+#[derive(Debug, Default)]
+pub struct MigrationReport {
+    pub from_version: u64,
+    pub applied: Vec<String>,
+    pub newer_than_supported: bool,
+}
+
+impl MigrationReport {
+    pub fn summary(&self) -> Option<String> {
+        if self.newer_than_supported {
+            return Some(format!(
+                "tasks.json was written by a newer TaskX (schema v{}, this build knows v{}); fields it added are kept but not used",
+                self.from_version, CURRENT_VERSION
+            ));
+        }
+        if self.applied.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "Upgraded tasks.json from v{} to v{}: {}",
+            self.from_version,
+            CURRENT_VERSION,
+            self.applied.join("; ")
+        ))
+    }
+}
+
+/// Detects the schema version of a raw document and upgrades it step by step.
+pub fn migrate(mut document: Value) -> Result<(Value, MigrationReport)> {
+    let version = detect_version(&document)?;
+    let mut report = MigrationReport { from_version: version, ..Default::default() };
+
+    // Documents from the future are read as-is; serde ignores the fields we don't know
+    if version > CURRENT_VERSION {
+        report.newer_than_supported = true;
+        return Ok((document, report));
+    }
+
+    let mut current = version;
+    while current < CURRENT_VERSION {
+        let migration = MIGRATIONS
+            .iter()
+            .find(|m| m.from == current)
+            .ok_or_else(|| anyhow!("no migration from schema v{}", current))?;
+
+        document = (migration.apply)(document)?;
+        report.applied.push(format!("v{}→v{} {}", current, current + 1, migration.description));
+        current += 1;
+    }
+
+    Ok((document, report))
+}
+
+fn detect_version(document: &Value) -> Result<u64> {
+    match document {
+        // Files written before the envelope existed are a bare array of tasks
+        Value::Array(_) => Ok(0),
+        Value::Object(map) => map
+            .get("version")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| anyhow!("task file has no numeric \"version\" field")),
+        _ => Err(anyhow!("task file is neither a task list nor a versioned document")),
+    }
+}
+
+fn wrap_in_envelope(document: Value) -> Result<Value> {
+    Ok(json!({ "version": 1, "tasks": document }))
+}
+
+fn status_changes_to_history(mut document: Value) -> Result<Value> {
+    if let Some(tasks) = document.get_mut("tasks").and_then(Value::as_array_mut) {
+        tasks.iter_mut().for_each(build_history);
+    }
+    if let Some(trash) = document.get_mut("trash").and_then(Value::as_array_mut) {
+        for entry in trash {
+            if let Some(task) = entry.get_mut("task") {
+                build_history(task);
+            }
+        }
+    }
+    document["version"] = json!(2);
+    Ok(document)
+}
+
+// Tasks that predate status tracking get a single "created" event in their current status
+fn build_history(task: &mut Value) {
+    let Some(task) = task.as_object_mut() else {
+        return;
+    };
+    let changes = task.remove("status_changes");
+    if task.contains_key("history") {
+        return;
+    }
+
+    let mut history = Vec::new();
+    let mut previous: Option<Value> = None;
+    for change in changes.as_ref().and_then(Value::as_array).into_iter().flatten() {
+        let (status, at) = (change["status"].clone(), change["at"].clone());
+        history.push(match previous {
+            None => json!({ "at": at, "event": "created", "status": status }),
+            Some(from) => json!({ "at": at, "event": "status_changed", "from": from, "to": status }),
+        });
+        previous = Some(status);
+    }
+    if history.is_empty() {
+        history.push(json!({
+            "at": task.get("created_at").cloned().unwrap_or(Value::Null),
+            "event": "created",
+            "status": task.get("status").cloned().unwrap_or(Value::Null),
+        }));
+    }
+
+    task.insert("history".to_string(), Value::Array(history));
+}
+
+fn rename_in_progress(mut document: Value) -> Result<Value> {
+    if let Some(tasks) = document.get_mut("tasks").and_then(Value::as_array_mut) {
+        tasks.iter_mut().for_each(rename_task_statuses);
+    }
+    if let Some(trash) = document.get_mut("trash").and_then(Value::as_array_mut) {
+        for entry in trash {
+            if let Some(task) = entry.get_mut("task") {
+                rename_task_statuses(task);
+            }
+        }
+    }
+    document["version"] = json!(3);
+    Ok(document)
+}
+
+fn rename_task_statuses(task: &mut Value) {
+    rename_status(task.get_mut("status"));
+    if let Some(history) = task.get_mut("history").and_then(Value::as_array_mut) {
+        for event in history {
+            for key in ["status", "from", "to"] {
+                rename_status(event.get_mut(key));
+            }
+        }
+    }
+}
+
+fn rename_status(status: Option<&mut Value>) {
+    if let Some(status) = status
+        && status == "InProgress"
+    {
+        *status = json!("In Progress");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::EventKind;
+    use crate::storage::json_storage::JsonStorage;
+    use crate::storage::storage_manager;
+    use crate::storage::Storage;
+
+    fn v0() -> Value {
+        json!([
+            {
+                "id": "a",
+                "title": "Ship it",
+                "status": "InProgress",
+                "priority": "High",
+                "created_at": "2026-01-01T09:00:00Z",
+                "status_changes": [
+                    { "status": "Todo", "at": "2026-01-01T09:00:00Z" },
+                    { "status": "InProgress", "at": "2026-01-02T09:00:00Z" }
+                ]
+            },
+            {
+                "id": "b",
+                "title": "Untracked",
+                "status": "Done",
+                "priority": "Low",
+                "created_at": "2026-01-03T09:00:00Z"
+            }
+        ])
+    }
+
+    fn v1() -> Value {
+        json!({
+            "version": 1,
+            "tasks": [{
+                "id": "a",
+                "title": "Ship it",
+                "status": "Todo",
+                "priority": "Medium",
+                "created_at": "2026-01-01T09:00:00Z",
+                "status_changes": [{ "status": "Todo", "at": "2026-01-01T09:00:00Z" }]
+            }],
+            "trash": [{
+                "task": {
+                    "id": "t",
+                    "title": "Old idea",
+                    "status": "InProgress",
+                    "priority": "Low",
+                    "created_at": "2026-01-04T09:00:00Z",
+                    "status_changes": [
+                        { "status": "Todo", "at": "2026-01-04T09:00:00Z" },
+                        { "status": "InProgress", "at": "2026-01-05T09:00:00Z" }
+                    ]
+                },
+                "deleted_at": "2026-01-06T09:00:00Z"
+            }]
+        })
+    }
+
+    fn v2() -> Value {
+        json!({
+            "version": 2,
+            "tasks": [{
+                "id": "a",
+                "title": "Ship it",
+                "status": "InProgress",
+                "priority": "Medium",
+                "created_at": "2026-01-01T09:00:00Z",
+                "history": [
+                    { "at": "2026-01-01T09:00:00Z", "event": "created", "status": "Todo" },
+                    { "at": "2026-01-02T09:00:00Z", "event": "status_changed", "from": "Todo", "to": "InProgress" }
+                ]
+            }],
+            "trash": [{
+                "task": {
+                    "id": "t",
+                    "title": "Old idea",
+                    "status": "InProgress",
+                    "priority": "Low",
+                    "created_at": "2026-01-04T09:00:00Z",
+                    "history": [{ "at": "2026-01-04T09:00:00Z", "event": "created", "status": "InProgress" }]
+                },
+                "deleted_at": "2026-01-06T09:00:00Z"
+            }]
+        })
+    }
+
+    #[test]
+    fn bare_array_is_upgraded_to_current_version() {
+        let (document, report) = migrate(v0()).unwrap();
+
+        assert_eq!(report.from_version, 0);
+        assert_eq!(report.applied.len(), 3);
+        assert_eq!(document["version"], json!(CURRENT_VERSION));
+        assert_eq!(document["tasks"][0]["status"], "In Progress");
+        assert!(document["tasks"][0].get("status_changes").is_none());
+        assert_eq!(
+            document["tasks"][0]["history"],
+            json!([
+                { "at": "2026-01-01T09:00:00Z", "event": "created", "status": "Todo" },
+                { "at": "2026-01-02T09:00:00Z", "event": "status_changed", "from": "Todo", "to": "In Progress" }
+            ])
+        );
+        // Without recorded changes the task was created in its current status
+        assert_eq!(
+            document["tasks"][1]["history"],
+            json!([{ "at": "2026-01-03T09:00:00Z", "event": "created", "status": "Done" }])
+        );
+    }
+
+    #[test]
+    fn v1_builds_history_for_tasks_and_trash() {
+        let (document, report) = migrate(v1()).unwrap();
+
+        assert_eq!(report.from_version, 1);
+        assert_eq!(report.applied.len(), 2);
+        assert_eq!(document["tasks"][0]["history"][0]["event"], "created");
+        let trashed = &document["trash"][0]["task"];
+        assert!(trashed.get("status_changes").is_none());
+        assert_eq!(trashed["status"], "In Progress");
+        assert_eq!(trashed["history"][1]["to"], "In Progress");
+    }
+
+    #[test]
+    fn v1_keeps_existing_history() {
+        let mut document = v1();
+        document["tasks"][0]["history"] = json!([{ "at": "2026-01-01T10:00:00Z", "event": "created", "status": "Todo" }]);
+
+        let (document, _) = migrate(document).unwrap();
+
+        assert!(document["tasks"][0].get("status_changes").is_none());
+        assert_eq!(document["tasks"][0]["history"][0]["at"], "2026-01-01T10:00:00Z");
+    }
+
+    #[test]
+    fn v2_renames_in_progress_in_tasks_and_trash() {
+        let (document, report) = migrate(v2()).unwrap();
+
+        assert_eq!(report.applied.len(), 1);
+        assert_eq!(document["tasks"][0]["status"], "In Progress");
+        assert_eq!(document["tasks"][0]["history"][1]["from"], "Todo");
+        assert_eq!(document["tasks"][0]["history"][1]["to"], "In Progress");
+        assert_eq!(document["trash"][0]["task"]["status"], "In Progress");
+        assert_eq!(document["trash"][0]["task"]["history"][0]["status"], "In Progress");
+    }
+
+    #[test]
+    fn migrated_documents_decode() {
+        for fixture in [v0(), v1(), v2()] {
+            let (document, report) = storage_manager::decode_document(fixture).unwrap();
+            assert!(report.summary().is_some());
+            assert!(document.tasks.iter().all(|task| !task.history.is_empty()));
+        }
+
+        let (document, _) = storage_manager::decode_document(v1()).unwrap();
+        let trashed = &document.trash[0].task;
+        assert_eq!(trashed.status.as_str(), "In Progress");
+        assert!(matches!(&trashed.history[1].kind, EventKind::StatusChanged { to, .. } if to.as_str() == "In Progress"));
+    }
+
+    #[test]
+    fn current_version_is_left_alone() {
+        let (document, _) = migrate(v2()).unwrap();
+        let (again, report) = migrate(document.clone()).unwrap();
+
+        assert_eq!(again, document);
+        assert!(report.applied.is_empty());
+        assert_eq!(report.summary(), None);
+    }
+
+    #[test]
+    fn newer_document_is_read_as_is() {
+        let mut document = v2();
+        document["version"] = json!(CURRENT_VERSION + 1);
+        document["tasks"][0]["status"] = json!("Todo");
+        document["tasks"][0]["new_field"] = json!(true);
+
+        let (migrated, report) = migrate(document.clone()).unwrap();
+
+        assert_eq!(migrated, document);
+        assert!(report.newer_than_supported);
+        assert!(report.summary().unwrap().contains("newer TaskX"));
+        let (decoded, _) = storage_manager::decode_document(document).unwrap();
+        assert_eq!(decoded.tasks[0].title, "Ship it");
+    }
+
+    #[test]
+    fn newer_document_survives_a_save() {
+        let dir = std::env::temp_dir().join(format!("taskx-newer-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut document = v2();
+        document["version"] = json!(CURRENT_VERSION + 1);
+        document["tasks"][0]["status"] = json!("Todo");
+        document["tasks"][0]["new_field"] = json!({ "nested": [1, 2] });
+        document["board_settings"] = json!({ "color": "blue" });
+        let path = storage_manager::get_tasks_file_path(&dir);
+        std::fs::write(&path, document.to_string()).unwrap();
+
+        let mut storage = JsonStorage::new(dir.clone());
+        let mut task = storage.load().unwrap().tasks.remove(0);
+        task.title = "Ship it today".to_string();
+        storage.upsert(&task).unwrap();
+        let saved: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(saved["version"], json!(CURRENT_VERSION + 1));
+        assert_eq!(saved["board_settings"], document["board_settings"]);
+        assert_eq!(saved["tasks"][0]["title"], "Ship it today");
+        assert_eq!(saved["tasks"][0]["new_field"], document["tasks"][0]["new_field"]);
+    }
+
+    #[test]
+    fn unversioned_object_is_rejected() {
+        assert!(migrate(json!({ "tasks": [] })).is_err());
+        assert!(migrate(json!("tasks")).is_err());
+    }
+}
@@ -1 +1,129 @@
-pub mod storage_manager;
\ No newline at end of file
+pub mod export;
+pub mod json_storage;
+pub mod migrations;
+pub mod projects;
+pub mod sqlite_storage;
+pub mod storage_manager;
+pub mod views;
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Duration, Utc};
+use std::fs;
+use std::path::Path;
+
+use crate::config;
+use crate::models::workflow::Workflow;
+use crate::models::{Task, TaskPriority, TaskStatus, TrashedTask};
+use json_storage::JsonStorage;
+use sqlite_storage::SqliteStorage;
+use storage_manager::LoadedTasks;
+
+/// Persistence backend the app reads tasks from and writes changes to.
+pub trait Storage {
+    /// Directory holding this board's files.
+    fn data_dir(&self) -> &Path;
+
+    /// Loads every task, in display order, along with the trash bin.
+    fn load(&mut self) -> Result<LoadedTasks>;
+
+    /// Inserts a new task or replaces the stored task with the same id.
+    fn upsert(&mut self, task: &Task) -> Result<()>;
+
+    /// Upserts several tasks as one all-or-nothing update.
+    fn upsert_many(&mut self, tasks: &[Task]) -> Result<()>;
+
+    /// Removes a task by id; removing an unknown id is not an error.
+    fn delete(&mut self, id: &str) -> Result<()>;
+
+    /// Returns the tasks matching `query`, in display order.
+    fn query(&mut self, query: &TaskQuery) -> Result<Vec<Task>>;
+
+    /// Removes a task from the board and keeps it in the trash bin.
+    fn trash(&mut self, entry: &TrashedTask) -> Result<()>;
+
+    /// Takes a task out of the trash bin and puts it back on the board.
+    fn restore(&mut self, task: &Task) -> Result<()>;
+
+    /// Permanently deletes tasks from the trash bin.
+    fn purge(&mut self, ids: &[String]) -> Result<()>;
+
+    /// Makes several writes, in order, as one all-or-nothing update.
+    fn apply(&mut self, writes: &[Write]) -> Result<()>;
+}
+
+/// One write in a `Storage::apply` batch, named after the method that makes it alone.
+#[derive(Debug, Clone)]
+pub enum Write {
+    Upsert(Task),
+    Delete(String),
+    Trash(TrashedTask),
+    Restore(Task),
+    Purge(Vec<String>),
+}
+
+/// Days a deleted task stays in the trash, from the `trash_retention_days` setting.
+pub fn trash_retention_days() -> i64 {
+    config::current().trash_retention_days()
+}
+
+/// Purges trash entries older than the retention period and returns the ones that remain.
+pub fn purge_expired_trash(storage: &mut dyn Storage, trash: Vec<TrashedTask>) -> Result<Vec<TrashedTask>> {
+    let cutoff = Utc::now() - Duration::days(trash_retention_days());
+    let (expired, kept): (Vec<TrashedTask>, Vec<TrashedTask>) =
+        trash.into_iter().partition(|entry| entry.deleted_at < cutoff);
+
+    if !expired.is_empty() {
+        let ids: Vec<String> = expired.into_iter().map(|entry| entry.task.id).collect();
+        storage.purge(&ids)?;
+    }
+    Ok(kept)
+}
+
+/// Reads the board's `workflow.toml`. A project without one shares the workflow of the
+/// default project, and without that TaskX uses the built-in Todo/In Progress/Done workflow.
+pub fn load_workflow(data_dir: &Path) -> Result<Workflow> {
+    let mut path = data_dir.join("workflow.toml");
+    if !path.exists() {
+        path = storage_manager::get_data_dir()?.join("workflow.toml");
+    }
+    if !path.exists() {
+        return Ok(Workflow::default());
+    }
+
+    let contents = fs::read_to_string(&path)?;
+    let workflow: Workflow =
+        toml::from_str(&contents).with_context(|| format!("failed to parse {}", path.display()))?;
+    workflow
+        .validate()
+        .map_err(|err| anyhow!("invalid workflow in {}: {}", path.display(), err))?;
+    Ok(workflow)
+}
+
+/// Criteria for `Storage::query`; unset fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct TaskQuery {
+    pub status: Option<TaskStatus>,
+    pub priority: Option<TaskPriority>,
+    pub due_before: Option<DateTime<Utc>>,
+}
+
+impl TaskQuery {
+    pub fn matches(&self, task: &Task) -> bool {
+        self.status.as_ref().is_none_or(|status| &task.status == status)
+            && self.priority.is_none_or(|priority| task.priority == priority)
+            && self.due_before.is_none_or(|limit| task.due_date.is_some_and(|due| due < limit))
+    }
+}
+
+/// Opens a project's board with the backend selected by the `storage` setting
+/// (`json` or `sqlite`, default `json`).
+pub fn open(project: &str) -> Result<Box<dyn Storage>> {
+    let data_dir = projects::existing_dir(&storage_manager::get_data_dir()?, project)?;
+    let backend = config::current().storage().to_string();
+
+    match backend.as_str() {
+        "json" => Ok(Box::new(JsonStorage::new(data_dir))),
+        "sqlite" => Ok(Box::new(SqliteStorage::open(&data_dir)?)),
+        other => Err(anyhow!("unknown storage backend \"{}\" (expected json or sqlite)", other)),
+    }
+}
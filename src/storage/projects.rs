@@ -0,0 +1,89 @@
+use anyhow::{Result, anyhow};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::models::workflow::Workflow;
+use crate::models::Task;
+use crate::storage::Storage;
+
+/// The project that lives directly in the data directory, where boards were kept
+/// before there were projects.
+pub const DEFAULT_PROJECT: &str = "default";
+
+/// Directory holding a project's tasks, undo journal and optional workflow.
+pub fn project_dir(root: &Path, name: &str) -> PathBuf {
+    if name == DEFAULT_PROJECT {
+        root.to_path_buf()
+    } else {
+        root.join("projects").join(name)
+    }
+}
+
+/// Every project under `root`, the default one first and the rest by name.
+pub fn list(root: &Path) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    let dir = root.join("projects");
+    if dir.exists() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir()
+                && let Some(name) = entry.file_name().to_str()
+                && check_name(name).is_ok()
+            {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names.sort();
+    names.insert(0, DEFAULT_PROJECT.to_string());
+    Ok(names)
+}
+
+/// Project names become directory names, so they are kept to letters, digits, `-` and `_`.
+pub fn check_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Project name cannot be empty".to_string());
+    }
+    if !name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+        return Err(format!("Project name \"{}\" may only contain letters, digits, - and _", name));
+    }
+    Ok(())
+}
+
+/// Creates an empty project and returns its directory.
+pub fn create(root: &Path, name: &str) -> Result<PathBuf> {
+    check_name(name).map_err(|err| anyhow!(err))?;
+    let dir = project_dir(root, name);
+    if name == DEFAULT_PROJECT || dir.exists() {
+        return Err(anyhow!("project \"{}\" already exists", name));
+    }
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Directory of an existing project.
+pub fn existing_dir(root: &Path, name: &str) -> Result<PathBuf> {
+    check_name(name).map_err(|err| anyhow!(err))?;
+    let dir = project_dir(root, name);
+    if !dir.exists() {
+        return Err(anyhow!("no project named \"{}\"", name));
+    }
+    Ok(dir)
+}
+
+/// Moves `task` from one project's storage to another's and returns it as stored
+/// there. Links to tasks left behind (parent, prerequisites) are dropped, and a
+/// status the target workflow does not know becomes its first status. The task is
+/// written to the target before it is removed from the source, so a failure can
+/// leave a copy behind but never loses it.
+pub fn move_task(from: &mut dyn Storage, to: &mut dyn Storage, task: &Task, to_workflow: &Workflow) -> Result<Task> {
+    let mut moved = task.clone();
+    moved.parent_id = None;
+    moved.blocked_by.clear();
+    moved.status = to_workflow.resolve(task.status.as_str()).unwrap_or_else(|_| to_workflow.initial());
+    moved.record_changes(task);
+
+    to.upsert(&moved)?;
+    from.delete(&task.id)?;
+    Ok(moved)
+}
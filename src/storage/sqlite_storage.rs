@@ -0,0 +1,323 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{Connection, Transaction, params};
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+
+use crate::models::{Task, TrashedTask};
+use crate::storage::migrations;
+use crate::storage::storage_manager::{self, Envelope, LoadedTasks};
+use crate::storage::{Storage, TaskQuery, Write};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS tasks (
+        id         TEXT PRIMARY KEY,
+        position   INTEGER NOT NULL,
+        status     TEXT NOT NULL,
+        priority   INTEGER NOT NULL,
+        due_date   TEXT,
+        data       TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_tasks_status ON tasks(status);
+    CREATE INDEX IF NOT EXISTS idx_tasks_priority ON tasks(priority);
+    CREATE INDEX IF NOT EXISTS idx_tasks_due_date ON tasks(due_date);
+    CREATE TABLE IF NOT EXISTS trash (
+        id         TEXT PRIMARY KEY,
+        deleted_at TEXT NOT NULL,
+        data       TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS meta (
+        key        TEXT PRIMARY KEY,
+        value      TEXT NOT NULL
+    );
+";
+
+/// Key of the `meta` row recording when tasks.json was imported.
+const JSON_IMPORTED: &str = "json_imported";
+
+/// Stores tasks in an embedded SQLite database (tasks.db), one row per task.
+///
+/// Indexed columns are denormalized for queries; the full task lives in `data` as
+/// JSON so new fields don't need a table migration.
+pub struct SqliteStorage {
+    conn: Connection,
+    data_dir: PathBuf,
+    /// Whether tasks.json is still to be imported, which happens on the first load only.
+    import_pending: bool,
+}
+
+impl SqliteStorage {
+    pub fn open(data_dir: &Path) -> Result<Self> {
+        let path = data_dir.join("tasks.db");
+        let conn = Connection::open(&path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+        conn.execute_batch(SCHEMA)?;
+
+        // A fresh database starts at the current schema version
+        let version: u64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version == 0 {
+            conn.execute_batch(&format!("PRAGMA user_version = {}", migrations::CURRENT_VERSION))?;
+        }
+
+        let imported: i64 =
+            conn.query_row("SELECT COUNT(*) FROM meta WHERE key = ?1", params![JSON_IMPORTED], |row| row.get(0))?;
+        // Databases from before the meta table that already hold tasks had their import then
+        let rows: i64 =
+            conn.query_row("SELECT (SELECT COUNT(*) FROM tasks) + (SELECT COUNT(*) FROM trash)", [], |row| row.get(0))?;
+        if imported == 0 && rows > 0 {
+            mark_imported(&conn)?;
+        }
+
+        Ok(Self {
+            conn,
+            data_dir: data_dir.to_path_buf(),
+            import_pending: imported == 0 && rows == 0,
+        })
+    }
+
+    fn read_rows(&self, sql: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<Value>> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let rows = stmt.query_map(params, |row| row.get::<_, String>(0))?;
+
+        let mut tasks = Vec::new();
+        for data in rows {
+            tasks.push(serde_json::from_str(&data?)?);
+        }
+        Ok(tasks)
+    }
+
+    /// Seeds a new database from an existing tasks.json, if there is one. Either way
+    /// the database is marked as imported, so tasks.json is never read again.
+    fn import_json(&mut self) -> Result<Option<LoadedTasks>> {
+        if !storage_manager::get_tasks_file_path(&self.data_dir).exists() {
+            mark_imported(&self.conn)?;
+            self.import_pending = false;
+            return Ok(None);
+        }
+
+        let mut loaded = storage_manager::load_tasks(&self.data_dir)?;
+        let tx = self.conn.transaction()?;
+        for task in &loaded.tasks {
+            upsert_in(&tx, task)?;
+        }
+        for entry in &loaded.trash {
+            trash_in(&tx, entry)?;
+        }
+        mark_imported(&tx)?;
+        tx.commit()?;
+        self.import_pending = false;
+
+        let notice = format!("Imported {} tasks from tasks.json into tasks.db", loaded.tasks.len());
+        loaded.warning = Some(loaded.warning.map_or(notice.clone(), |w| format!("{}; {}", w, notice)));
+        Ok(Some(loaded))
+    }
+}
+
+fn upsert_in(tx: &Transaction, task: &Task) -> Result<()> {
+    let next_position: i64 = tx.query_row(
+        "SELECT COALESCE(MAX(position) + 1, 0) FROM tasks",
+        [],
+        |row| row.get(0),
+    )?;
+
+    // Existing tasks keep their position so the board order stays stable
+    tx.execute(
+        "INSERT INTO tasks (id, position, status, priority, due_date, data)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(id) DO UPDATE SET
+             status = excluded.status,
+             priority = excluded.priority,
+             due_date = excluded.due_date,
+             data = excluded.data",
+        params![
+            task.id,
+            next_position,
+            task.status.to_string(),
+            task.priority as i64,
+            task.due_date.map(format_timestamp),
+            serde_json::to_string(task)?,
+        ],
+    )?;
+    Ok(())
+}
+
+fn write_in(tx: &Transaction, write: &Write) -> Result<()> {
+    match write {
+        Write::Upsert(task) => upsert_in(tx, task)?,
+        Write::Delete(id) => {
+            tx.execute("DELETE FROM tasks WHERE id = ?1", params![id])?;
+        }
+        Write::Trash(entry) => {
+            tx.execute("DELETE FROM tasks WHERE id = ?1", params![entry.task.id])?;
+            trash_in(tx, entry)?;
+        }
+        Write::Restore(task) => {
+            tx.execute("DELETE FROM trash WHERE id = ?1", params![task.id])?;
+            upsert_in(tx, task)?;
+        }
+        Write::Purge(ids) => {
+            for id in ids {
+                tx.execute("DELETE FROM trash WHERE id = ?1", params![id])?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn mark_imported(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO meta (key, value) VALUES (?1, ?2)",
+        params![JSON_IMPORTED, format_timestamp(Utc::now())],
+    )?;
+    Ok(())
+}
+
+fn trash_in(tx: &Transaction, entry: &TrashedTask) -> Result<()> {
+    tx.execute(
+        "INSERT OR REPLACE INTO trash (id, deleted_at, data) VALUES (?1, ?2, ?3)",
+        params![
+            entry.task.id,
+            format_timestamp(entry.deleted_at),
+            serde_json::to_string(entry)?,
+        ],
+    )?;
+    Ok(())
+}
+
+// Fixed-width UTC timestamps compare correctly as text
+fn format_timestamp(date: DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+impl Storage for SqliteStorage {
+    fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
+    fn load(&mut self) -> Result<LoadedTasks> {
+        if self.import_pending
+            && let Some(imported) = self.import_json()?
+        {
+            return Ok(imported);
+        }
+
+        let version: u64 = self.conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        let rows = self.read_rows("SELECT data FROM tasks ORDER BY position", &[])?;
+        let trash_rows = self.read_rows("SELECT data FROM trash ORDER BY deleted_at", &[])?;
+        let (document, report) = storage_manager::decode_document(json!({
+            "version": version,
+            "tasks": rows,
+            "trash": trash_rows,
+        }))?;
+
+        // Persist upgraded rows so migrations only run once
+        if !report.applied.is_empty() {
+            let tx = self.conn.transaction()?;
+            for task in &document.tasks {
+                upsert_in(&tx, task)?;
+            }
+            for entry in &document.trash {
+                trash_in(&tx, entry)?;
+            }
+            tx.execute_batch(&format!("PRAGMA user_version = {}", migrations::CURRENT_VERSION))?;
+            tx.commit()?;
+        }
+
+        Ok(LoadedTasks {
+            tasks: document.tasks,
+            trash: document.trash,
+            warning: None,
+            migrations: report,
+            envelope: Envelope::default(),
+        })
+    }
+
+    fn upsert(&mut self, task: &Task) -> Result<()> {
+        self.apply(&[Write::Upsert(task.clone())])
+    }
+
+    fn upsert_many(&mut self, tasks: &[Task]) -> Result<()> {
+        let writes: Vec<Write> = tasks.iter().cloned().map(Write::Upsert).collect();
+        self.apply(&writes)
+    }
+
+    fn delete(&mut self, id: &str) -> Result<()> {
+        self.apply(&[Write::Delete(id.to_string())])
+    }
+
+    fn query(&mut self, query: &TaskQuery) -> Result<Vec<Task>> {
+        // Rows are filtered in SQL, so they must be imported and upgraded first
+        let version: u64 = self.conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version < migrations::CURRENT_VERSION || self.import_pending {
+            self.load()?;
+        }
+
+        let status = query.status.as_ref().map(|s| s.to_string());
+        let priority = query.priority.map(|p| p as i64);
+        let due_before = query.due_before.map(format_timestamp);
+
+        let rows = self.read_rows(
+            "SELECT data FROM tasks
+             WHERE (?1 IS NULL OR status = ?1)
+               AND (?2 IS NULL OR priority = ?2)
+               AND (?3 IS NULL OR (due_date IS NOT NULL AND due_date < ?3))
+             ORDER BY position",
+            &[&status, &priority, &due_before],
+        )?;
+
+        let (document, _) = storage_manager::decode_document(json!({
+            "version": migrations::CURRENT_VERSION,
+            "tasks": rows,
+        }))?;
+        Ok(document.tasks)
+    }
+
+    fn trash(&mut self, entry: &TrashedTask) -> Result<()> {
+        self.apply(&[Write::Trash(entry.clone())])
+    }
+
+    fn restore(&mut self, task: &Task) -> Result<()> {
+        self.apply(&[Write::Restore(task.clone())])
+    }
+
+    fn purge(&mut self, ids: &[String]) -> Result<()> {
+        self.apply(&[Write::Purge(ids.to_vec())])
+    }
+
+    fn apply(&mut self, writes: &[Write]) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        for write in writes {
+            write_in(&tx, write)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TaskPriority, TaskStatus};
+
+    #[test]
+    fn tasks_json_is_imported_once() {
+        let dir = std::env::temp_dir().join(format!("taskx-sqlite-import-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let task = Task::new("Imported", "", TaskStatus::new("Todo"), TaskPriority::Medium);
+        storage_manager::save_tasks(&dir, std::slice::from_ref(&task), &[], &Envelope::default()).unwrap();
+
+        let mut storage = SqliteStorage::open(&dir).unwrap();
+        assert_eq!(storage.load().unwrap().tasks.len(), 1);
+        storage.trash(&TrashedTask { task: task.clone(), deleted_at: Utc::now() }).unwrap();
+
+        // An empty board is not a reason to import again, now or after reopening
+        let loaded = storage.load().unwrap();
+        assert!(loaded.tasks.is_empty());
+        assert_eq!(loaded.trash.len(), 1);
+        let mut reopened = SqliteStorage::open(&dir).unwrap();
+        assert!(reopened.query(&TaskQuery::default()).unwrap().is_empty());
+        assert!(reopened.load().unwrap().tasks.is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
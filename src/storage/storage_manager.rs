@@ -1,53 +1,257 @@
-use anyhow::Result;
-use serde_json;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::{self, Map, Value};
 use std::fs::{self, File};
 use std::io::prelude::*;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use directories::ProjectDirs;
 
-use crate::models::Task;
+use crate::config;
+use crate::models::{Task, TrashedTask};
+use crate::storage::migrations::{self, MigrationReport};
 
-// TODO: move to config
+/// Tasks read from disk, plus a warning when they had to be recovered from a backup.
+pub struct LoadedTasks {
+    pub tasks: Vec<Task>,
+    pub trash: Vec<TrashedTask>,
+    pub warning: Option<String>,
+    pub migrations: MigrationReport,
+    pub envelope: Envelope,
+}
+
+/// On-disk layout of tasks.json once it has been migrated to the current schema.
+#[derive(Deserialize)]
+pub struct TaskDocument {
+    pub version: u64,
+    pub tasks: Vec<Task>,
+    #[serde(default)]
+    pub trash: Vec<TrashedTask>,
+    /// Fields a newer TaskX added next to the tasks.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl TaskDocument {
+    fn into_loaded(self, warning: Option<String>, migrations: MigrationReport) -> LoadedTasks {
+        let envelope = Envelope { version: self.version, extra: self.extra };
+        LoadedTasks { tasks: self.tasks, trash: self.trash, warning, migrations, envelope }
+    }
+}
+
+/// What tasks.json holds besides the tasks, written back on save. A file from a
+/// newer TaskX keeps its schema version and the fields this build does not know.
+#[derive(Debug, Clone, Default)]
+pub struct Envelope {
+    pub version: u64,
+    pub extra: Map<String, Value>,
+}
+
+/// Name of the directory that holds a board kept next to the files it belongs to.
+pub const LOCAL_BOARD_DIR: &str = ".taskx";
 
-fn get_data_dir() -> Result<PathBuf> {
+/// Where boards live: the nearest `.taskx/` directory from the working directory up,
+/// like git finds its repository, or the global data directory when there is none.
+pub fn get_data_dir() -> Result<PathBuf> {
+    if let Some(board) = find_local_board() {
+        return Ok(board);
+    }
+    get_global_data_dir()
+}
+
+/// The directory of the global board: the `data_dir` setting, or the platform's
+/// data directory.
+pub fn get_global_data_dir() -> Result<PathBuf> {
+    let data_dir = match config::current().data_dir() {
+        Some(dir) => dir,
+        None => platform_data_dir()?,
+    };
+    if !data_dir.exists() {
+        fs::create_dir_all(&data_dir)?;
+    }
+
+    Ok(data_dir)
+}
+
+/// The per-user data directory the platform suggests, such as `~/.local/share/task_manager`.
+pub fn platform_data_dir() -> Result<PathBuf> {
     let proj_dirs = ProjectDirs::from("com", "task_manager", "task_manager")
         .ok_or_else(|| anyhow::anyhow!("Could not determine project directories"))?;
+    Ok(proj_dirs.data_dir().to_path_buf())
+}
 
-    let data_dir = proj_dirs.data_dir();
-    if !data_dir.exists() {
-        fs::create_dir_all(data_dir)?;
+/// The nearest `.taskx/` directory in the working directory or one of its parents.
+pub fn find_local_board() -> Option<PathBuf> {
+    let cwd = std::env::current_dir().ok()?;
+    cwd.ancestors()
+        .map(|dir| dir.join(LOCAL_BOARD_DIR))
+        .find(|board| board.is_dir())
+}
+
+/// Creates a `.taskx/` board in `dir`. Backups, the undo journal and prompt histories
+/// are personal, so the board ignores them for git.
+pub fn init_local_board(dir: &Path) -> Result<PathBuf> {
+    let board = dir.join(LOCAL_BOARD_DIR);
+    if board.exists() {
+        return Err(anyhow::anyhow!("{} already exists", board.display()));
     }
+    fs::create_dir_all(&board)?;
+    write_atomic(&board.join(".gitignore"), b"backups/\njournal.json\nsearch_history.json\ncommand_history.json\n")?;
+    Ok(board)
+}
 
-    Ok(data_dir.to_path_buf())
+pub fn get_tasks_file_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("tasks.json")
 }
 
-fn get_tasks_file_path() -> Result<PathBuf> {
-    let data_dir = get_data_dir()?;
-    Ok(data_dir.join("tasks.json"))
+fn get_backup_dir(data_dir: &Path) -> Result<PathBuf> {
+    let backup_dir = data_dir.join("backups");
+    if !backup_dir.exists() {
+        fs::create_dir_all(&backup_dir)?;
+    }
+    Ok(backup_dir)
 }
 
-pub fn save_tasks(tasks: &[Task]) -> Result<()> {
-    let file_path = get_tasks_file_path()?;
-    let json = serde_json::to_string_pretty(tasks)?;
+pub fn save_tasks(data_dir: &Path, tasks: &[Task], trash: &[TrashedTask], envelope: &Envelope) -> Result<()> {
+    let file_path = get_tasks_file_path(data_dir);
+    let mut document = envelope.extra.clone();
+    document.insert("version".to_string(), envelope.version.max(migrations::CURRENT_VERSION).into());
+    document.insert("tasks".to_string(), serde_json::to_value(tasks)?);
+    document.insert("trash".to_string(), serde_json::to_value(trash)?);
+    let json = serde_json::to_string_pretty(&document)?;
 
-    let mut file = File::create(file_path)?;
-    file.write_all(json.as_bytes())?;
+    if file_path.exists() {
+        backup_tasks_file(data_dir, &file_path)?;
+    }
+    write_atomic(&file_path, json.as_bytes())?;
 
     Ok(())
 }
 
-pub fn load_tasks() -> Result<Vec<Task>> {
-    let file_path = get_tasks_file_path()?;
+pub fn load_tasks(data_dir: &Path) -> Result<LoadedTasks> {
+    let file_path = get_tasks_file_path(data_dir);
 
     // If file doesn't exist, return empty vector
     if !file_path.exists() {
-        return Ok(Vec::new());
+        return Ok(LoadedTasks {
+            tasks: Vec::new(),
+            trash: Vec::new(),
+            warning: None,
+            migrations: MigrationReport::default(),
+            envelope: Envelope::default(),
+        });
+    }
+
+    let error = match read_tasks_file(&file_path) {
+        Ok((document, migrations)) => {
+            return Ok(document.into_loaded(None, migrations));
+        }
+        Err(err) => err,
+    };
+
+    // The primary file is unreadable: fall back to the newest backup that parses
+    for backup in list_backups(data_dir)?.iter().rev() {
+        if let Ok((document, migrations)) = read_tasks_file(backup) {
+            let corrupt_path = quarantine_corrupt_file(&file_path)?;
+            write_atomic(&file_path, &fs::read(backup)?)?;
+            let warning = format!(
+                "tasks.json was unreadable ({:#}); restored {} tasks from backup {} (corrupt file kept as {})",
+                error,
+                document.tasks.len(),
+                file_name(backup),
+                file_name(&corrupt_path),
+            );
+            return Ok(document.into_loaded(Some(warning), migrations));
+        }
     }
 
-    let mut file = File::open(file_path)?;
+    Err(error.context("tasks.json is corrupt and no valid backup was found"))
+}
+
+fn read_tasks_file(path: &Path) -> Result<(TaskDocument, MigrationReport)> {
+    let mut file = File::open(path)?;
     let mut contents = String::new();
     file.read_to_string(&mut contents)?;
 
-    let tasks: Vec<Task> = serde_json::from_str(&contents)?;
-    Ok(tasks)
-}
\ No newline at end of file
+    let raw: serde_json::Value = serde_json::from_str(&contents)
+        .with_context(|| format!("failed to parse {}", path.display()))?;
+    decode_document(raw).with_context(|| format!("failed to read tasks from {}", path.display()))
+}
+
+/// Migrates a raw task document to the current schema and deserializes it.
+pub fn decode_document(raw: serde_json::Value) -> Result<(TaskDocument, MigrationReport)> {
+    let (document, report) = migrations::migrate(raw)?;
+    let document: TaskDocument = serde_json::from_value(document)?;
+    Ok((document, report))
+}
+
+/// Writes `contents` to a temporary sibling file, syncs it and renames it over `path`,
+/// so a crash mid-write never leaves a truncated file behind.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    // Named after the whole file and this process, so the board and a CLI command
+    // writing at once never share a temporary file
+    let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or("file");
+    let tmp_path = dir.join(format!("{}.{}.tmp", file_name, std::process::id()));
+
+    {
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+    }
+    fs::rename(&tmp_path, path)?;
+
+    // Persist the rename itself; not every platform lets us open a directory
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+
+    Ok(())
+}
+
+/// Copies the current tasks file into the backup directory and prunes old backups.
+fn backup_tasks_file(data_dir: &Path, file_path: &Path) -> Result<()> {
+    let backup_dir = get_backup_dir(data_dir)?;
+    let timestamp = Utc::now().format("%Y%m%dT%H%M%S%.3f");
+    let backup_path = backup_dir.join(format!("tasks-{}.json", timestamp));
+    fs::copy(file_path, &backup_path)?;
+
+    let backups = list_backups(data_dir)?;
+    let max_backups = config::current().max_backups();
+    if backups.len() > max_backups {
+        for old in &backups[..backups.len() - max_backups] {
+            fs::remove_file(old)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns backup files sorted from oldest to newest.
+fn list_backups(data_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut backups: Vec<PathBuf> = fs::read_dir(get_backup_dir(data_dir)?)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            let name = file_name(path);
+            name.starts_with("tasks-") && name.ends_with(".json")
+        })
+        .collect();
+
+    // Timestamps are zero-padded, so lexical order is chronological
+    backups.sort();
+    Ok(backups)
+}
+
+/// Keeps a copy of an unreadable tasks file so restoring a backup doesn't destroy it.
+fn quarantine_corrupt_file(file_path: &Path) -> Result<PathBuf> {
+    let timestamp = Utc::now().format("%Y%m%dT%H%M%S");
+    let corrupt_path = file_path.with_extension(format!("json.corrupt-{}", timestamp));
+    fs::copy(file_path, &corrupt_path)?;
+    Ok(corrupt_path)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
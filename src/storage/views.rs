@@ -0,0 +1,96 @@
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::models::filter::Filter;
+use crate::storage::storage_manager;
+
+/// A named task query, kept in the project's `views.toml` so it can be reused
+/// from the CLI and, when pinned, shown as a tab of its own.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedView {
+    pub name: String,
+    pub query: String,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+impl SavedView {
+    pub fn filter(&self) -> Result<Filter> {
+        self.query
+            .parse()
+            .map_err(|err| anyhow!("view \"{}\": {}", self.name, err))
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SavedViews {
+    #[serde(default, rename = "view")]
+    pub views: Vec<SavedView>,
+}
+
+impl SavedViews {
+    pub fn load(data_dir: &Path) -> Result<Self> {
+        let path = data_dir.join("views.toml");
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(&path)?;
+        toml::from_str(&contents).with_context(|| format!("failed to parse {}", path.display()))
+    }
+
+    pub fn save(&self, data_dir: &Path) -> Result<()> {
+        let contents = toml::to_string_pretty(self)?;
+        storage_manager::write_atomic(&data_dir.join("views.toml"), contents.as_bytes())
+    }
+
+    pub fn get(&self, name: &str) -> Result<&SavedView> {
+        self.views
+            .iter()
+            .find(|view| view.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| anyhow!("no view named \"{}\"", name))
+    }
+
+    /// Adds a view, or replaces the query of the one with the same name.
+    pub fn save_view(&mut self, name: &str, query: &str, pinned: bool) -> Result<()> {
+        if name.trim().is_empty() {
+            return Err(anyhow!("view name cannot be empty"));
+        }
+        query.parse::<Filter>().map_err(|err| anyhow!(err))?;
+
+        match self.views.iter_mut().find(|view| view.name.eq_ignore_ascii_case(name)) {
+            Some(view) => {
+                view.query = query.to_string();
+                view.pinned |= pinned;
+            }
+            None => self.views.push(SavedView { name: name.trim().to_string(), query: query.to_string(), pinned }),
+        }
+        Ok(())
+    }
+
+    pub fn set_pinned(&mut self, name: &str, pinned: bool) -> Result<()> {
+        let view = self
+            .views
+            .iter_mut()
+            .find(|view| view.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| anyhow!("no view named \"{}\"", name))?;
+        view.pinned = pinned;
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> Result<SavedView> {
+        let index = self
+            .views
+            .iter()
+            .position(|view| view.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| anyhow!("no view named \"{}\"", name))?;
+        Ok(self.views.remove(index))
+    }
+
+    /// Indexes of the views shown as tabs, in the order they were saved.
+    pub fn pinned(&self) -> Vec<usize> {
+        (0..self.views.len()).filter(|&i| self.views[i].pinned).collect()
+    }
+}
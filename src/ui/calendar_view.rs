@@ -0,0 +1,221 @@
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::app::calendar::{self, CalendarView};
+use crate::app::App;
+use crate::models::keymap::{Action, KeyMode};
+use crate::models::Task;
+use crate::ui::help;
+use crate::utils;
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+pub fn render(frame: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),     // Calendar
+            Constraint::Length(1),  // Key hints
+        ])
+        .split(area);
+
+    match app.calendar_view {
+        CalendarView::Month => render_month(frame, app, chunks[0]),
+        CalendarView::Week => render_week(frame, app, chunks[0]),
+    }
+
+    render_hints(frame, app, chunks[1]);
+}
+
+fn render_month(frame: &mut Frame, app: &App, area: Rect) {
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(32)])
+        .split(area);
+
+    let block = Block::default()
+        .title(format!(" {} ", app.calendar_cursor.format("%B %Y")))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.accent));
+    let grid_area = block.inner(columns[0]);
+    frame.render_widget(block, columns[0]);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Ratio(1, 6),
+            Constraint::Ratio(1, 6),
+            Constraint::Ratio(1, 6),
+            Constraint::Ratio(1, 6),
+            Constraint::Ratio(1, 6),
+            Constraint::Ratio(1, 6),
+        ])
+        .split(grid_area);
+
+    for (i, cell) in split_week(rows[0]).iter().enumerate() {
+        let header = Paragraph::new(Span::styled(
+            WEEKDAYS[i],
+            Style::default().fg(app.theme.text).add_modifier(Modifier::BOLD),
+        ));
+        frame.render_widget(header, *cell);
+    }
+
+    let start = app.calendar_month_start();
+    for week in 0..6 {
+        for (weekday, cell) in split_week(rows[week + 1]).iter().enumerate() {
+            let date = start + Duration::days((week * 7 + weekday) as i64);
+            render_day(frame, app, date, *cell, false);
+        }
+    }
+
+    render_agenda(frame, app, columns[1]);
+}
+
+fn render_week(frame: &mut Frame, app: &App, area: Rect) {
+    let start = calendar::week_start(app.calendar_cursor);
+    let end = start + Duration::days(6);
+
+    let block = Block::default()
+        .title(format!(" Week of {} – {} ", start.format("%b %-d"), end.format("%b %-d, %Y")))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.accent));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    for (weekday, cell) in split_week(inner).iter().enumerate() {
+        render_day(frame, app, start + Duration::days(weekday as i64), *cell, true);
+    }
+}
+
+/// One day cell; `detailed` cells also show the priority of each task.
+fn render_day(frame: &mut Frame, app: &App, date: NaiveDate, area: Rect, detailed: bool) {
+    let today = Local::now().date_naive();
+    let is_cursor = date == app.calendar_cursor;
+    let in_month = date.month() == app.calendar_cursor.month();
+    let tasks = app.tasks_due_on(date);
+
+    let title = if detailed {
+        format!(" {} {} ", WEEKDAYS[date.weekday().num_days_from_monday() as usize], date.day())
+    } else {
+        format!(" {} ", date.day())
+    };
+
+    let mut title_style = Style::default().fg(if in_month || detailed { app.theme.text } else { app.theme.faint });
+    if date == today {
+        title_style = title_style.fg(app.theme.accent).add_modifier(Modifier::BOLD);
+    }
+
+    let border_style = if is_cursor {
+        Style::default().fg(app.theme.highlight)
+    } else if tasks.iter().any(|task| task.is_overdue(&app.workflow)) {
+        Style::default().fg(app.theme.danger)
+    } else {
+        Style::default().fg(app.theme.border)
+    };
+
+    let block = Block::default()
+        .title(Span::styled(title, title_style))
+        .borders(Borders::ALL)
+        .border_style(border_style);
+    let inner = block.inner(area);
+    let width = inner.width as usize;
+
+    let mut lines: Vec<Line> = tasks
+        .iter()
+        .enumerate()
+        .map(|(i, task)| {
+            let selected = is_cursor && i == app.calendar_task_index;
+            task_line(app, task, width, selected, detailed)
+        })
+        .collect();
+
+    // Leave room for a "+N more" line when the cell is too short
+    let capacity = inner.height as usize;
+    if lines.len() > capacity && capacity > 0 {
+        let hidden = lines.len() - (capacity - 1);
+        lines.truncate(capacity - 1);
+        lines.push(Line::from(Span::styled(
+            format!("+{} more", hidden),
+            Style::default().fg(app.theme.muted),
+        )));
+    }
+
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Tasks due on the cursor day, beside the month grid.
+fn render_agenda(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default()
+        .title(format!(" {} ", app.calendar_cursor.format("%a %b %-d")))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.accent));
+    let width = block.inner(area).width as usize;
+
+    let tasks = app.tasks_due_on(app.calendar_cursor);
+    let lines: Vec<Line> = if tasks.is_empty() {
+        vec![Line::from(Span::styled("Nothing due", Style::default().fg(app.theme.muted)))]
+    } else {
+        tasks
+            .iter()
+            .enumerate()
+            .map(|(i, task)| task_line(app, task, width, i == app.calendar_task_index, true))
+            .collect()
+    };
+
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn task_line(app: &App, task: &Task, width: usize, selected: bool, detailed: bool) -> Line<'static> {
+    let priority_color = app.theme.priority(task.priority);
+    let mut spans = Vec::new();
+
+    let mut used = 0;
+    if task.is_overdue(&app.workflow) {
+        spans.push(Span::styled("!", Style::default().fg(app.theme.badge_text).bg(app.theme.danger).add_modifier(Modifier::BOLD)));
+        used += 1;
+    }
+    if detailed {
+        spans.push(Span::styled("● ", Style::default().fg(priority_color)));
+        used += 2;
+    }
+
+    let mut title_style = Style::default().fg(if detailed { app.theme.text } else { priority_color });
+    if selected {
+        title_style = title_style.bg(app.theme.selection).add_modifier(Modifier::BOLD);
+    }
+    spans.push(Span::styled(
+        utils::truncate_string(&task.title, width.saturating_sub(used)),
+        title_style,
+    ));
+
+    Line::from(spans)
+}
+
+fn split_week(area: Rect) -> std::rc::Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, 7); 7])
+        .split(area)
+}
+
+fn render_hints(frame: &mut Frame, app: &App, area: Rect) {
+    let mut spans = vec![Span::raw(" ")];
+    spans.extend(help::hint_spans(app, KeyMode::Calendar, &[
+        (&[Action::Left, Action::Right], " day  "),
+        (&[Action::Down, Action::Up], " week  "),
+        (&[Action::PreviousMonth, Action::NextMonth], " month  "),
+        (&[Action::Today], " today  "),
+        (&[Action::ToggleWeek], " month/week  "),
+        (&[Action::NextTask, Action::PreviousTask], " cycle tasks  "),
+        (&[Action::Open], " open  "),
+        (&[Action::Earlier, Action::Later], " reschedule"),
+    ]));
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
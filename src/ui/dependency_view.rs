@@ -0,0 +1,99 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+use crate::models::dependencies::{self, Direction as Flow, GraphLine};
+use crate::models::keymap::{Action, KeyMode};
+use crate::models::Task;
+use crate::ui::help;
+use crate::ui::task_detail::centered_rect;
+
+/// Popup with what the task waits on above and what waits on it below.
+pub fn render(frame: &mut Frame, app: &App, task: &Task) {
+    let popup_area = centered_rect(70, 70, frame.size());
+    super::clear(frame, app, popup_area);
+
+    let block = Block::default()
+        .title(format!(" Dependencies of \"{}\" ", task.title))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.accent));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Percentage(50), // Waits on
+            Constraint::Min(0),         // Blocks
+            Constraint::Length(1),      // Footer
+        ])
+        .split(inner);
+
+    let upstream = dependencies::graph_lines(&app.tasks, task, Flow::Upstream);
+    let downstream = dependencies::graph_lines(&app.tasks, task, Flow::Downstream);
+    render_tree(frame, app, " Waits on ", &upstream, chunks[0]);
+    render_tree(frame, app, " Blocks ", &downstream, chunks[1]);
+
+    let mut spans = vec![
+        Span::styled("✓", Style::default().fg(app.theme.success)),
+        Span::styled(" done  ", Style::default().fg(app.theme.muted)),
+        Span::styled("(*)", Style::default().fg(app.theme.muted)),
+        Span::styled(" shown above  ", Style::default().fg(app.theme.muted)),
+        Span::styled("Esc", Style::default().fg(app.theme.key)),
+    ];
+    // The key that opened the graph closes it too, if it is still bound
+    let graph = help::hint_spans(app, KeyMode::Details, &[(&[Action::Graph], " to close")]);
+    if graph.is_empty() {
+        spans.push(Span::styled(" to close", Style::default().fg(app.theme.muted)));
+    } else {
+        spans.push(Span::styled(" or ", Style::default().fg(app.theme.muted)));
+        spans.extend(graph);
+    }
+    let footer = Paragraph::new(Line::from(spans));
+    frame.render_widget(footer, chunks[2]);
+}
+
+fn render_tree(frame: &mut Frame, app: &App, title: &str, graph: &[GraphLine], area: ratatui::layout::Rect) {
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.border));
+
+    // The root line is the task itself; a lone root means there is nothing to draw
+    let lines: Vec<Line> = if graph.len() <= 1 {
+        vec![Line::from(Span::styled("Nothing", Style::default().fg(app.theme.faint)))]
+    } else {
+        graph.iter().map(|line| graph_line(app, line)).collect()
+    };
+
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn graph_line<'a>(app: &App, line: &'a GraphLine) -> Line<'a> {
+    let task = line.task;
+    let done = app.workflow.is_done(&task.status);
+    let status_color = app.theme.status(&app.workflow, task.status.as_str());
+
+    let mut title_style = Style::default().fg(if done { app.theme.muted } else { app.theme.text });
+    if line.prefix.is_empty() {
+        title_style = title_style.add_modifier(Modifier::BOLD);
+    }
+
+    let mut spans = vec![
+        Span::styled(line.prefix.clone(), Style::default().fg(app.theme.faint)),
+        Span::styled(if done { "✓ " } else { "○ " }, Style::default().fg(if done { app.theme.success } else { status_color })),
+        Span::styled(task.title.as_str(), title_style),
+        Span::styled(format!("  {}", task.status), Style::default().fg(status_color)),
+        Span::styled(format!("  {}", task.id.chars().take(8).collect::<String>()), Style::default().fg(app.theme.faint)),
+    ];
+    if line.repeated {
+        spans.push(Span::styled(" (*)", Style::default().fg(app.theme.muted)));
+    }
+    Line::from(spans)
+}
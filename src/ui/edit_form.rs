@@ -0,0 +1,118 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+use strum::IntoEnumIterator;
+
+use crate::app::edit_form::{EditForm, FormField};
+use crate::app::App;
+use crate::ui::task_detail::centered_rect;
+
+pub fn render(frame: &mut Frame, app: &App, form: &EditForm) {
+    let popup_area = centered_rect(60, 60, frame.size());
+
+    // Clear the area where the popup will be rendered
+    super::clear(frame, app, popup_area);
+
+    let block = Block::default()
+        .title(format!(" Edit Task ({}) ", form.task_id.chars().take(8).collect::<String>()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.label));
+
+    let inner_area = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Min(0),     // Fields
+            Constraint::Length(1),  // Error
+            Constraint::Length(1),  // Footer
+        ])
+        .split(inner_area);
+
+    // Space the fields out when the popup is tall enough
+    let spaced = chunks[0].height as usize >= FormField::iter().count() * 2;
+    let mut lines = Vec::new();
+    for field in FormField::iter() {
+        let focused = field == form.focused;
+        let label_style = if focused {
+            Style::default().fg(app.theme.accent).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(app.theme.label)
+        };
+
+        let value = match field {
+            FormField::Title => form.title.clone(),
+            FormField::Description => form.description.clone(),
+            FormField::Status => format!("◀ {} ▶", form.status),
+            FormField::Priority => format!("◀ {} ▶", form.priority),
+            FormField::Points => form.points.clone(),
+            FormField::Estimate => form.estimate.clone(),
+            FormField::DueDate => form.due_date.clone(),
+            FormField::Tags => form.tags.clone(),
+            FormField::Parent => form.parent.clone(),
+            FormField::BlockedBy => form.blocked_by.clone(),
+            FormField::Repeat => form.repeat.clone(),
+        };
+
+        let mut spans = vec![
+            Span::styled(if focused { "› " } else { "  " }, label_style),
+            Span::styled(format!("{:<13}", format!("{}:", field)), label_style),
+            Span::styled(value, Style::default().fg(app.theme.text)),
+        ];
+        if focused && !field.is_picker() {
+            spans.push(Span::styled("▏", Style::default().fg(app.theme.accent)));
+        }
+        if field == FormField::Estimate && form.estimate.is_empty() {
+            spans.push(Span::styled(" e.g. 90m, 2h or 1h30m", Style::default().fg(app.theme.faint)));
+        }
+        if field == FormField::DueDate && form.due_date.is_empty() {
+            spans.push(Span::styled(" YYYY-MM-DD", Style::default().fg(app.theme.faint)));
+        }
+        if field == FormField::Tags && form.tags.is_empty() {
+            spans.push(Span::styled(" comma separated", Style::default().fg(app.theme.faint)));
+        }
+        if field == FormField::Parent && form.parent.is_empty() {
+            spans.push(Span::styled(" task id, empty for none", Style::default().fg(app.theme.faint)));
+        }
+        if field == FormField::BlockedBy && form.blocked_by.is_empty() {
+            spans.push(Span::styled(" task ids, comma separated", Style::default().fg(app.theme.faint)));
+        }
+        if field == FormField::Repeat && form.repeat.is_empty() {
+            spans.push(Span::styled(" e.g. weekly mon,thu", Style::default().fg(app.theme.faint)));
+        }
+
+        lines.push(Line::from(spans));
+        if spaced {
+            lines.push(Line::from(""));
+        }
+    }
+
+    let fields = Paragraph::new(Text::from(lines)).wrap(Wrap { trim: false });
+    frame.render_widget(fields, chunks[0]);
+
+    if let Some(error) = &form.error {
+        let error_line = Paragraph::new(Line::from(Span::styled(
+            error.as_str(),
+            Style::default().fg(app.theme.danger).add_modifier(Modifier::BOLD),
+        )));
+        frame.render_widget(error_line, chunks[1]);
+    }
+
+    let footer = Paragraph::new(Line::from(vec![
+        Span::styled("Tab", Style::default().fg(app.theme.key)),
+        Span::styled(" next field  ", Style::default().fg(app.theme.muted)),
+        Span::styled("←/→", Style::default().fg(app.theme.key)),
+        Span::styled(" change  ", Style::default().fg(app.theme.muted)),
+        Span::styled("Enter", Style::default().fg(app.theme.key)),
+        Span::styled(" save  ", Style::default().fg(app.theme.muted)),
+        Span::styled("Esc", Style::default().fg(app.theme.key)),
+        Span::styled(" cancel", Style::default().fg(app.theme.muted)),
+    ]));
+    frame.render_widget(footer, chunks[2]);
+}
@@ -0,0 +1,127 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::finder::TaskFinder;
+use crate::app::App;
+use crate::models::Task;
+use crate::ui::task_detail::centered_rect;
+use crate::utils;
+
+/// Fuzzy task finder: the query, ranked matches, and a preview of the highlighted task.
+pub fn render(frame: &mut Frame, app: &App, finder: &TaskFinder) {
+    let popup_area = centered_rect(80, 70, frame.size());
+    super::clear(frame, app, popup_area);
+
+    let block = Block::default()
+        .title(format!(" Find task ({} of {}) ", finder.results.len(), app.tasks.len()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.accent));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(2),  // Query
+            Constraint::Min(0),     // Results and preview
+            Constraint::Length(1),  // Footer
+        ])
+        .split(inner);
+
+    let prompt = Paragraph::new(Line::from(vec![
+        Span::styled("> ", Style::default().fg(app.theme.label)),
+        Span::styled(finder.query.as_str(), Style::default().fg(app.theme.text)),
+        Span::styled("█", Style::default().fg(app.theme.faint)),
+    ]));
+    frame.render_widget(prompt, rows[0]);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[1]);
+
+    let lines: Vec<Line> = finder
+        .results
+        .iter()
+        .enumerate()
+        .filter_map(|(i, id)| app.tasks.iter().find(|task| &task.id == id).map(|task| (i, task)))
+        .map(|(i, task)| {
+            let mut style = Style::default().fg(app.theme.text);
+            if i == finder.index {
+                style = style.bg(app.theme.selection).add_modifier(Modifier::BOLD);
+            }
+            let width = columns[0].width.saturating_sub(10) as usize;
+            Line::from(vec![
+                Span::styled(format!("{} ", &task.id[..8.min(task.id.len())]), Style::default().fg(app.theme.faint)),
+                Span::styled(utils::truncate_string(&task.title, width), style),
+            ])
+        })
+        .collect();
+
+    // Keep the highlighted task on screen in long lists
+    let visible = columns[0].height as usize;
+    let scroll = (finder.index + 1).saturating_sub(visible) as u16;
+    let results = if lines.is_empty() {
+        Paragraph::new(Span::styled("No matching tasks", Style::default().fg(app.theme.faint)))
+    } else {
+        Paragraph::new(lines).scroll((scroll, 0))
+    };
+    frame.render_widget(results, columns[0]);
+
+    if let Some(task) = app.finder_task() {
+        let preview = Paragraph::new(preview_lines(app, task))
+            .block(Block::default().borders(Borders::LEFT).border_style(Style::default().fg(app.theme.border)))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(preview, columns[1]);
+    }
+
+    let footer = Paragraph::new(Line::from(vec![
+        Span::styled("↑↓", Style::default().fg(app.theme.key)),
+        Span::styled(" select  ", Style::default().fg(app.theme.muted)),
+        Span::styled("Enter", Style::default().fg(app.theme.key)),
+        Span::styled(" go to task  ", Style::default().fg(app.theme.muted)),
+        Span::styled("Esc", Style::default().fg(app.theme.key)),
+        Span::styled(" close", Style::default().fg(app.theme.muted)),
+    ]));
+    frame.render_widget(footer, rows[2]);
+}
+
+fn preview_lines<'a>(app: &'a App, task: &'a Task) -> Vec<Line<'a>> {
+    let label = Style::default().fg(app.theme.muted);
+    let mut lines = vec![
+        Line::from(Span::styled(task.title.as_str(), Style::default().fg(app.theme.text).add_modifier(Modifier::BOLD))),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Status: ", label),
+            Span::styled(task.status.to_string(), Style::default().fg(app.theme.status(&app.workflow, task.status.as_str()))),
+            Span::styled("  Priority: ", label),
+            Span::styled(task.priority.to_string(), Style::default().fg(app.theme.priority(task.priority))),
+        ]),
+    ];
+    if let Some(due) = task.due_date {
+        lines.push(Line::from(vec![
+            Span::styled("Due: ", label),
+            Span::raw(utils::format_date(due)),
+        ]));
+    }
+    if !task.tags.is_empty() {
+        lines.push(Line::from(vec![Span::styled("Tags: ", label), Span::raw(task.tags.join(", "))]));
+    }
+    if let Some((done, total)) = app.progress(task) {
+        lines.push(Line::from(vec![Span::styled("Progress: ", label), Span::raw(format!("{}/{}", done, total))]));
+    }
+    if app.is_blocked(task) {
+        lines.push(Line::from(Span::styled("Blocked", Style::default().fg(app.theme.danger))));
+    }
+    if !task.description.is_empty() {
+        lines.push(Line::from(""));
+        lines.extend(task.description.lines().map(Line::from));
+    }
+    lines
+}
@@ -1,112 +1,144 @@
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span, Text},
-    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    widgets::{Block, Borders, Padding, Paragraph, Wrap},
     Frame,
 };
 
+use strum::IntoEnumIterator;
+
 use crate::app::App;
+use crate::models::keymap::{Action, KeyMode};
 
-pub fn render(frame: &mut Frame, _app: &App) {
+/// Help built from the active keymap: the board keys on the left, the keys of
+/// tabs, popups and prompts on the right.
+pub fn render(frame: &mut Frame, app: &App) {
     let size = frame.size();
     let popup_area = centered_rect(80, 80, size);
 
     // Clear the area where the popup will be rendered
-    frame.render_widget(Clear, popup_area);
+    super::clear(frame, app, popup_area);
 
     // Create help popup
     let block = Block::default()
         .title(" Help ")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Yellow));
-
-    // Create the help text with different sections
-    let mut text_spans = Vec::new();
-
-    // Title
-    text_spans.push(
-        Line::from(Span::styled(
-            "TaskX - Terminal Task Manager",
-            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
-        ))
-    );
-    text_spans.push(Line::from(""));
-
-    // Navigation section
-    text_spans.push(
-        Line::from(Span::styled(
-            "Navigation:",
-            Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
-        ))
-    );
-    add_help_item(&mut text_spans, "↑/k", "Move selection up");
-    add_help_item(&mut text_spans, "↓/j", "Move selection down");
-    add_help_item(&mut text_spans, "←/h", "Previous column (Kanban view)");
-    add_help_item(&mut text_spans, "→/l", "Next column (Kanban view)");
-    add_help_item(&mut text_spans, "Enter", "Toggle task details");
-    add_help_item(&mut text_spans, "Tab", "Next tab");
-    add_help_item(&mut text_spans, "Shift+Tab", "Previous tab");
-    text_spans.push(Line::from(""));
-
-    // Task management section
-    text_spans.push(
-        Line::from(Span::styled(
-            "Task Management:",
-            Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
-        ))
-    );
-    add_help_item(&mut text_spans, "n", "Create new task");
-    add_help_item(&mut text_spans, "Space", "Move task to next status");
-    add_help_item(&mut text_spans, "e/i", "Edit task (not implemented yet)");
-    add_help_item(&mut text_spans, "d", "Delete task (not implemented yet)");
-    text_spans.push(Line::from(""));
-
-    // View options section
-    text_spans.push(
-        Line::from(Span::styled(
-            "View Options:",
-            Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
-        ))
-    );
-    add_help_item(&mut text_spans, "v", "Toggle view mode (Kanban/List/Detail)");
-    add_help_item(&mut text_spans, "f", "Toggle filter mode");
-    add_help_item(&mut text_spans, "1", "Filter Todo tasks");
-    add_help_item(&mut text_spans, "2", "Filter In Progress tasks");
-    add_help_item(&mut text_spans, "3", "Filter Done tasks");
-    text_spans.push(Line::from(""));
-
-    // General section
-    text_spans.push(
-        Line::from(Span::styled(
-            "General:",
-            Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
-        ))
-    );
-    add_help_item(&mut text_spans, "?", "Toggle help");
-    add_help_item(&mut text_spans, "q", "Quit");
-    text_spans.push(Line::from(""));
+        .border_style(Style::default().fg(app.theme.label));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(2),  // Title
+            Constraint::Min(0),     // Key bindings
+            Constraint::Length(1),  // Footer
+        ])
+        .split(inner);
+
+    let title = Paragraph::new(Line::from(Span::styled(
+        "TaskX - Terminal Task Manager",
+        Style::default().fg(app.theme.label).add_modifier(Modifier::BOLD),
+    )));
+    frame.render_widget(title, rows[0]);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[1]);
+
+    let mut left = Vec::new();
+    add_mode(&mut left, app, KeyMode::Normal);
+
+    let mut right = Vec::new();
+    for mode in KeyMode::iter().filter(|&mode| mode != KeyMode::Normal) {
+        add_mode(&mut right, app, mode);
+    }
+    add_section(&mut right, app, "Prompts");
+    add_help_item(&mut right, app, "Enter / Esc", "Confirm / cancel");
+    add_help_item(&mut right, app, "↑ / ↓", "Earlier searches and commands");
+    add_help_item(&mut right, app, "Tab", "Next field (edit form), complete (:)");
+
+    for (lines, area) in [(left, columns[0]), (right, columns[1])] {
+        let paragraph = Paragraph::new(Text::from(lines))
+            .block(Block::default().padding(Padding::right(2)))
+            .wrap(Wrap { trim: true })
+            .scroll((app.help_scroll, 0));
+        frame.render_widget(paragraph, area);
+    }
 
     // Footer
-    text_spans.push(
-        Line::from(Span::styled(
-            "Press any key to close this help screen",
-            Style::default().fg(Color::Yellow),
-        ))
-    );
+    let help_key = key_label(app, KeyMode::Normal, Action::Help);
+    let footer = Paragraph::new(Line::from(Span::styled(
+        format!("↑↓ scroll | {} or Esc closes | keys can be changed in keymap.toml", help_key),
+        Style::default().fg(app.theme.label),
+    )));
+    frame.render_widget(footer, rows[2]);
+}
+
+/// A heading and one line per bound action of `mode`.
+fn add_mode(lines: &mut Vec<Line<'static>>, app: &App, mode: KeyMode) {
+    let bindings = app.keymap.describe(mode);
+    if bindings.is_empty() {
+        return;
+    }
+    add_section(lines, app, mode.title());
+
+    // The status filters read better as one "1-9" line when they keep their digits
+    let status_keys: Vec<&Vec<String>> = bindings
+        .iter()
+        .filter(|(_, action)| matches!(action, Action::FilterStatus(_)))
+        .map(|(keys, _)| keys)
+        .collect();
+    let digits = status_keys.len() == 9
+        && status_keys.iter().enumerate().all(|(i, keys)| keys.len() == 1 && keys[0] == (i + 1).to_string());
+
+    for (keys, action) in &bindings {
+        match action {
+            Action::FilterStatus(1) if digits => add_help_item(lines, app, "1-9", "Filter by the nth workflow status"),
+            Action::FilterStatus(_) if digits => {}
+            _ => add_help_item(lines, app, &keys.join(" / "), &action.description(mode)),
+        }
+    }
+    lines.push(Line::from(""));
+}
 
-    let text = Text::from(text_spans);
+fn add_section(lines: &mut Vec<Line<'static>>, app: &App, title: &str) {
+    lines.push(Line::from(Span::styled(
+        format!("{}:", title),
+        Style::default().fg(app.theme.success).add_modifier(Modifier::BOLD),
+    )));
+}
 
-    let paragraph = Paragraph::new(text)
-        .block(block)
-        .wrap(Wrap { trim: true });
+/// The first key bound to `action`, for hints.
+pub fn key_label(app: &App, mode: KeyMode, action: Action) -> String {
+    app.keymap.keys_for(mode, action).into_iter().next().unwrap_or_default()
+}
 
-    frame.render_widget(paragraph, popup_area);
+/// Footer hints such as "h/l day", naming the first key bound to each action of a
+/// group. Groups without any bound key are left out.
+pub fn hint_spans(app: &App, mode: KeyMode, hints: &[(&[Action], &'static str)]) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    for (actions, label) in hints {
+        let keys: Vec<String> = actions
+            .iter()
+            .map(|&action| key_label(app, mode, action))
+            .filter(|key| !key.is_empty())
+            .collect();
+        if keys.is_empty() {
+            continue;
+        }
+        spans.push(Span::styled(keys.join("/"), Style::default().fg(app.theme.key)));
+        spans.push(Span::styled(*label, Style::default().fg(app.theme.muted)));
+    }
+    spans
 }
 
-fn add_help_item(spans: &mut Vec<Line<'static>>, key: &str, description: &str) {
+fn add_help_item(spans: &mut Vec<Line<'static>>, app: &App, key: &str, description: &str) {
     spans.push(Line::from(vec![
-        Span::styled(format!("  {:<12}", key), Style::default().fg(Color::Cyan)),
+        Span::styled(format!("  {:<12}", key), Style::default().fg(app.theme.accent)),
         Span::raw(description.to_string()),
     ]));
 }
@@ -1,6 +1,5 @@
 use itertools::Itertools;
 use ratatui::{
-    backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span, Text},
@@ -9,42 +8,29 @@ use ratatui::{
 };
 
 use crate::app::App;
-use crate::models::{Task, TaskStatus, TaskPriority};
+use crate::models::{Task, TaskPriority};
 use crate::utils;
-use crate::ui::colors;
 
 pub fn render(frame: &mut Frame, app: &App, area: Rect) {
+    // One equal-width column per workflow status
+    let count = app.workflow.statuses.len() as u32;
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(33),
-            Constraint::Percentage(33),
-            Constraint::Percentage(34),
-        ])
+        .constraints(vec![Constraint::Ratio(1, count); count as usize])
         .split(area);
 
     let tasks = app.filtered_tasks();
 
-    // Group tasks by status
-    let todo_tasks: Vec<&Task> = tasks.iter()
-        .filter(|&&t| t.status == TaskStatus::Todo)
-        .cloned()
-        .collect();
-
-    let in_progress_tasks: Vec<&Task> = tasks.iter()
-        .filter(|&&t| t.status == TaskStatus::InProgress)
-        .cloned()
-        .collect();
+    for (column, status) in app.workflow.statuses.iter().enumerate() {
+        let column_tasks: Vec<&Task> = tasks.iter()
+            .filter(|&&t| app.workflow.column_of(&t.status) == column)
+            .cloned()
+            .collect();
 
-    let done_tasks: Vec<&Task> = tasks.iter()
-        .filter(|&&t| t.status == TaskStatus::Done)
-        .cloned()
-        .collect();
-
-    // Render columns with different colors
-    render_column(frame, app, chunks[0], "TODO", &todo_tasks, TaskStatus::Todo, Color::Blue);
-    render_column(frame, app, chunks[1], "IN PROGRESS", &in_progress_tasks, TaskStatus::InProgress, Color::Green);
-    render_column(frame, app, chunks[2], "DONE", &done_tasks, TaskStatus::Done, Color::Gray);
+        let title = status.name.to_uppercase();
+        let color = app.theme.status(&app.workflow, &status.name);
+        render_column(frame, app, chunks[column], &title, &column_tasks, column, color);
+    }
 }
 
 // Function to get priority weight for sorting (higher priority = higher weight)
@@ -63,11 +49,25 @@ fn render_column(
     area: Rect,
     title: &str,
     tasks: &[&Task],
-    status: TaskStatus,
+    column: usize,
     color: Color,
 ) {
-    // Create title with count in parentheses
-    let title = format!(" {} ({}) ", title, tasks.len());
+    // Create title with count in parentheses, then the column's estimate totals
+    // Summed as u64: a few large estimates would overflow u32
+    let points: u64 = tasks.iter().filter_map(|task| task.points).map(u64::from).sum();
+    let minutes: u64 = tasks.iter().filter_map(|task| task.estimate_minutes).map(u64::from).sum();
+    let mut totals = Vec::new();
+    if points > 0 {
+        totals.push(format!("{} pts", points));
+    }
+    if minutes > 0 {
+        totals.push(utils::format_estimate(minutes));
+    }
+    let title = if totals.is_empty() {
+        format!(" {} ({}) ", title, tasks.len())
+    } else {
+        format!(" {} ({}) {} ", title, tasks.len(), totals.join(" · "))
+    };
 
     // Define column block with custom border color
     let column_block = Block::default()
@@ -83,7 +83,7 @@ fn render_column(
 
     if tasks.is_empty() {
         let text = Text::from(Line::from(vec![
-            Span::styled("No tasks", Style::default().fg(Color::DarkGray)),
+            Span::styled("No tasks", Style::default().fg(app.theme.faint)),
         ]));
 
         let paragraph = Paragraph::new(text)
@@ -93,18 +93,22 @@ fn render_column(
         return;
     }
 
-    // Sort tasks by priority (highest priority first)
-    let sorted_tasks: Vec<&Task> = tasks.iter()
-        .cloned()
-        .sorted_by(|a, b| get_priority_weight(&b).cmp(&get_priority_weight(&a)))
-        .collect();
+    // Sort tasks by priority (highest priority first), unless sorted with :sort
+    let sorted_tasks: Vec<&Task> = if app.sort.is_some() {
+        tasks.to_vec()
+    } else {
+        tasks.iter()
+            .cloned()
+            .sorted_by(|a, b| get_priority_weight(b).cmp(&get_priority_weight(a)))
+            .collect()
+    };
 
     let selected_index = if app.selected_task_index.is_some() {
         let all_tasks = app.filtered_tasks();
         if let Some(idx) = app.selected_task_index {
             if idx < all_tasks.len() {
                 let selected_task = all_tasks[idx];
-                if selected_task.status == status {
+                if app.workflow.column_of(&selected_task.status) == column {
                     sorted_tasks.iter().position(|&t| t.id == selected_task.id)
                 } else {
                     None
@@ -124,13 +128,7 @@ fn render_column(
         .iter()
         .map(|&task| {
             // Get priority color for badge
-            let priority_color = match task.priority.to_string().as_str() {
-                "Critical" => Color::Rgb(239, 68, 68),    // Red
-                "High" => Color::Rgb(251, 191, 36),       // Orange
-                "Medium" => Color::Rgb(16, 185, 129),     // Green
-                "Low" => Color::Rgb(59, 130, 246),        // Blue
-                _ => Color::DarkGray,
-            };
+            let priority_color = app.theme.priority(task.priority);
 
             // Get label based on priority
             let label = match task.priority.to_string().as_str() {
@@ -140,35 +138,53 @@ fn render_column(
                 _ => " Other ",
             };
 
+            // Prerequisites that are not done yet
+            let blocked = app.is_blocked(task);
+            let blocked_badge = if blocked {
+                Span::styled(" Blocked ", Style::default().bg(app.theme.danger).fg(app.theme.badge_text).add_modifier(Modifier::BOLD))
+            } else {
+                Span::raw("")
+            };
+
             // Create single line with priority badge, title, and label badge
-            let task_line = Line::from(vec![
+            let mut spans = vec![
                 // Priority badge first
                 Span::styled(
                     format!(" {} ", task.priority),
                     Style::default()
                         .bg(priority_color)
-                        .fg(Color::Black)
+                        .fg(app.theme.badge_text)
                 ),
                 Span::raw(" "),
-                // Title in the middle
+                blocked_badge,
+                Span::raw(if blocked { " " } else { "" }),
+            ];
+            // Title in the middle, with search matches highlighted
+            spans.extend(super::highlight_matches(
+                app,
+                &task.title,
+                Style::default().fg(app.theme.text).add_modifier(Modifier::BOLD),
+            ));
+            spans.extend([
+                Span::raw(" "),
+                // Recurring tasks come back when done
+                Span::styled(if task.recurrence.is_some() { "↻ " } else { "" }, Style::default().fg(app.theme.special)),
+                // Someone is working on it right now
+                Span::styled(if task.running_timer().is_some() { "⏱ " } else { "" }, Style::default().fg(app.theme.success)),
+                // Checklist and subtask progress
                 Span::styled(
-                    task.title.clone(),
-                    Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+                    app.progress(task).map_or(String::new(), |(done, total)| format!("{}/{} ", done, total)),
+                    Style::default().fg(app.theme.accent),
                 ),
-                Span::raw(" "),
                 // Label badge last
                 Span::styled(
                     label,
                     Style::default()
-                        .bg(Color::Rgb(60, 60, 60))
-                        .fg(Color::White),
+                        .bg(app.theme.badge)
+                        .fg(app.theme.text),
                 ),
             ]);
-
-            // Create task card with border and padding
-            let task_block = Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::DarkGray));
+            let task_line = Line::from(spans);
 
             // Create list item with block and content
             // Add empty lines for spacing between cards
@@ -177,18 +193,20 @@ fn render_column(
             ])
             .style(Style::default())
         })
-        .intersperse(
-            // Add an empty ListItem between each task for spacing
-            ListItem::new(vec![Line::from("")])
-                .style(Style::default())
-        )
         .collect();
 
+    // Add an empty ListItem between each task for spacing
+    let task_items: Vec<ListItem> = Itertools::intersperse(
+        task_items.into_iter(),
+        ListItem::new(vec![Line::from("")]).style(Style::default()),
+    )
+    .collect();
+
     // Create list of task cards
     let tasks_list = List::new(task_items)
         .highlight_style(
             Style::default()
-                .fg(Color::Yellow) // Just highlight text in yellow instead of background
+                .fg(app.theme.highlight) // Just highlight text instead of background
                 .add_modifier(Modifier::BOLD)
         )
         .highlight_symbol("");
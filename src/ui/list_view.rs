@@ -1,7 +1,7 @@
 use ratatui::{
     layout::{Constraint, Rect},
-    style::{Color, Modifier, Style},
-    text::Span,
+    style::{Modifier, Style},
+    text::{Line, Span},
     widgets::{Block, Borders, Cell, Row, Table},
     Frame,
 };
@@ -10,32 +10,58 @@ use crate::app::App;
 use crate::utils;
 
 pub fn render(frame: &mut Frame, app: &App, area: Rect) {
-    let tasks = app.filtered_tasks();
+    let rows = app.tree_rows();
     let selected = app.selected_task_index;
 
-    let header_cells = ["ID", "Title", "Status", "Priority"]
+    let header_cells = ["ID", "Title", "Progress", "Status", "Priority"]
         .iter()
-        .map(|h| Cell::from(*h).style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD)));
+        .map(|h| Cell::from(*h).style(Style::default().fg(app.theme.text).add_modifier(Modifier::BOLD)));
 
-    let header = Row::new(header_cells).style(Style::default().bg(Color::DarkGray));
+    let header = Row::new(header_cells).style(Style::default().bg(app.theme.header));
 
-    let rows = tasks.iter().enumerate().map(|(i, task)| {
-        let status_color = utils::parse_color(task.status_color());
-        let priority_color = utils::parse_color(task.priority_color());
+    let rows = rows.iter().enumerate().map(|(i, &(task, depth))| {
+        let status_color = app.theme.status(&app.workflow, task.status.as_str());
+        let priority_color = app.theme.priority(task.priority);
 
         let id = Span::raw(format!("#{}", i + 1));
-        let title = Span::raw(utils::truncate_string(&task.title, 40));
+        // Subtasks are indented under their parent; ▸ marks a folded parent
+        let marker = if !app.has_subtasks(task) {
+            "  "
+        } else if app.collapsed.contains(&task.id) {
+            "▸ "
+        } else {
+            "▾ "
+        };
+        let indent = "  ".repeat(depth);
+        let mut title = vec![Span::raw(format!("{}{}", indent, marker))];
+        let truncated = utils::truncate_string(&task.title, 40usize.saturating_sub(indent.len()));
+        title.extend(super::highlight_matches(app, &truncated, Style::default()));
+        if task.recurrence.is_some() {
+            title.push(Span::styled(" ↻", Style::default().fg(app.theme.special)));
+        }
+        if task.running_timer().is_some() {
+            title.push(Span::styled(" ⏱", Style::default().fg(app.theme.success)));
+        }
+        if app.is_blocked(task) {
+            title.push(Span::raw(" "));
+            title.push(Span::styled(" Blocked ", Style::default().fg(app.theme.badge_text).bg(app.theme.danger)));
+        }
+        let progress = Span::styled(
+            app.progress(task).map_or(String::new(), |(done, total)| format!("{}/{}", done, total)),
+            Style::default().fg(app.theme.accent),
+        );
         let status = Span::styled(format!("{}", task.status), Style::default().fg(status_color));
         let priority = Span::styled(format!("{}", task.priority), Style::default().fg(priority_color));
 
         let mut row_style = Style::default();
         if selected == Some(i) {
-            row_style = row_style.bg(Color::DarkGray);
+            row_style = row_style.bg(app.theme.selection);
         }
 
         let cells = vec![
             Cell::from(id),
-            Cell::from(title),
+            Cell::from(Line::from(title)),
+            Cell::from(progress),
             Cell::from(status),
             Cell::from(priority),
         ];
@@ -46,6 +72,7 @@ pub fn render(frame: &mut Frame, app: &App, area: Rect) {
     let widths = [
         Constraint::Length(5),
         Constraint::Percentage(60),
+        Constraint::Length(9),
         Constraint::Length(14),
         Constraint::Length(10),
     ];
@@ -1,23 +1,32 @@
+mod calendar_view;
+mod dependency_view;
+mod edit_form;
+mod finder;
 mod kanban_view;
 mod list_view;
+mod palette;
+mod project_switcher;
 mod task_detail;
 mod help;
 mod tabs;
-mod colors;
+mod theme_switcher;
+mod statistics_view;
+mod trash_view;
 
-use itertools::Itertools;
 use ratatui::{
-    backend::Backend,
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
     text::{Line, Span, Text},
-    widgets::{Block, Borders, Paragraph, Tabs, Wrap},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
     Frame,
 };
 
+use chrono::{Duration, Utc};
+use crate::app::subtasks::InputTarget;
 use crate::app::{App, AppMode, AppTab, InputMode};
-use crate::models::{TaskStatus, TaskPriority};
-use crate::utils;
+use crate::models::keymap::{Action, KeyMode, KeySequence};
+use crate::models::{search, time_tracking};
+use crate::stats;
 
 pub fn render(frame: &mut Frame, app: &App) {
     let chunks = Layout::default()
@@ -29,6 +38,9 @@ pub fn render(frame: &mut Frame, app: &App) {
         ])
         .split(frame.size());
 
+    // Paint the theme's background first; everything else draws over it
+    frame.render_widget(Block::default().style(app.theme.base()), frame.size());
+
     render_tabs(frame, app, chunks[0]);
 
     match app.current_tab {
@@ -44,164 +56,162 @@ pub fn render(frame: &mut Frame, app: &App) {
                 }
             }
         },
-        AppTab::Calendar => render_calendar(frame, app, chunks[1]),
-        AppTab::Statistics => render_statistics(frame, app, chunks[1]),
+        AppTab::Calendar => calendar_view::render(frame, app, chunks[1]),
+        AppTab::Statistics => statistics_view::render(frame, app, chunks[1]),
+        AppTab::Trash => trash_view::render(frame, app, chunks[1]),
     }
 
     render_input_bar(frame, app, chunks[2]);
+    if app.palette.is_some() {
+        palette::render(frame, app, chunks[1]);
+    }
 
     // Handle task details modal
-    if app.show_task_details {
-        if let Some(index) = app.selected_task_index {
-            let tasks = app.filtered_tasks();
-            if let Some(task) = tasks.get(index) {
-                task_detail::render(frame, app, chunks[1], task);
+    if app.show_task_details
+        && let Some(index) = app.selected_task_index
+    {
+        let tasks = app.filtered_tasks();
+        if let Some(task) = tasks.get(index) {
+            task_detail::render(frame, app, chunks[1], task);
+            if app.show_dependency_graph {
+                dependency_view::render(frame, app, task);
             }
         }
     }
 
+    if let Some(form) = &app.edit_form {
+        edit_form::render(frame, app, form);
+    }
+
+    if let Some(switcher) = &app.project_switcher {
+        project_switcher::render(frame, app, switcher);
+    }
+
+    if let Some(switcher) = &app.theme_switcher {
+        theme_switcher::render(frame, app, switcher);
+    }
+
+    if let Some(task_finder) = &app.finder {
+        finder::render(frame, app, task_finder);
+    }
+
     if app.show_help {
         help::render(frame, app);
     }
 }
 
 fn render_tabs(frame: &mut Frame, app: &App, area: Rect) {
-    let titles = vec![
-        Line::from(" Tasks "),
-        Line::from(" Calendar "),
-        Line::from(" Statistics "),
-    ];
-
-    let selected_tab = app.current_tab as usize;
+    // Pinned views get tabs between Tasks and Calendar
+    let selected_tab = app.tab_index();
 
-    let tab_widget = Tabs::new(titles)
+    let tab_widget = tabs::get_tab_titles(app.tab_titles())
         .block(Block::default().borders(Borders::BOTTOM))
         .select(selected_tab)
-        .highlight_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+        .highlight_style(Style::default().fg(app.theme.accent).add_modifier(Modifier::BOLD));
 
     frame.render_widget(tab_widget, area);
-}
 
-fn render_calendar(frame: &mut Frame, app: &App, area: Rect) {
-    let block = Block::default()
-        .title(" Calendar View ")
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
-
-    let text = Text::from(Line::from(vec![
-        Span::styled("Calendar view ", Style::default().fg(Color::Yellow)),
-        Span::raw("coming soon!"),
-    ]));
-
-    let paragraph = Paragraph::new(text)
-        .block(block)
-        .wrap(Wrap { trim: true });
-
-    frame.render_widget(paragraph, area);
-}
-
-fn render_statistics(frame: &mut Frame, app: &App, area: Rect) {
-    let block = Block::default()
-        .title(" Statistics ")
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
-
-    // Collect task statistics
-    let total = app.tasks.len();
-    let todo = app.tasks.iter().filter(|t| t.status == TaskStatus::Todo).count();
-    let in_progress = app.tasks.iter().filter(|t| t.status == TaskStatus::InProgress).count();
-    let done = app.tasks.iter().filter(|t| t.status == TaskStatus::Done).count();
-
-    let mut lines = Vec::new();
-
-    lines.push(Line::from(
-        Span::styled("Task Statistics", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
-    ));
-
-    lines.push(Line::from(""));
-
-    lines.push(Line::from(vec![
-        Span::raw("Total Tasks: "),
-        Span::styled(total.to_string(), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
-    ]));
-
-    lines.push(Line::from(vec![
-        Span::raw("Todo: "),
-        Span::styled(todo.to_string(), Style::default().fg(Color::Blue)),
-        Span::raw("  In Progress: "),
-        Span::styled(in_progress.to_string(), Style::default().fg(Color::Green)),
-        Span::raw("  Done: "),
-        Span::styled(done.to_string(), Style::default().fg(Color::Gray)),
-    ]));
-
-    lines.push(Line::from(""));
-
-    lines.push(Line::from(
-        Span::styled("Priority Breakdown", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
-    ));
-
-    lines.push(Line::from(vec![
-        Span::raw("Critical: "),
-        Span::styled(
-            app.tasks.iter().filter(|t| t.priority == TaskPriority::Critical).count().to_string(),
-            Style::default().fg(Color::Red)
-        ),
-    ]));
-
-    lines.push(Line::from(vec![
-        Span::raw("High: "),
-        Span::styled(
-            app.tasks.iter().filter(|t| t.priority == TaskPriority::High).count().to_string(),
-            Style::default().fg(Color::LightRed)
-        ),
-    ]));
-
-    lines.push(Line::from(vec![
-        Span::raw("Medium: "),
-        Span::styled(
-            app.tasks.iter().filter(|t| t.priority == TaskPriority::Medium).count().to_string(),
-            Style::default().fg(Color::Yellow)
-        ),
-    ]));
-
-    lines.push(Line::from(vec![
-        Span::raw("Low: "),
-        Span::styled(
-            app.tasks.iter().filter(|t| t.priority == TaskPriority::Low).count().to_string(),
-            Style::default().fg(Color::Blue)
-        ),
-    ]));
-
-    let paragraph = Paragraph::new(lines)
-        .block(block)
-        .wrap(Wrap { trim: true });
-
-    frame.render_widget(paragraph, area);
+    // The open board and project sit at the right end of the tab bar
+    let board = match &app.local_board {
+        Some(dir) => dir
+            .parent()
+            .and_then(|repo| repo.file_name())
+            .map_or_else(|| dir.display().to_string(), |repo| format!("{}/.taskx", repo.to_string_lossy())),
+        None => "global".to_string(),
+    };
+    let board_color = if app.local_board.is_some() { app.theme.success } else { app.theme.muted };
+    let project = Paragraph::new(Line::from(vec![
+        Span::styled("Board: ", Style::default().fg(app.theme.muted)),
+        Span::styled(board, Style::default().fg(board_color).add_modifier(Modifier::BOLD)),
+        Span::styled("  Project: ", Style::default().fg(app.theme.muted)),
+        Span::styled(app.project.as_str(), Style::default().fg(app.theme.accent).add_modifier(Modifier::BOLD)),
+        Span::raw(" "),
+    ]))
+    .alignment(Alignment::Right);
+    frame.render_widget(project, Rect { height: 1, ..area });
 }
 
 fn render_input_bar(frame: &mut Frame, app: &App, area: Rect) {
     let text = match app.input_mode {
         InputMode::Normal => {
+            // A running timer, the filter and the active search stay visible behind messages and hints
+            let mut spans = running_timer_spans(app);
+            if app.filter_active {
+                spans.push(Span::styled(format!("filter: {}", app.filter_text), Style::default().fg(app.theme.accent).add_modifier(Modifier::BOLD)));
+                spans.push(Span::styled(" | ", Style::default().fg(app.theme.text)));
+            }
+            if app.is_searching() {
+                spans.push(Span::styled(format!("/{}", app.search_query), Style::default().fg(app.theme.label).add_modifier(Modifier::BOLD)));
+                spans.push(Span::styled(" | ", Style::default().fg(app.theme.text)));
+            }
+            // The start of a multi-key binding, like vim's showcmd
+            if !app.pending_keys.is_empty() {
+                spans.push(Span::styled(KeySequence(app.pending_keys.clone()).to_string(), Style::default().fg(app.theme.special).add_modifier(Modifier::BOLD)));
+                spans.push(Span::styled(" | ", Style::default().fg(app.theme.text)));
+            }
+            match &app.status_message {
+                Some(message) => spans.push(Span::styled(message.as_str(), Style::default().fg(app.theme.label))),
+                None => {
+                    // Hints name whatever keys the keymap binds
+                    let (next, next_hint) = if app.is_searching() {
+                        (help::key_label(app, KeyMode::Search, Action::NextMatch), " next match")
+                    } else {
+                        (help::key_label(app, KeyMode::Normal, Action::NewTask), " new task")
+                    };
+                    let hints = [
+                        (help::key_label(app, KeyMode::Normal, Action::Help), " for help"),
+                        (help::key_label(app, KeyMode::Normal, Action::Quit), " to quit"),
+                        (next, next_hint),
+                        (help::key_label(app, KeyMode::Normal, Action::ToggleMode), " toggle view"),
+                    ];
+                    for (i, (key, hint)) in hints.into_iter().filter(|(key, _)| !key.is_empty()).enumerate() {
+                        if i > 0 {
+                            spans.push(Span::styled(" | ", Style::default().fg(app.theme.text)));
+                        } else {
+                            spans.push(Span::styled("Press ", Style::default().fg(app.theme.info)));
+                        }
+                        spans.push(Span::styled(key, Style::default().fg(app.theme.key)));
+                        spans.push(Span::styled(hint, Style::default().fg(app.theme.info)));
+                    }
+                }
+            }
+            Text::from(Line::from(spans))
+        },
+        InputMode::Editing => {
+            let label = match &app.input_target {
+                InputTarget::NewTask => "New task: ",
+                InputTarget::Subtask(_) => "New subtask: ",
+                InputTarget::ChecklistItem(_) => "New checklist item: ",
+                InputTarget::TimerNote(_) => "Timer note: ",
+                InputTarget::NewProject => "New project: ",
+                InputTarget::Filter => "Filter: ",
+                InputTarget::ViewName => "Save view as: ",
+            };
             Text::from(Line::from(vec![
-                Span::styled("Press ", Style::default().fg(Color::Blue)),
-                Span::styled("?", Style::default().fg(Color::Yellow)),
-                Span::styled(" for help", Style::default().fg(Color::Blue)),
-                Span::styled(" | ", Style::default().fg(Color::White)),
-                Span::styled("q", Style::default().fg(Color::Yellow)),
-                Span::styled(" to quit", Style::default().fg(Color::Blue)),
-                Span::styled(" | ", Style::default().fg(Color::White)),
-                Span::styled("n", Style::default().fg(Color::Yellow)),
-                Span::styled(" new task", Style::default().fg(Color::Blue)),
-                Span::styled(" | ", Style::default().fg(Color::White)),
-                Span::styled("v", Style::default().fg(Color::Yellow)),
-                Span::styled(" toggle view", Style::default().fg(Color::Blue)),
+                Span::styled(label, Style::default().fg(app.theme.label)),
+                Span::styled(&app.new_task_input, Style::default().fg(app.theme.text)),
+                Span::styled(" (ESC to cancel, ENTER to save)", Style::default().fg(app.theme.faint)),
             ]))
         },
-        InputMode::Editing => {
+        InputMode::Search => {
+            Text::from(Line::from(vec![
+                Span::styled("/", Style::default().fg(app.theme.label)),
+                Span::styled(&app.search_query, Style::default().fg(app.theme.text)),
+                Span::styled(" (↑↓ history, ENTER to keep, ESC to cancel)", Style::default().fg(app.theme.faint)),
+            ]))
+        },
+        InputMode::Command => {
+            let input = app.palette.as_ref().map_or("", |palette| palette.input.as_str());
+            Text::from(Line::from(vec![
+                Span::styled(":", Style::default().fg(app.theme.label)),
+                Span::styled(input, Style::default().fg(app.theme.text)),
+                Span::styled(" (TAB complete, ↑↓ history, ENTER to run, ESC to cancel)", Style::default().fg(app.theme.faint)),
+            ]))
+        },
+        InputMode::EditForm => {
             Text::from(Line::from(vec![
-                Span::styled("New task: ", Style::default().fg(Color::Yellow)),
-                Span::styled(&app.new_task_input, Style::default().fg(Color::White)),
-                Span::styled(" (ESC to cancel, ENTER to save)", Style::default().fg(Color::DarkGray)),
+                Span::styled("Editing task", Style::default().fg(app.theme.label)),
+                Span::styled(" (TAB next field, ENTER to save, ESC to cancel)", Style::default().fg(app.theme.faint)),
             ]))
         },
     };
@@ -211,4 +221,40 @@ fn render_input_bar(frame: &mut Frame, app: &App, area: Rect) {
         .wrap(Wrap { trim: true });
 
     frame.render_widget(input_block, area);
-}
\ No newline at end of file
+}
+
+/// "⏱ 12m Title | " for the task whose timer is running, if any.
+fn running_timer_spans(app: &App) -> Vec<Span<'_>> {
+    let Some(task) = time_tracking::running(&app.tasks) else {
+        return Vec::new();
+    };
+    let elapsed = task.running_timer().map_or_else(Duration::zero, |entry| entry.duration(Utc::now()));
+    vec![
+        Span::styled(format!("⏱ {} ", stats::format_duration(elapsed)), Style::default().fg(app.theme.success).add_modifier(Modifier::BOLD)),
+        Span::styled(task.title.as_str(), Style::default().fg(app.theme.text)),
+        Span::styled(" | ", Style::default().fg(app.theme.text)),
+    ]
+}
+
+/// Blanks a popup's area in the theme's colors.
+fn clear(frame: &mut Frame, app: &App, area: Rect) {
+    frame.render_widget(Clear, area);
+    frame.render_widget(Block::default().style(app.theme.base()), area);
+}
+
+/// `text` split into spans with every match of the active search highlighted.
+fn highlight_matches(app: &App, text: &str, style: Style) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut last = 0;
+    for (start, end) in search::match_ranges(text, &app.search_query) {
+        if start > last {
+            spans.push(Span::styled(text[last..start].to_string(), style));
+        }
+        spans.push(Span::styled(text[start..end].to_string(), style.bg(app.theme.search_match).fg(app.theme.badge_text)));
+        last = end;
+    }
+    if last < text.len() || spans.is_empty() {
+        spans.push(Span::styled(text[last..].to_string(), style));
+    }
+    spans
+}
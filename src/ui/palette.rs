@@ -0,0 +1,50 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+
+const MAX_VISIBLE: usize = 10;
+
+/// Completions for the `:` command line, drawn just above the input bar.
+pub fn render(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(palette) = &app.palette else {
+        return;
+    };
+    let completions = app.palette_completions();
+    if completions.is_empty() {
+        return;
+    }
+
+    let selected = palette.selected % completions.len();
+    let height = (completions.len().min(MAX_VISIBLE) as u16 + 2).min(area.height);
+    let popup_area = Rect { y: area.bottom().saturating_sub(height), height, ..area };
+    super::clear(frame, app, popup_area);
+
+    let width = completions.iter().map(|c| c.text.chars().count()).max().unwrap_or(0);
+    let lines: Vec<Line> = completions
+        .iter()
+        .enumerate()
+        .map(|(i, completion)| {
+            let mut style = Style::default().fg(app.theme.label);
+            if i == selected {
+                style = style.bg(app.theme.selection).add_modifier(Modifier::BOLD);
+            }
+            Line::from(vec![
+                Span::styled(format!(" {:<width$} ", completion.text, width = width), style),
+                Span::styled(format!(" {}", completion.hint), Style::default().fg(app.theme.muted)),
+            ])
+        })
+        .collect();
+
+    // Keep the highlighted completion on screen
+    let scroll = (selected + 1).saturating_sub(MAX_VISIBLE) as u16;
+    let list = Paragraph::new(lines)
+        .scroll((scroll, 0))
+        .block(Block::default().title(" Commands ").borders(Borders::ALL).border_style(Style::default().fg(app.theme.accent)));
+    frame.render_widget(list, popup_area);
+}
@@ -0,0 +1,73 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::app::projects::ProjectSwitcher;
+use crate::app::App;
+use crate::models::keymap::{Action, KeyMode};
+use crate::ui::help;
+use crate::ui::task_detail::centered_rect;
+
+/// Popup listing the projects, to switch boards or to pick where a task goes.
+pub fn render(frame: &mut Frame, app: &App, switcher: &ProjectSwitcher) {
+    let popup_area = centered_rect(50, 50, frame.size());
+    super::clear(frame, app, popup_area);
+
+    let moving = switcher
+        .moving
+        .as_ref()
+        .and_then(|id| app.tasks.iter().find(|task| &task.id == id));
+    let title = match moving {
+        Some(task) => format!(" Move \"{}\" to ", task.title),
+        None => " Projects ".to_string(),
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.accent));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Min(0),     // Projects
+            Constraint::Length(1),  // Footer
+        ])
+        .split(inner);
+
+    let lines: Vec<Line> = switcher
+        .projects
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let current = *name == app.project;
+            let mut style = Style::default().fg(if current { app.theme.accent } else { app.theme.text });
+            if i == switcher.index {
+                style = style.bg(app.theme.selection).add_modifier(Modifier::BOLD);
+            }
+            Line::from(vec![
+                Span::styled(if current { "● " } else { "  " }, Style::default().fg(app.theme.accent)),
+                Span::styled(name.as_str(), style),
+            ])
+        })
+        .collect();
+
+    // Keep the highlighted project on screen in long lists
+    let visible = chunks[0].height as usize;
+    let scroll = (switcher.index + 1).saturating_sub(visible) as u16;
+    frame.render_widget(Paragraph::new(lines).scroll((scroll, 0)), chunks[0]);
+
+    let hints: &[(&[Action], &str)] = if moving.is_some() {
+        &[(&[Action::Open], " move here  "), (&[Action::Close], " close")]
+    } else {
+        &[(&[Action::Open], " switch  "), (&[Action::NewProject], " new project  "), (&[Action::Close], " close")]
+    };
+    let footer = Paragraph::new(Line::from(help::hint_spans(app, KeyMode::Projects, hints)));
+    frame.render_widget(footer, chunks[1]);
+}
@@ -0,0 +1,226 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    symbols::Marker,
+    text::{Line, Span},
+    widgets::{Axis, Bar, BarChart, BarGroup, Block, Borders, Chart, Dataset, GraphType, Paragraph},
+    Frame,
+};
+
+use chrono::{Duration, NaiveDate, Utc};
+
+use crate::app::App;
+use crate::stats::{self, DailySnapshot};
+
+pub fn render(frame: &mut Frame, app: &App, area: Rect) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(4),        // Summary
+            Constraint::Percentage(45),   // Line charts
+            Constraint::Min(0),           // Bar charts
+            Constraint::Percentage(25),   // Time tracking
+        ])
+        .split(area);
+
+    let top = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[1]);
+    let bottom = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(rows[2]);
+    let tracked = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[3]);
+
+    let snapshots = stats::daily_snapshots(&app.tasks, &app.workflow, stats::CHART_DAYS);
+    let now = Utc::now();
+    let days = stats::tracked_per_day(&app.tasks, stats::TRACKED_DAYS, now);
+    let weeks = stats::tracked_per_week(&app.tasks, stats::TRACKED_WEEKS, now);
+
+    render_summary(frame, app, rows[0]);
+    render_burnup(frame, app, &snapshots, top[0]);
+    render_cumulative_flow(frame, app, &snapshots, top[1]);
+    render_throughput(frame, app, bottom[0]);
+    render_priorities(frame, app, bottom[1]);
+    render_tracked(frame, app, " Time tracked per day ", &days, "%a", tracked[0]);
+    render_tracked(frame, app, " Time tracked per week ", &weeks, "%b %-d", tracked[1]);
+}
+
+fn render_summary(frame: &mut Frame, app: &App, area: Rect) {
+    let tasks = &app.tasks;
+    let today = stats::daily_snapshots(tasks, &app.workflow, 1).remove(0);
+    let lead = stats::average_lead_time(tasks, &app.workflow).map_or("-".to_string(), stats::format_duration);
+    let cycle = stats::average_cycle_time(tasks, &app.workflow).map_or("-".to_string(), stats::format_duration);
+    let now = Utc::now();
+    let accuracy = stats::estimate_calibration(tasks, &app.workflow, now)
+        .and_then(|(estimated, tracked, _)| stats::percent_of_estimate(estimated, tracked))
+        .map_or("-".to_string(), |percent| format!("{}%", percent));
+    let per_point = stats::time_per_point(tasks, &app.workflow, now).map_or("-".to_string(), stats::format_duration);
+    let value = Style::default().fg(app.theme.text).add_modifier(Modifier::BOLD);
+
+    let mut counts = vec![Span::raw("Total: "), Span::styled(tasks.len().to_string(), value)];
+    for (status, count) in app.workflow.statuses.iter().zip(&today.counts) {
+        counts.push(Span::raw(format!("  {}: ", status.name)));
+        counts.push(Span::styled(count.to_string(), Style::default().fg(app.theme.status(&app.workflow, &status.name))));
+    }
+
+    let lines = vec![
+        Line::from(counts),
+        Line::from(vec![
+            Span::raw("Avg lead time: "),
+            Span::styled(lead, value),
+            Span::raw("  Avg cycle time: "),
+            Span::styled(cycle, value),
+            Span::raw("  Tracked vs estimated: "),
+            Span::styled(accuracy, value),
+            Span::raw("  Time per point: "),
+            Span::styled(per_point, value),
+        ]),
+    ];
+
+    let block = Block::default()
+        .title(" Statistics ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.accent));
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn render_burnup(frame: &mut Frame, app: &App, snapshots: &[DailySnapshot], area: Rect) {
+    let scope = series(snapshots, DailySnapshot::total);
+    let done = series(snapshots, |s| s.done);
+    let remaining = series(snapshots, DailySnapshot::remaining);
+
+    let datasets = vec![
+        line("Scope", app.theme.info, &scope),
+        line("Done", app.theme.success, &done),
+        line("Remaining", app.theme.danger, &remaining),
+    ];
+    render_line_chart(frame, app, " Burnup / Burndown ", datasets, snapshots, area);
+}
+
+/// Stacked counts drawn as lines: each band between two lines is one status.
+fn render_cumulative_flow(frame: &mut Frame, app: &App, snapshots: &[DailySnapshot], area: Rect) {
+    let bands: Vec<Vec<(f64, f64)>> = (0..app.workflow.statuses.len())
+        .map(|column| series(snapshots, |s| s.stacked_from(column)))
+        .collect();
+
+    let datasets = app
+        .workflow
+        .statuses
+        .iter()
+        .zip(&bands)
+        .map(|(status, data)| line(&status.name, app.theme.status(&app.workflow, &status.name), data))
+        .collect();
+    render_line_chart(frame, app, " Cumulative Flow ", datasets, snapshots, area);
+}
+
+fn render_line_chart(frame: &mut Frame, app: &App, title: &str, datasets: Vec<Dataset>, snapshots: &[DailySnapshot], area: Rect) {
+    let max = snapshots.iter().map(DailySnapshot::total).max().unwrap_or(0).max(1) as f64;
+    let last = snapshots.len().saturating_sub(1);
+    let date_label = |i: usize| Span::raw(snapshots.get(i).map_or(String::new(), |s| s.date.format("%b %-d").to_string()));
+
+    let chart = Chart::new(datasets)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .x_axis(
+            Axis::default()
+                .style(Style::default().fg(app.theme.muted))
+                .bounds([0.0, last as f64])
+                .labels(vec![date_label(0), date_label(last / 2), date_label(last)]),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(app.theme.muted))
+                .bounds([0.0, max])
+                .labels(vec![Span::raw("0"), Span::raw(format!("{}", max as u64))]),
+        );
+
+    frame.render_widget(chart, area);
+}
+
+fn render_throughput(frame: &mut Frame, app: &App, area: Rect) {
+    let weeks = stats::weekly_throughput(&app.tasks, &app.workflow, stats::THROUGHPUT_WEEKS);
+    let bars: Vec<Bar> = weeks
+        .iter()
+        .map(|(start, count)| {
+            Bar::default()
+                .value(*count)
+                .label(Line::from(start.format("%b %-d").to_string()))
+                .style(Style::default().fg(app.theme.success))
+        })
+        .collect();
+
+    let chart = BarChart::default()
+        .block(Block::default().title(" Throughput (done per week) ").borders(Borders::ALL))
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(6)
+        .bar_gap(1)
+        .value_style(Style::default().fg(app.theme.badge_text).bg(app.theme.success));
+
+    frame.render_widget(chart, area);
+}
+
+fn render_priorities(frame: &mut Frame, app: &App, area: Rect) {
+    let bars: Vec<Bar> = stats::priority_counts(&app.tasks)
+        .into_iter()
+        .map(|(priority, count)| {
+            let color = app.theme.priority(priority);
+            Bar::default()
+                .value(count)
+                .label(Line::from(priority.to_string()))
+                .style(Style::default().fg(color))
+                .value_style(Style::default().fg(app.theme.badge_text).bg(color))
+        })
+        .collect();
+
+    let chart = BarChart::default()
+        .block(Block::default().title(" Priority Distribution ").borders(Borders::ALL))
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(8)
+        .bar_gap(2);
+
+    frame.render_widget(chart, area);
+}
+
+/// Hours tracked per period; `label` formats the period's first day.
+fn render_tracked(frame: &mut Frame, app: &App, title: &str, periods: &[(NaiveDate, Duration)], label: &str, area: Rect) {
+    let bars: Vec<Bar> = periods
+        .iter()
+        .map(|(date, time)| {
+            Bar::default()
+                .value(time.num_minutes().max(0) as u64)
+                .text_value(format!("{:.1}h", time.num_minutes() as f64 / 60.0))
+                .label(Line::from(date.format(label).to_string()))
+                .style(Style::default().fg(app.theme.special))
+        })
+        .collect();
+
+    let chart = BarChart::default()
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(6)
+        .bar_gap(1)
+        .value_style(Style::default().fg(app.theme.badge_text).bg(app.theme.special));
+
+    frame.render_widget(chart, area);
+}
+
+fn series(snapshots: &[DailySnapshot], value: impl Fn(&DailySnapshot) -> u64) -> Vec<(f64, f64)> {
+    snapshots
+        .iter()
+        .enumerate()
+        .map(|(i, snapshot)| (i as f64, value(snapshot) as f64))
+        .collect()
+}
+
+fn line<'a>(name: &'a str, color: Color, data: &'a [(f64, f64)]) -> Dataset<'a> {
+    Dataset::default()
+        .name(name)
+        .marker(Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(color))
+        .data(data)
+}
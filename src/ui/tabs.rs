@@ -3,10 +3,6 @@ use ratatui::{
     widgets::Tabs,
 };
 
-pub fn get_tab_titles() -> Tabs<'static> {
-    Tabs::new(vec![
-        Line::from(" Tasks "),
-        Line::from(" Calendar "),
-        Line::from(" Statistics "),
-    ])
-}
\ No newline at end of file
+pub fn get_tab_titles(titles: Vec<String>) -> Tabs<'static> {
+    Tabs::new(titles.into_iter().map(|title| Line::from(format!(" {} ", title))).collect::<Vec<_>>())
+}
@@ -1,18 +1,20 @@
 use ratatui::{
-    backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span, Text},
-    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    widgets::{Block, Borders, Paragraph, Wrap},
     Frame,
 };
 
 use chrono::{DateTime, Local, Utc};
 use crate::app::App;
-use crate::models::Task;
+use crate::models::keymap::{Action, KeyMode};
+use crate::models::{dependencies, subtasks, Task};
+use crate::stats;
+use crate::ui::help;
 use crate::utils;
 
-pub fn render(frame: &mut Frame, app: &App, area: Rect, task: &Task) {
+pub fn render(frame: &mut Frame, app: &App, _area: Rect, task: &Task) {
     // Only show modal if the flag is set
     if !app.show_task_details {
         return;
@@ -22,7 +24,19 @@ pub fn render(frame: &mut Frame, app: &App, area: Rect, task: &Task) {
     let popup_area = centered_rect(80, 80, size);
 
     // Clear the area where the popup will be rendered
-    frame.render_widget(Clear, popup_area);
+    super::clear(frame, app, popup_area);
+
+    // Timeline grows with the history, up to eight visible events
+    let history_height = task.history.len().clamp(1, 8) as u16 + 2;
+    let checklist_height = task.checklist.len().clamp(1, 6) as u16 + 2;
+
+    // Subtasks appear only for tasks that are part of a tree
+    let children: Vec<&Task> = subtasks::children(&app.tasks, &task.id).collect();
+    let parent = task.parent_id.as_deref().and_then(|id| app.tasks.iter().find(|t| t.id == id));
+    let subtask_height = match (children.len(), parent) {
+        (0, None) => 0,
+        (count, parent) => (count + parent.is_some() as usize).min(6) as u16 + 2,
+    };
 
     // Create a layout for the task details
     let chunks = Layout::default()
@@ -33,7 +47,10 @@ pub fn render(frame: &mut Frame, app: &App, area: Rect, task: &Task) {
             Constraint::Length(2),  // Creation date
             Constraint::Length(2),  // Status
             Constraint::Length(2),  // Priority
-            Constraint::Min(5),     // Description
+            Constraint::Min(4),     // Description
+            Constraint::Length(checklist_height), // Checklist
+            Constraint::Length(subtask_height),   // Subtasks
+            Constraint::Length(history_height),   // History
             Constraint::Length(1),  // Empty space
             Constraint::Length(1),  // Footer
         ])
@@ -43,49 +60,88 @@ pub fn render(frame: &mut Frame, app: &App, area: Rect, task: &Task) {
     let block = Block::default()
         .title(format!(" Task Details ({}) ", task.id.chars().take(8).collect::<String>()))
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
+        .border_style(Style::default().fg(app.theme.accent));
 
     frame.render_widget(block, popup_area);
 
     // Title section
     let title_text = Text::from(vec![
         Line::from(vec![
-            Span::styled("Title: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            Span::styled("Title: ", Style::default().fg(app.theme.label).add_modifier(Modifier::BOLD)),
             Span::raw(&task.title),
         ]),
     ]);
     let title_paragraph = Paragraph::new(title_text);
     frame.render_widget(title_paragraph, chunks[0]);
 
-    // Created at
-    let created_text = Text::from(vec![
-        Line::from(vec![
-            Span::styled("Created: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-            Span::raw(task.created_at.format("%Y-%m-%d %H:%M").to_string()),
-        ]),
-    ]);
+    // Created at, and the time tracked so far
+    let mut created_spans = vec![
+        Span::styled("Created: ", Style::default().fg(app.theme.label).add_modifier(Modifier::BOLD)),
+        Span::raw(format_date(task.created_at)),
+    ];
+    if !task.time_entries.is_empty() || task.estimate_minutes.is_some() {
+        let entries = task.time_entries.len();
+        created_spans.push(Span::styled("  Time spent: ", Style::default().fg(app.theme.label).add_modifier(Modifier::BOLD)));
+        created_spans.push(Span::raw(stats::format_duration(task.time_spent(Utc::now()))));
+        if let Some(estimate) = task.estimate_minutes {
+            created_spans.push(Span::raw(format!(" of {} estimated", utils::format_estimate(estimate.into()))));
+        }
+        created_spans.push(Span::styled(
+            format!(" ({} {})", entries, if entries == 1 { "entry" } else { "entries" }),
+            Style::default().fg(app.theme.muted),
+        ));
+    }
+    if let Some(entry) = task.running_timer() {
+        let since = entry.start.with_timezone(&Local).format("%H:%M");
+        created_spans.push(Span::styled(format!("  ⏱ running since {} ({} to stop)", since, help::key_label(app, KeyMode::Details, Action::Timer)), Style::default().fg(app.theme.success)));
+    }
+    let created_text = Text::from(vec![Line::from(created_spans)]);
     let created_paragraph = Paragraph::new(created_text);
     frame.render_widget(created_paragraph, chunks[1]);
 
-    // Status with colored indicator
-    let status_color = utils::parse_color(task.status_color());
+    // Status with colored indicator, plus whatever still blocks the task
+    let open = dependencies::open_prerequisites(&app.tasks, task, &app.workflow);
+    let blocked_note = if open.is_empty() {
+        String::new()
+    } else {
+        let titles: Vec<&str> = open.iter().map(|t| t.title.as_str()).collect();
+        format!("  Blocked by {} (g for graph)", titles.join(", "))
+    };
+    let status_color = app.theme.status(&app.workflow, task.status.as_str());
     let status_text = Text::from(vec![
         Line::from(vec![
-            Span::styled("Status: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            Span::styled("Status: ", Style::default().fg(app.theme.label).add_modifier(Modifier::BOLD)),
             Span::styled(format!("{}", task.status), Style::default().fg(status_color)),
+            Span::styled(blocked_note, Style::default().fg(app.theme.danger)),
         ]),
     ]);
     let status_paragraph = Paragraph::new(status_text);
     frame.render_widget(status_paragraph, chunks[2]);
 
-    // Priority with colored indicator
-    let priority_color = utils::parse_color(task.priority_color());
-    let priority_text = Text::from(vec![
-        Line::from(vec![
-            Span::styled("Priority: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-            Span::styled(format!("{}", task.priority), Style::default().fg(priority_color)),
-        ]),
-    ]);
+    // Priority with colored indicator, story points, and the repeat rule if there is one
+    let priority_color = app.theme.priority(task.priority);
+    let mut priority_spans = vec![
+        Span::styled("Priority: ", Style::default().fg(app.theme.label).add_modifier(Modifier::BOLD)),
+        Span::styled(format!("{}", task.priority), Style::default().fg(priority_color)),
+    ];
+    if let Some(points) = task.points {
+        priority_spans.push(Span::styled("  Points: ", Style::default().fg(app.theme.label).add_modifier(Modifier::BOLD)));
+        priority_spans.push(Span::raw(points.to_string()));
+    }
+    if let Some(recurrence) = &task.recurrence {
+        priority_spans.push(Span::styled("  Repeats: ", Style::default().fg(app.theme.label).add_modifier(Modifier::BOLD)));
+        priority_spans.push(Span::styled(format!("↻ {}", recurrence), Style::default().fg(app.theme.special)));
+    }
+    if let Some(series_id) = &task.series_id {
+        let completed = app
+            .tasks
+            .iter()
+            .filter(|t| t.id != task.id && (&t.id == series_id || t.series_id.as_ref() == Some(series_id)))
+            .filter(|t| app.workflow.is_done(&t.status))
+            .count();
+        priority_spans.push(Span::styled(format!("  ({} done before)", completed), Style::default().fg(app.theme.muted)));
+    }
+    let priority_text = Text::from(vec![Line::from(priority_spans)]);
     let priority_paragraph = Paragraph::new(priority_text);
     frame.render_widget(priority_paragraph, chunks[3]);
 
@@ -93,11 +149,11 @@ pub fn render(frame: &mut Frame, app: &App, area: Rect, task: &Task) {
     let description_block = Block::default()
         .title(" Description ")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::DarkGray));
+        .border_style(Style::default().fg(app.theme.border));
 
     let description_text = if task.description.is_empty() {
         Text::from(Line::from(vec![
-            Span::styled("No description provided", Style::default().fg(Color::DarkGray)),
+            Span::styled("No description provided", Style::default().fg(app.theme.faint)),
         ]))
     } else {
         Text::from(task.description.as_str())
@@ -109,22 +165,112 @@ pub fn render(frame: &mut Frame, app: &App, area: Rect, task: &Task) {
 
     frame.render_widget(description_paragraph, chunks[4]);
 
+    // Checklist with the cursor used by Space/x
+    let (done, total) = task.checklist_progress();
+    let checklist_block = Block::default()
+        .title(format!(" Checklist ({}/{}) ", done, total))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.border));
+
+    let checklist_lines: Vec<Line> = if task.checklist.is_empty() {
+        vec![Line::from(Span::styled(
+            format!("No checklist items ({} to add)", help::key_label(app, KeyMode::Details, Action::AddItem)),
+            Style::default().fg(app.theme.faint),
+        ))]
+    } else {
+        task.checklist
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let mut style = if item.done {
+                    Style::default().fg(app.theme.faint).add_modifier(Modifier::CROSSED_OUT)
+                } else {
+                    Style::default().fg(app.theme.text)
+                };
+                if i == app.checklist_index {
+                    style = style.bg(app.theme.selection).add_modifier(Modifier::BOLD);
+                }
+                Line::from(vec![
+                    Span::styled(if item.done { "[x] " } else { "[ ] " }, Style::default().fg(app.theme.accent)),
+                    Span::styled(item.text.clone(), style),
+                ])
+            })
+            .collect()
+    };
+
+    // Keep the cursor row on screen in long checklists
+    let visible = checklist_height.saturating_sub(2) as usize;
+    let scroll = (app.checklist_index + 1).saturating_sub(visible) as u16;
+    frame.render_widget(Paragraph::new(checklist_lines).block(checklist_block).scroll((scroll, 0)), chunks[5]);
+
+    // Parent and subtasks
+    if subtask_height > 0 {
+        let subtask_block = Block::default()
+            .title(" Subtasks ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(app.theme.border));
+
+        let mut subtask_lines = Vec::new();
+        if let Some(parent) = parent {
+            subtask_lines.push(Line::from(vec![
+                Span::styled("Parent: ", Style::default().fg(app.theme.muted)),
+                Span::raw(parent.title.clone()),
+            ]));
+        }
+        for child in &children {
+            let status_color = app.theme.status(&app.workflow, child.status.as_str());
+            let mark = if app.workflow.is_done(&child.status) { "✓ " } else { "○ " };
+            subtask_lines.push(Line::from(vec![
+                Span::styled(mark, Style::default().fg(status_color)),
+                Span::raw(child.title.clone()),
+                Span::styled(format!("  {}", child.status), Style::default().fg(status_color)),
+            ]));
+        }
+        frame.render_widget(Paragraph::new(subtask_lines).block(subtask_block), chunks[6]);
+    }
+
+    // History timeline, newest first
+    let history_block = Block::default()
+        .title(" History ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.border));
+
+    let history_lines: Vec<Line> = if task.history.is_empty() {
+        vec![Line::from(Span::styled("No history recorded", Style::default().fg(app.theme.faint)))]
+    } else {
+        task.history
+            .iter()
+            .rev()
+            .map(|event| {
+                Line::from(vec![
+                    Span::styled(format_date(event.at), Style::default().fg(app.theme.muted)),
+                    Span::raw("  "),
+                    Span::raw(event.kind.to_string()),
+                ])
+            })
+            .collect()
+    };
+
+    frame.render_widget(Paragraph::new(history_lines).block(history_block), chunks[7]);
+
     // Footer with instructions
     let footer_text = Text::from(vec![
-        Line::from(vec![
-            Span::styled("Press ", Style::default().fg(Color::Gray)),
-            Span::styled("Esc", Style::default().fg(Color::Yellow)),
-            Span::styled(" or ", Style::default().fg(Color::Gray)),
-            Span::styled("Enter", Style::default().fg(Color::Yellow)),
-            Span::styled(" to close", Style::default().fg(Color::Gray)),
-        ]),
+        Line::from(help::hint_spans(app, KeyMode::Details, &[
+            (&[Action::Close], " close  "),
+            (&[Action::Down, Action::Up], " item  "),
+            (&[Action::ToggleItem], " check  "),
+            (&[Action::AddItem], " add  "),
+            (&[Action::RemoveItem], " remove  "),
+            (&[Action::Graph], " dependencies  "),
+            (&[Action::Timer], " timer"),
+        ])),
     ]);
     let footer_paragraph = Paragraph::new(footer_text);
-    frame.render_widget(footer_paragraph, chunks[6]);
+    frame.render_widget(footer_paragraph, chunks[9]);
 }
 
 /// Helper function to create a centered rect using up certain percentage of the available rect
-fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints(
@@ -151,5 +297,5 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
 }
 
 fn format_date(date: DateTime<Utc>) -> String {
-    date.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string()
+    utils::format_datetime(date)
 }
\ No newline at end of file
@@ -0,0 +1,81 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use strum::IntoEnumIterator;
+
+use crate::app::themes::ThemeSwitcher;
+use crate::app::App;
+use crate::config;
+use crate::models::keymap::{Action, KeyMode};
+use crate::models::TaskPriority;
+use crate::ui::help;
+use crate::ui::task_detail::centered_rect;
+
+/// Popup listing the themes; the board behind it already shows the highlighted one.
+pub fn render(frame: &mut Frame, app: &App, switcher: &ThemeSwitcher) {
+    let popup_area = centered_rect(40, 50, frame.size());
+    super::clear(frame, app, popup_area);
+
+    let block = Block::default()
+        .title(" Themes ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.accent));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Min(0),     // Themes
+            Constraint::Length(2),  // Sample badges
+            Constraint::Length(1),  // Footer
+        ])
+        .split(inner);
+
+    // The saved theme is marked; the highlighted one is what the board shows
+    let saved = config::current().theme().to_string();
+    let lines: Vec<Line> = switcher
+        .names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let current = *name == saved;
+            let mut style = Style::default().fg(if current { app.theme.accent } else { app.theme.text });
+            if i == switcher.index {
+                style = style.bg(app.theme.selection).add_modifier(Modifier::BOLD);
+            }
+            Line::from(vec![
+                Span::styled(if current { "● " } else { "  " }, Style::default().fg(app.theme.accent)),
+                Span::styled(name.as_str(), style),
+            ])
+        })
+        .collect();
+
+    // Keep the highlighted theme on screen in long lists
+    let visible = chunks[0].height as usize;
+    let scroll = (switcher.index + 1).saturating_sub(visible) as u16;
+    frame.render_widget(Paragraph::new(lines).scroll((scroll, 0)), chunks[0]);
+
+    let mut badges = Vec::new();
+    for priority in TaskPriority::iter() {
+        badges.push(Span::styled(
+            format!(" {} ", priority),
+            Style::default().bg(app.theme.priority(priority)).fg(app.theme.badge_text),
+        ));
+        badges.push(Span::raw(" "));
+    }
+    frame.render_widget(Paragraph::new(Line::from(badges)), chunks[1]);
+
+    let hints: &[(&[Action], &str)] = &[
+        (&[Action::Up, Action::Down], " preview  "),
+        (&[Action::Open], " keep  "),
+        (&[Action::Close], " cancel"),
+    ];
+    let footer = Paragraph::new(Line::from(help::hint_spans(app, KeyMode::Themes, hints)));
+    frame.render_widget(footer, chunks[2]);
+}
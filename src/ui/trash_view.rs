@@ -0,0 +1,77 @@
+use chrono::{Duration, Utc};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    Frame,
+};
+
+use crate::app::App;
+use crate::storage;
+use crate::utils;
+
+pub fn render(frame: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),     // Trash table
+            Constraint::Length(1),  // Key hints
+        ])
+        .split(area);
+
+    let retention = Duration::days(storage::trash_retention_days());
+    let now = Utc::now();
+
+    let header_cells = ["Title", "Status", "Priority", "Deleted", "Purged in"]
+        .iter()
+        .map(|h| Cell::from(*h).style(Style::default().fg(app.theme.text).add_modifier(Modifier::BOLD)));
+
+    let header = Row::new(header_cells).style(Style::default().bg(app.theme.header));
+
+    let rows = app.trash.iter().enumerate().map(|(i, entry)| {
+        let task = &entry.task;
+        let status_color = app.theme.status(&app.workflow, task.status.as_str());
+        let priority_color = app.theme.priority(task.priority);
+        // Round up so a task deleted a moment ago shows the full retention period
+        let days_left = ((entry.deleted_at + retention - now).num_hours() + 23).div_euclid(24).max(0);
+
+        let mut row_style = Style::default();
+        if app.selected_trash_index == Some(i) {
+            row_style = row_style.bg(app.theme.selection);
+        }
+
+        Row::new(vec![
+            Cell::from(Span::raw(utils::truncate_string(&task.title, 40))),
+            Cell::from(Span::styled(format!("{}", task.status), Style::default().fg(status_color))),
+            Cell::from(Span::styled(format!("{}", task.priority), Style::default().fg(priority_color))),
+            Cell::from(Span::raw(utils::format_datetime(entry.deleted_at))),
+            Cell::from(Span::raw(format!("{} days", days_left))),
+        ])
+        .style(row_style)
+    });
+
+    let widths = [
+        Constraint::Percentage(40),
+        Constraint::Length(14),
+        Constraint::Length(10),
+        Constraint::Length(18),
+        Constraint::Length(10),
+    ];
+
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().title(format!(" Trash ({}) ", app.trash.len())).borders(Borders::ALL));
+
+    frame.render_widget(table, chunks[0]);
+
+    let hints = Paragraph::new(Line::from(vec![
+        Span::styled(" r", Style::default().fg(app.theme.key)),
+        Span::styled(" restore  ", Style::default().fg(app.theme.muted)),
+        Span::styled("x", Style::default().fg(app.theme.key)),
+        Span::styled(" delete forever  ", Style::default().fg(app.theme.muted)),
+        Span::styled("X", Style::default().fg(app.theme.key)),
+        Span::styled(" empty trash", Style::default().fg(app.theme.muted)),
+    ]));
+    frame.render_widget(hints, chunks[1]);
+}
@@ -1,21 +1,29 @@
-use ratatui::style::{Color, Style};
-use std::str::FromStr;
-
-pub fn parse_color(hex: &str) -> Color {
-    if let Some(hex) = hex.strip_prefix('#') {
-        if hex.len() == 6 {
-            if let (Ok(r), Ok(g), Ok(b)) = (
-                u8::from_str_radix(&hex[0..2], 16),
-                u8::from_str_radix(&hex[2..4], 16),
-                u8::from_str_radix(&hex[4..6], 16),
-            ) {
-                return Color::Rgb(r, g, b);
-            }
-        }
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+use ratatui::style::Color;
+
+use crate::config;
+
+pub fn is_hex_color(value: &str) -> bool {
+    value
+        .strip_prefix('#')
+        .is_some_and(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// A `#RRGGBB` color or a terminal color name such as `cyan` or `light_red`;
+/// `None` for anything else.
+pub fn color_from_str(value: &str) -> Option<Color> {
+    if let Some(hex) = value.strip_prefix('#')
+        && hex.len() == 6
+        && let (Ok(r), Ok(g), Ok(b)) = (
+            u8::from_str_radix(&hex[0..2], 16),
+            u8::from_str_radix(&hex[2..4], 16),
+            u8::from_str_radix(&hex[4..6], 16),
+        )
+    {
+        return Some(Color::Rgb(r, g, b));
     }
 
-    // Fallback to foreground color if parsing fails
-    match hex {
+    let color = match value {
         "red" => Color::Red,
         "green" => Color::Green,
         "blue" => Color::Blue,
@@ -24,21 +32,90 @@ pub fn parse_color(hex: &str) -> Color {
         "cyan" => Color::Cyan,
         "gray" => Color::Gray,
         "dark_gray" => Color::DarkGray,
+        "light_red" => Color::LightRed,
+        "light_green" => Color::LightGreen,
+        "light_blue" => Color::LightBlue,
+        "light_yellow" => Color::LightYellow,
+        "light_magenta" => Color::LightMagenta,
+        "light_cyan" => Color::LightCyan,
         "white" => Color::White,
         "black" => Color::Black,
-        _ => Color::Reset,
-    }
-}
-
-pub fn styled_text(text: &str, color_hex: &str) -> (String, Style) {
-    let color = parse_color(color_hex);
-    (text.to_string(), Style::default().fg(color))
+        "default" => Color::Reset,
+        _ => return None,
+    };
+    Some(color)
 }
 
 pub fn truncate_string(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
+    if s.chars().count() <= max_len {
         s.to_string()
     } else {
-        format!("{}…", &s[..max_len.saturating_sub(1)])
+        let kept: String = s.chars().take(max_len.saturating_sub(1)).collect();
+        format!("{}…", kept)
+    }
+}
+
+/// A day in the `date_format` setting.
+pub fn format_day(day: NaiveDate) -> String {
+    day.format(config::current().date_format()).to_string()
+}
+
+/// The local day of `date` in the `date_format` setting.
+pub fn format_date(date: DateTime<Utc>) -> String {
+    format_day(date.with_timezone(&Local).date_naive())
+}
+
+/// The local day and time of `date`, the day in the `date_format` setting.
+pub fn format_datetime(date: DateTime<Utc>) -> String {
+    format!("{} {}", format_date(date), date.with_timezone(&Local).format("%H:%M"))
+}
+
+/// Parses a due date given as `YYYY-MM-DD` (end of that local day) or RFC 3339.
+pub fn parse_due_date(input: &str) -> Result<DateTime<Utc>, String> {
+    let input = input.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        let end_of_day = date.and_hms_opt(23, 59, 59).expect("valid time");
+        return Local
+            .from_local_datetime(&end_of_day)
+            .earliest()
+            .map(|local| local.with_timezone(&Utc))
+            .ok_or_else(|| format!("\"{}\" is not a valid local date", input));
+    }
+
+    DateTime::parse_from_rfc3339(input)
+        .map(|date| date.with_timezone(&Utc))
+        .map_err(|_| format!("invalid date \"{}\" (expected YYYY-MM-DD)", input))
+}
+
+/// Parses an effort estimate into minutes: `90m`, `2h`, `1.5h`, `1h30m`, or a bare number of hours.
+pub fn parse_estimate(input: &str) -> Result<u32, String> {
+    let compact: String = input.trim().to_lowercase().split_whitespace().collect();
+    let invalid = || format!("invalid estimate \"{}\" (expected e.g. 90m, 2h or 1h30m)", input.trim());
+    if compact.is_empty() {
+        return Err(invalid());
     }
-}
\ No newline at end of file
+
+    let (hours, minutes) = match compact.split_once('h') {
+        Some((hours, rest)) => (hours, rest.strip_suffix('m').unwrap_or(rest)),
+        None => match compact.strip_suffix('m') {
+            Some(minutes) => ("", minutes),
+            None => (compact.as_str(), ""),
+        },
+    };
+    let hours: f64 = if hours.is_empty() { 0.0 } else { hours.parse().map_err(|_| invalid())? };
+    let minutes: f64 = if minutes.is_empty() { 0.0 } else { minutes.parse().map_err(|_| invalid())? };
+    let total = hours * 60.0 + minutes;
+    if !total.is_finite() || total <= 0.0 || total > f64::from(u32::MAX) {
+        return Err(invalid());
+    }
+    Ok(total.round() as u32)
+}
+
+/// Formats minutes of effort in hours, the unit estimates are made in: "45m", "2h", "1h 30m", "30h".
+pub fn format_estimate(minutes: u64) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{}m", minutes),
+        (hours, 0) => format!("{}h", hours),
+        (hours, minutes) => format!("{}h {}m", hours, minutes),
+    }
+}